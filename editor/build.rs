@@ -0,0 +1,48 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+// Release builds look for the config and the game's assets next to the executable,
+// like the game does
+fn copy_dir_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dst)?;
+
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let from = entry.path();
+        let to = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursive(&from, &to)?;
+        } else {
+            fs::copy(&from, &to)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=../game/assets");
+    println!("cargo:rerun-if-changed=../engine/config");
+
+    let manifest_dir =
+        PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR missing"));
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR missing"));
+    let target_profile_dir = out_dir
+        .ancestors()
+        .nth(3)
+        .expect("Failed to locate target/<profile> dir from OUT_DIR")
+        .to_path_buf();
+
+    copy_dir_recursive(
+        &manifest_dir.join("../engine/config"),
+        &target_profile_dir.join("config"),
+    )
+    .expect("Copy ../engine/config failed");
+    copy_dir_recursive(
+        &manifest_dir.join("../game/assets"),
+        &target_profile_dir.join("assets"),
+    )
+    .expect("Copy ../game/assets failed");
+}
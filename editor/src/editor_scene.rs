@@ -0,0 +1,290 @@
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use engine::core::app::App;
+use engine::core::input::{INVALID_INPUT_EVENT_HANDLER_ID, InputEventHandlerId, InputEventType};
+use engine::core::scene::Scene;
+use engine::core::tilemap::Tile;
+use engine::core::tileset::Tileset;
+use engine::entity::Entity;
+use engine::math::Vec2;
+use engine::sdl2::mouse::MouseButton;
+
+use crate::map_editor::MapEditor;
+use crate::map_view::MapViewComponent;
+use crate::tile_palette::TilePalette;
+
+const TILESET_PATH: [&str; 2] = ["tilesets", "tileset.json"];
+// Pixels per second at full axis input, at zoom 1
+const PAN_SPEED: f32 = 300.0;
+const ZOOM_STEP: f32 = 1.25;
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 8.0;
+
+// Paints the map under the mouse.
+//
+// Left mouse paints, right mouse erases, the wheel picks the variant.
+// Arrows/WASD or a middle mouse drag pan the view, the editor_* actions do the rest.
+pub struct EditorScene {
+    m_map_path: PathBuf,
+    m_map_editor: Option<Rc<RefCell<MapEditor>>>,
+    m_tile_palette: Option<Rc<TilePalette>>,
+    m_group_index: usize,
+    m_variant: i32,
+    m_is_offgrid: bool,
+    m_pan_input: Rc<Cell<Vec2>>,
+    m_pressed_actions: Rc<RefCell<Vec<String>>>,
+    m_input_event_handler_id: InputEventHandlerId,
+    // Mouse position of the last frame while dragging the view
+    m_drag_position: Option<Vec2>,
+}
+
+impl EditorScene {
+    pub fn new(map_path: PathBuf) -> Self {
+        Self {
+            m_map_path: map_path,
+            m_map_editor: None,
+            m_tile_palette: None,
+            m_group_index: 0,
+            m_variant: 0,
+            m_is_offgrid: false,
+            m_pan_input: Rc::new(Cell::new(Vec2::zero())),
+            m_pressed_actions: Rc::new(RefCell::new(Vec::new())),
+            m_input_event_handler_id: INVALID_INPUT_EVENT_HANDLER_ID,
+            m_drag_position: None,
+        }
+    }
+
+    fn load(&mut self, app: &mut App) -> Result<(), String> {
+        let tileset = Tileset::from_file(app.get_assets().get_asset_path(TILESET_PATH)?)?;
+        let map_editor = Rc::new(RefCell::new(MapEditor::open(&self.m_map_path, tileset)?));
+        let tile_palette = Rc::new(TilePalette::load(app)?);
+
+        let mut entity = Entity::new();
+        entity.set_name("map_view");
+        entity
+            .add_component(MapViewComponent::new(
+                map_editor.clone(),
+                tile_palette.clone(),
+            ))
+            .unwrap();
+        app.spawn_scene_entity(entity);
+
+        self.m_map_editor = Some(map_editor);
+        self.m_tile_palette = Some(tile_palette);
+        Ok(())
+    }
+
+    fn add_input_event_handler(&mut self, app: &mut App) {
+        let pan_input = self.m_pan_input.clone();
+        let pressed_actions = self.m_pressed_actions.clone();
+        self.m_input_event_handler_id =
+            app.get_input()
+                .borrow_mut()
+                .add_input_event_handler(move |event| match event.ev_type {
+                    InputEventType::Pressed => {
+                        pressed_actions.borrow_mut().push(event.ev_name.to_string());
+                    }
+                    InputEventType::Axis => {
+                        // The vertical axis is positive upwards
+                        let mut pan = pan_input.get();
+                        match event.ev_name {
+                            "horizontal" => pan.x = event.axis_value,
+                            "vertical" => pan.y = -event.axis_value,
+                            _ => {}
+                        }
+                        pan_input.set(pan);
+                    }
+                    InputEventType::Released => {}
+                });
+    }
+
+    fn remove_input_event_handler(&mut self, app: &mut App) {
+        app.get_input()
+            .borrow_mut()
+            .remove_input_event_handler(self.m_input_event_handler_id);
+
+        self.m_input_event_handler_id = INVALID_INPUT_EVENT_HANDLER_ID;
+        self.m_pressed_actions.borrow_mut().clear();
+    }
+
+    fn update_view(&mut self, app: &mut App, delta_time: f32) {
+        let zoom = app.get_camera_zoom();
+        let mut camera_position =
+            app.get_camera_position() + self.m_pan_input.get() * (PAN_SPEED * delta_time / zoom);
+
+        let input = app.get_input();
+        let input = input.borrow();
+        let mouse_position = input.get_mouse_position();
+        if input.is_mouse_button_down(MouseButton::Middle) {
+            if let Some(drag_position) = self.m_drag_position {
+                camera_position -= (mouse_position - drag_position) / zoom;
+            }
+            self.m_drag_position = Some(mouse_position);
+        } else {
+            self.m_drag_position = None;
+        }
+
+        app.set_camera_position(camera_position);
+    }
+
+    fn handle_actions(&mut self, app: &mut App) {
+        let (Some(map_editor), Some(tile_palette)) = (&self.m_map_editor, &self.m_tile_palette)
+        else {
+            return;
+        };
+
+        let group_count = tile_palette.get_groups().len();
+        let mut map_editor = map_editor.borrow_mut();
+        let pressed_actions = std::mem::take(&mut *self.m_pressed_actions.borrow_mut());
+        for action in pressed_actions {
+            match action.as_str() {
+                "editor_next_type" => {
+                    self.m_group_index = (self.m_group_index + 1) % group_count;
+                    self.m_variant = 0;
+                }
+                "editor_prev_type" => {
+                    self.m_group_index = (self.m_group_index + group_count - 1) % group_count;
+                    self.m_variant = 0;
+                }
+                "editor_toggle_offgrid" => self.m_is_offgrid = !self.m_is_offgrid,
+                "editor_toggle_autotile" => {
+                    let is_autotile_enabled = !map_editor.is_autotile_enabled();
+                    map_editor.set_autotile_enabled(is_autotile_enabled);
+                    println!(
+                        "Auto-tiling {}",
+                        if is_autotile_enabled { "on" } else { "off" }
+                    );
+                }
+                "editor_autotile_map" => map_editor.autotile_map(),
+                "editor_undo" => {
+                    map_editor.undo();
+                }
+                "editor_redo" => {
+                    map_editor.redo();
+                }
+                "editor_save" => match map_editor.save() {
+                    Ok(()) => println!("Saved '{}'", map_editor.get_path().display()),
+                    Err(err) => eprintln!("Save failed: {}", err),
+                },
+                "editor_load" => match map_editor.reload() {
+                    Ok(()) => println!("Loaded '{}'", map_editor.get_path().display()),
+                    Err(err) => eprintln!("Load failed: {}", err),
+                },
+                "editor_zoom_in" => {
+                    app.set_camera_zoom((app.get_camera_zoom() * ZOOM_STEP).min(MAX_ZOOM));
+                }
+                "editor_zoom_out" => {
+                    app.set_camera_zoom((app.get_camera_zoom() / ZOOM_STEP).max(MIN_ZOOM));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn update_brush(&mut self, app: &mut App) {
+        let (Some(map_editor), Some(tile_palette)) = (&self.m_map_editor, &self.m_tile_palette)
+        else {
+            return;
+        };
+
+        let group = &tile_palette.get_groups()[self.m_group_index];
+        let variant_count = group.texture_ids.len() as i32;
+
+        let input = app.get_input();
+        let input = input.borrow();
+        let wheel = input.get_mouse_wheel();
+        if wheel != 0.0 {
+            self.m_variant = (self.m_variant - wheel.signum() as i32).rem_euclid(variant_count);
+        }
+
+        let mouse_position = app
+            .get_view_transform()
+            .inverse_transform_point(input.get_mouse_position());
+        let mut map_editor = map_editor.borrow_mut();
+        let tile_size = map_editor.get_tilemap().get_tile_size() as f32;
+        let tile_pos = (
+            (mouse_position.x / tile_size).floor() as i32,
+            (mouse_position.y / tile_size).floor() as i32,
+        );
+
+        let mut tile = Tile {
+            tile_type: group.tile_type.clone(),
+            variant: self.m_variant,
+            pos: mouse_position,
+        };
+        if !self.m_is_offgrid {
+            tile.pos = Vec2::from_xy(tile_pos.0 as f32, tile_pos.1 as f32) * tile_size;
+        }
+        map_editor.set_cursor_tile(Some(tile.clone()));
+
+        let is_painting = input.is_mouse_button_down(MouseButton::Left);
+        let is_erasing = input.is_mouse_button_down(MouseButton::Right);
+        if self.m_is_offgrid {
+            // One offgrid tile per click
+            if input.is_mouse_button_pressed(MouseButton::Left) {
+                map_editor.add_offgrid_tile(tile);
+            } else if input.is_mouse_button_pressed(MouseButton::Right)
+                && let Some(index) = find_offgrid_tile(&map_editor, tile_palette, mouse_position)
+            {
+                map_editor.remove_offgrid_tile(index);
+            }
+        } else if is_painting {
+            map_editor.paint_tile(tile_pos, tile);
+        } else if is_erasing {
+            map_editor.erase_tile(tile_pos);
+        }
+
+        if !is_painting && !is_erasing {
+            map_editor.end_edit();
+        }
+    }
+}
+
+impl Scene for EditorScene {
+    fn on_enter(&mut self, app: &mut App) {
+        if let Err(err) = self.load(app) {
+            eprintln!("Failed to open '{}': {}", self.m_map_path.display(), err);
+        }
+
+        self.add_input_event_handler(app);
+    }
+
+    fn on_exit(&mut self, app: &mut App) {
+        self.remove_input_event_handler(app);
+
+        if let Some(map_editor) = &self.m_map_editor
+            && map_editor.borrow().has_unsaved_changes()
+        {
+            eprintln!(
+                "Unsaved changes to '{}' were lost",
+                self.m_map_path.display()
+            );
+        }
+    }
+
+    fn update(&mut self, app: &mut App, delta_time: f32) {
+        self.update_view(app, delta_time);
+        self.handle_actions(app);
+        self.update_brush(app);
+    }
+}
+
+// The topmost offgrid tile under `position`
+fn find_offgrid_tile(
+    map_editor: &MapEditor,
+    tile_palette: &TilePalette,
+    position: Vec2,
+) -> Option<usize> {
+    let tilemap = map_editor.get_tilemap();
+    tilemap.get_offgrid_tiles().iter().rposition(|tile| {
+        let size = tile_palette
+            .get_texture_size(&tile.tile_type, tile.variant)
+            .unwrap_or(Vec2::one() * tilemap.get_tile_size() as f32);
+        position.x >= tile.pos.x
+            && position.y >= tile.pos.y
+            && position.x < tile.pos.x + size.x
+            && position.y < tile.pos.y + size.y
+    })
+}
@@ -0,0 +1,119 @@
+use engine::core::tilemap::{Tile, TilePos, Tilemap};
+
+// One change to the map, with enough state to apply and revert it
+#[derive(Debug, Clone, PartialEq)]
+pub enum TileChange {
+    // None is an empty cell
+    SetTile {
+        tile_pos: TilePos,
+        before: Option<Tile>,
+        after: Option<Tile>,
+    },
+    AddOffgrid {
+        index: usize,
+        tile: Tile,
+    },
+    RemoveOffgrid {
+        index: usize,
+        tile: Tile,
+    },
+}
+
+impl TileChange {
+    pub fn apply(&self, tilemap: &mut Tilemap) {
+        match self {
+            TileChange::SetTile {
+                tile_pos, after, ..
+            } => set_cell(tilemap, *tile_pos, after.clone()),
+            TileChange::AddOffgrid { index, tile } => {
+                tilemap.insert_offgrid_tile(*index, tile.clone());
+            }
+            TileChange::RemoveOffgrid { index, .. } => {
+                tilemap.remove_offgrid_tile(*index);
+            }
+        }
+    }
+
+    pub fn revert(&self, tilemap: &mut Tilemap) {
+        match self {
+            TileChange::SetTile {
+                tile_pos, before, ..
+            } => set_cell(tilemap, *tile_pos, before.clone()),
+            TileChange::AddOffgrid { index, .. } => {
+                tilemap.remove_offgrid_tile(*index);
+            }
+            TileChange::RemoveOffgrid { index, tile } => {
+                tilemap.insert_offgrid_tile(*index, tile.clone());
+            }
+        }
+    }
+}
+
+// The changes of one user action, e.g. a whole brush stroke, undone and redone together
+pub type Edit = Vec<TileChange>;
+
+pub struct EditHistory {
+    m_undo_stack: Vec<Edit>,
+    m_redo_stack: Vec<Edit>,
+}
+
+impl EditHistory {
+    pub fn new() -> Self {
+        Self {
+            m_undo_stack: Vec::new(),
+            m_redo_stack: Vec::new(),
+        }
+    }
+
+    // The edit is already applied to the map. A new edit can't be redone past.
+    pub fn push(&mut self, edit: Edit) {
+        if edit.is_empty() {
+            return;
+        }
+
+        self.m_undo_stack.push(edit);
+        self.m_redo_stack.clear();
+    }
+
+    // Returns false when there is nothing to undo
+    pub fn undo(&mut self, tilemap: &mut Tilemap) -> bool {
+        let Some(edit) = self.m_undo_stack.pop() else {
+            return false;
+        };
+
+        for change in edit.iter().rev() {
+            change.revert(tilemap);
+        }
+        self.m_redo_stack.push(edit);
+
+        true
+    }
+
+    // Returns false when there is nothing to redo
+    pub fn redo(&mut self, tilemap: &mut Tilemap) -> bool {
+        let Some(edit) = self.m_redo_stack.pop() else {
+            return false;
+        };
+
+        for change in &edit {
+            change.apply(tilemap);
+        }
+        self.m_undo_stack.push(edit);
+
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.m_undo_stack.clear();
+        self.m_redo_stack.clear();
+    }
+}
+
+fn set_cell(tilemap: &mut Tilemap, tile_pos: TilePos, tile: Option<Tile>) {
+    match tile {
+        Some(tile) => tilemap.set_tile(tile_pos, tile),
+        None => {
+            tilemap.remove_tile(tile_pos);
+        }
+    }
+}
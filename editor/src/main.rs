@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use engine::core::app::App;
+use engine::core::path_utils::get_assets_root_path;
+use engine::core::scene::SceneTransition;
+
+mod editor_scene;
+mod history;
+mod map_editor;
+mod map_view;
+mod tile_palette;
+
+const TARGET_FPS: u32 = 60;
+const VSYNC_ENABLED: bool = true;
+const WINDOW_TITLE: &str = "Rusty Platformer Editor";
+const WINDOW_WIDTH: u32 = 960;
+const WINDOW_HEIGHT: u32 = 720;
+
+// editor [map]
+// The map is a level number, e.g. `2` for assets/maps/2.json, or a path to a map file.
+fn main() {
+    let map_path = get_map_path(std::env::args().nth(1));
+
+    let mut app = App::new(
+        TARGET_FPS,
+        VSYNC_ENABLED,
+        WINDOW_TITLE,
+        WINDOW_WIDTH,
+        WINDOW_HEIGHT,
+    );
+
+    app.push_scene(
+        Box::new(editor_scene::EditorScene::new(map_path)),
+        SceneTransition::Cut,
+    );
+
+    app.run();
+}
+
+fn get_map_path(arg: Option<String>) -> PathBuf {
+    let arg = arg.unwrap_or_else(|| String::from("0"));
+    if arg.parse::<usize>().is_ok() {
+        get_assets_root_path()
+            .join("maps")
+            .join(format!("{}.json", arg))
+    } else {
+        PathBuf::from(arg)
+    }
+}
@@ -0,0 +1,325 @@
+use std::path::{Path, PathBuf};
+
+use engine::core::autotile::{Neighbor, get_autotile_variant};
+use engine::core::tilemap::{Tile, TilePos, Tilemap};
+use engine::core::tileset::Tileset;
+
+use crate::history::{Edit, EditHistory, TileChange};
+
+const DEFAULT_TILE_SIZE: i32 = 16;
+
+// The map being edited and its undo history, independent of any input or rendering.
+// Changes are collected into the current edit until end_edit(), so a brush stroke
+// is undone in one step.
+pub struct MapEditor {
+    m_tilemap: Tilemap,
+    m_path: PathBuf,
+    // Declares the autotile rules
+    m_tileset: Tileset,
+    m_history: EditHistory,
+    m_current_edit: Edit,
+    m_is_autotile_enabled: bool,
+    m_has_unsaved_changes: bool,
+    // What the brush would paint, drawn see-through under the mouse. Its pos is in pixels.
+    m_cursor_tile: Option<Tile>,
+}
+
+impl MapEditor {
+    // Starts an empty map when the file doesn't exist yet
+    pub fn open<P>(path: P, tileset: Tileset) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let tilemap = if path.exists() {
+            Tilemap::from_file(path)?
+        } else {
+            Tilemap::new(DEFAULT_TILE_SIZE)
+        };
+
+        Ok(Self {
+            m_tilemap: tilemap,
+            m_path: path.to_path_buf(),
+            m_tileset: tileset,
+            m_history: EditHistory::new(),
+            m_current_edit: Vec::new(),
+            m_is_autotile_enabled: true,
+            m_has_unsaved_changes: false,
+            m_cursor_tile: None,
+        })
+    }
+
+    pub fn get_tilemap(&self) -> &Tilemap {
+        &self.m_tilemap
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.m_path
+    }
+
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.m_has_unsaved_changes
+    }
+
+    pub fn is_autotile_enabled(&self) -> bool {
+        self.m_is_autotile_enabled
+    }
+
+    // Fixes up the painted tile and its neighbors as they are painted
+    pub fn set_autotile_enabled(&mut self, is_autotile_enabled: bool) {
+        self.m_is_autotile_enabled = is_autotile_enabled;
+    }
+
+    pub fn get_cursor_tile(&self) -> Option<&Tile> {
+        self.m_cursor_tile.as_ref()
+    }
+
+    pub fn set_cursor_tile(&mut self, cursor_tile: Option<Tile>) {
+        self.m_cursor_tile = cursor_tile;
+    }
+
+    pub fn paint_tile(&mut self, tile_pos: TilePos, tile: Tile) {
+        let is_same_type = self
+            .m_tilemap
+            .get_tile(tile_pos)
+            .is_some_and(|current| current.tile_type == tile.tile_type);
+
+        // Dragging over auto-tiled tiles of the same type would undo their fix-ups
+        if !(self.m_is_autotile_enabled && is_same_type) {
+            self.set_cell(tile_pos, Some(tile));
+        }
+        self.autotile_around(tile_pos);
+    }
+
+    pub fn erase_tile(&mut self, tile_pos: TilePos) {
+        self.set_cell(tile_pos, None);
+        self.autotile_around(tile_pos);
+    }
+
+    pub fn add_offgrid_tile(&mut self, tile: Tile) {
+        let index = self.m_tilemap.get_offgrid_tiles().len();
+        self.record(TileChange::AddOffgrid { index, tile });
+    }
+
+    pub fn remove_offgrid_tile(&mut self, index: usize) {
+        let Some(tile) = self.m_tilemap.get_offgrid_tiles().get(index).cloned() else {
+            return;
+        };
+
+        self.record(TileChange::RemoveOffgrid { index, tile });
+    }
+
+    // Recomputes the variant of every auto-tiled tile, as one edit
+    pub fn autotile_map(&mut self) {
+        self.end_edit();
+
+        let tile_positions: Vec<TilePos> = self
+            .m_tilemap
+            .tile_iter()
+            .map(|(tile_pos, _)| tile_pos)
+            .collect();
+        for tile_pos in tile_positions {
+            self.autotile(tile_pos);
+        }
+
+        self.end_edit();
+    }
+
+    // Closes the current edit, e.g. when the mouse button is released
+    pub fn end_edit(&mut self) {
+        let edit = std::mem::take(&mut self.m_current_edit);
+        self.m_history.push(edit);
+    }
+
+    pub fn undo(&mut self) -> bool {
+        self.end_edit();
+        let has_undone = self.m_history.undo(&mut self.m_tilemap);
+        self.m_has_unsaved_changes |= has_undone;
+        has_undone
+    }
+
+    pub fn redo(&mut self) -> bool {
+        self.end_edit();
+        let has_redone = self.m_history.redo(&mut self.m_tilemap);
+        self.m_has_unsaved_changes |= has_redone;
+        has_redone
+    }
+
+    pub fn save(&mut self) -> Result<(), String> {
+        self.end_edit();
+        self.m_tilemap.to_file(&self.m_path)?;
+        self.m_has_unsaved_changes = false;
+        Ok(())
+    }
+
+    // Drops the history, unsaved changes are lost
+    pub fn reload(&mut self) -> Result<(), String> {
+        self.m_tilemap = Tilemap::from_file(&self.m_path)?;
+        self.m_history.clear();
+        self.m_current_edit.clear();
+        self.m_has_unsaved_changes = false;
+        Ok(())
+    }
+
+    fn autotile_around(&mut self, tile_pos: TilePos) {
+        if !self.m_is_autotile_enabled {
+            return;
+        }
+
+        self.autotile(tile_pos);
+        for neighbor in Neighbor::ALL {
+            let offset = neighbor.get_offset();
+            self.autotile((tile_pos.0 + offset.0, tile_pos.1 + offset.1));
+        }
+    }
+
+    fn autotile(&mut self, tile_pos: TilePos) {
+        let Some(variant) = get_autotile_variant(&self.m_tileset, &self.m_tilemap, tile_pos) else {
+            return;
+        };
+
+        let Some(tile) = self.m_tilemap.get_tile(tile_pos) else {
+            return;
+        };
+
+        if tile.variant != variant {
+            let mut tile = tile.clone();
+            tile.variant = variant;
+            self.set_cell(tile_pos, Some(tile));
+        }
+    }
+
+    // Records nothing when the cell already holds the tile
+    fn set_cell(&mut self, tile_pos: TilePos, after: Option<Tile>) {
+        let before = self.m_tilemap.get_tile(tile_pos).cloned();
+        if before.is_none() && after.is_none() {
+            return;
+        }
+
+        if let (Some(before), Some(after)) = (&before, &after)
+            && before.tile_type == after.tile_type
+            && before.variant == after.variant
+        {
+            return;
+        }
+
+        self.record(TileChange::SetTile {
+            tile_pos,
+            before,
+            after,
+        });
+    }
+
+    fn record(&mut self, change: TileChange) {
+        change.apply(&mut self.m_tilemap);
+        self.m_current_edit.push(change);
+        self.m_has_unsaved_changes = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use engine::math::Vec2;
+    use engine::test_fixtures::{get_map_path, load_tileset};
+
+    use super::*;
+
+    fn get_temp_path(file_name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("editor_{}_{}", std::process::id(), file_name))
+    }
+
+    fn grass(variant: i32) -> Tile {
+        Tile {
+            tile_type: String::from("grass"),
+            variant,
+            pos: Vec2::zero(),
+        }
+    }
+
+    #[test]
+    fn shipped_maps_round_trip_unchanged() {
+        for level_index in 0..3 {
+            let map_path = get_map_path(level_index);
+            let original = std::fs::read(&map_path).unwrap();
+
+            let mut map_editor = MapEditor::open(&map_path, load_tileset()).unwrap();
+            map_editor.m_path = get_temp_path(&format!("{}.json", level_index));
+            map_editor.save().unwrap();
+            let saved = std::fs::read(map_editor.get_path()).unwrap();
+            std::fs::remove_file(map_editor.get_path()).unwrap();
+
+            assert!(original == saved, "maps/{}.json changed", level_index);
+        }
+    }
+
+    #[test]
+    fn undo_and_redo_a_stroke() {
+        let mut map_editor =
+            MapEditor::open(get_temp_path("missing.json"), load_tileset()).unwrap();
+        map_editor.set_autotile_enabled(false);
+
+        map_editor.paint_tile((0, 0), grass(1));
+        map_editor.paint_tile((1, 0), grass(1));
+        map_editor.end_edit();
+        map_editor.add_offgrid_tile(grass(0));
+        map_editor.end_edit();
+        assert_eq!(map_editor.get_tilemap().get_tile_count(), 2);
+        assert_eq!(map_editor.get_tilemap().get_offgrid_tiles().len(), 1);
+
+        assert!(map_editor.undo());
+        assert!(map_editor.get_tilemap().get_offgrid_tiles().is_empty());
+        assert!(map_editor.undo());
+        assert_eq!(map_editor.get_tilemap().get_tile_count(), 0);
+        assert!(!map_editor.undo());
+
+        assert!(map_editor.redo());
+        assert_eq!(map_editor.get_tilemap().get_tile_count(), 2);
+        assert_eq!(
+            map_editor.get_tilemap().get_tile((1, 0)),
+            Some(&{
+                let mut tile = grass(1);
+                tile.pos = Vec2::from_xy(1.0, 0.0);
+                tile
+            })
+        );
+
+        // A new edit drops the undone ones
+        map_editor.erase_tile((0, 0));
+        map_editor.end_edit();
+        assert!(!map_editor.redo());
+        assert_eq!(map_editor.get_tilemap().get_tile_count(), 1);
+    }
+
+    #[test]
+    fn painting_autotiles_the_neighbors() {
+        let mut map_editor =
+            MapEditor::open(get_temp_path("missing.json"), load_tileset()).unwrap();
+
+        map_editor.paint_tile((0, 0), grass(0));
+        map_editor.paint_tile((1, 0), grass(0));
+        map_editor.paint_tile((2, 0), grass(0));
+        map_editor.end_edit();
+
+        let tilemap = map_editor.get_tilemap();
+        let variants: Vec<i32> = (0..3)
+            .map(|x| tilemap.get_tile((x, 0)).unwrap().variant)
+            .collect();
+        // Lone tiles and the ends of a row have no rule and keep the painted variant
+        assert_eq!(variants, vec![0, 0, 0]);
+
+        map_editor.paint_tile((0, 1), grass(0));
+        map_editor.paint_tile((1, 1), grass(0));
+        map_editor.paint_tile((2, 1), grass(0));
+        map_editor.end_edit();
+
+        let tilemap = map_editor.get_tilemap();
+        assert_eq!(tilemap.get_tile((0, 0)).unwrap().variant, 0);
+        assert_eq!(tilemap.get_tile((1, 0)).unwrap().variant, 1);
+        assert_eq!(tilemap.get_tile((2, 0)).unwrap().variant, 2);
+        assert_eq!(tilemap.get_tile((0, 1)).unwrap().variant, 6);
+        assert_eq!(tilemap.get_tile((1, 1)).unwrap().variant, 5);
+        assert_eq!(tilemap.get_tile((2, 1)).unwrap().variant, 4);
+    }
+}
@@ -0,0 +1,70 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use engine::components::{Component, component};
+use engine::core::render::{RenderData, RenderQueue};
+use engine::core::tilemap::Tile;
+use engine::math::Vec2;
+
+use crate::map_editor::MapEditor;
+use crate::tile_palette::TilePalette;
+
+// How see-through the tile under the mouse is drawn
+const CURSOR_TILE_ALPHA: f32 = 0.5;
+
+// Draws the edited map: offgrid tiles behind the grid, then the brush under the mouse
+#[component(priority = RENDER)]
+pub struct MapViewComponent {
+    m_map_editor: Rc<RefCell<MapEditor>>,
+    m_tile_palette: Rc<TilePalette>,
+}
+
+impl Component for MapViewComponent {
+    fn render_tick(&mut self, _delta_time: f32, render_queue: &mut RenderQueue) {
+        let map_editor = self.m_map_editor.borrow();
+        let tilemap = map_editor.get_tilemap();
+        let tile_size = tilemap.get_tile_size() as f32;
+
+        for tile in tilemap.get_offgrid_tiles() {
+            self.enqueue_tile(render_queue, tile, tile.pos, 1.0);
+        }
+
+        for (_, tile) in tilemap.tile_iter() {
+            self.enqueue_tile(render_queue, tile, tile.pos * tile_size, 1.0);
+        }
+
+        if let Some(tile) = map_editor.get_cursor_tile() {
+            self.enqueue_tile(render_queue, tile, tile.pos, CURSOR_TILE_ALPHA);
+        }
+    }
+}
+
+#[component]
+impl MapViewComponent {
+    pub fn new(map_editor: Rc<RefCell<MapEditor>>, tile_palette: Rc<TilePalette>) -> Self {
+        Self {
+            m_map_editor: map_editor,
+            m_tile_palette: tile_palette,
+        }
+    }
+
+    // Tiles without an image, e.g. types that were removed from images/tiles, are skipped
+    fn enqueue_tile(
+        &self,
+        render_queue: &mut RenderQueue,
+        tile: &Tile,
+        position: Vec2,
+        alpha: f32,
+    ) {
+        let Some(texture_id) = self
+            .m_tile_palette
+            .get_texture_id(&tile.tile_type, tile.variant)
+        else {
+            return;
+        };
+
+        render_queue.enqueue(
+            RenderData::new(texture_id, position, position, Vec2::one()).with_alpha(alpha),
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use engine::core::app::App;
+use engine::core::assets::TextureId;
+use engine::math::Vec2;
+
+const TILE_IMAGES_PATH: [&str; 2] = ["images", "tiles"];
+
+// The images of one tile type, indexed by variant
+pub struct TileGroup {
+    pub tile_type: String,
+    pub texture_ids: Vec<TextureId>,
+    pub texture_sizes: Vec<Vec2>,
+}
+
+// Every tile type in images/tiles/<type>/<variant>.png, sorted by name
+pub struct TilePalette {
+    m_groups: Vec<TileGroup>,
+}
+
+impl TilePalette {
+    // The textures belong to the active scene
+    pub fn load(app: &mut App) -> Result<Self, String> {
+        let tiles_path = app.get_assets().get_asset_path(TILE_IMAGES_PATH)?;
+        let mut tile_types: Vec<String> = std::fs::read_dir(&tiles_path)
+            .map_err(|err| format!("Failed to read '{}': {}", tiles_path.display(), err))?
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        tile_types.sort();
+
+        let mut groups = Vec::new();
+        for tile_type in tile_types {
+            let mut group = TileGroup {
+                tile_type,
+                texture_ids: Vec::new(),
+                texture_sizes: Vec::new(),
+            };
+
+            // Variants are numbered from 0 without gaps
+            let tile_type_path = tiles_path.join(&group.tile_type);
+            loop {
+                let image_path = tile_type_path.join(format!("{}.png", group.texture_ids.len()));
+                if !image_path.exists() {
+                    break;
+                }
+
+                let texture_id = app.load_scene_texture(&image_path)?;
+                let texture_size = app
+                    .get_assets()
+                    .get_texture(texture_id)
+                    .map(|texture| {
+                        let query = texture.query();
+                        Vec2::from_xy(query.width as f32, query.height as f32)
+                    })
+                    .unwrap_or(Vec2::zero());
+                group.texture_ids.push(texture_id);
+                group.texture_sizes.push(texture_size);
+            }
+
+            if !group.texture_ids.is_empty() {
+                groups.push(group);
+            }
+        }
+
+        if groups.is_empty() {
+            return Err(format!("No tile images in '{}'", tiles_path.display()));
+        }
+
+        Ok(Self { m_groups: groups })
+    }
+
+    pub fn get_groups(&self) -> &[TileGroup] {
+        &self.m_groups
+    }
+
+    pub fn get_group(&self, tile_type: &str) -> Option<&TileGroup> {
+        self.m_groups
+            .iter()
+            .find(|group| group.tile_type == tile_type)
+    }
+
+    pub fn get_texture_id(&self, tile_type: &str, variant: i32) -> Option<TextureId> {
+        let group = self.get_group(tile_type)?;
+        usize::try_from(variant)
+            .ok()
+            .and_then(|variant| group.texture_ids.get(variant))
+            .copied()
+    }
+
+    pub fn get_texture_size(&self, tile_type: &str, variant: i32) -> Option<Vec2> {
+        let group = self.get_group(tile_type)?;
+        usize::try_from(variant)
+            .ok()
+            .and_then(|variant| group.texture_sizes.get(variant))
+            .copied()
+    }
+}
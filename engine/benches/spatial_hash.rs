@@ -0,0 +1,106 @@
+// Compares the spatial hash broad phase with naive pairwise tests.
+// Run with `cargo bench -p engine --bench spatial_hash`.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use engine::math::{Rect, Vec2};
+use engine::physics::SpatialHash;
+
+const WORLD_SIZE: f32 = 2048.0;
+const COLLIDER_SIZE: f32 = 16.0;
+const CELL_SIZE: f32 = 32.0;
+const COUNTS: [usize; 4] = [100, 500, 1000, 4000];
+
+// Deterministic positions, so runs are comparable
+fn make_rects(count: usize) -> Vec<Rect> {
+    let mut state: u32 = 0x9e37_79b9;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        (state as f32 / u32::MAX as f32) * WORLD_SIZE
+    };
+
+    (0..count)
+        .map(|_| Rect::from_pos_size(Vec2::from_xy(next(), next()), Vec2::one() * COLLIDER_SIZE))
+        .collect()
+}
+
+fn naive_pairs(rects: &[Rect]) -> usize {
+    let mut count = 0;
+    for (index, a) in rects.iter().enumerate() {
+        for b in &rects[index + 1..] {
+            if a.intersects(b) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+fn spatial_hash_pairs(spatial_hash: &SpatialHash<usize>, rects: &[Rect]) -> usize {
+    spatial_hash
+        .query_pairs()
+        .into_iter()
+        .filter(|(a, b)| rects[*a].intersects(&rects[*b]))
+        .count()
+}
+
+fn bench_pairs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("overlapping_pairs");
+
+    for count in COUNTS {
+        let rects = make_rects(count);
+
+        let mut spatial_hash = SpatialHash::new(CELL_SIZE);
+        for (index, rect) in rects.iter().enumerate() {
+            spatial_hash.insert(index, *rect);
+        }
+
+        assert_eq!(
+            naive_pairs(&rects),
+            spatial_hash_pairs(&spatial_hash, &rects)
+        );
+
+        group.bench_with_input(BenchmarkId::new("naive", count), &rects, |b, rects| {
+            b.iter(|| naive_pairs(black_box(rects)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("spatial_hash", count),
+            &rects,
+            |b, rects| b.iter(|| spatial_hash_pairs(black_box(&spatial_hash), black_box(rects))),
+        );
+    }
+
+    group.finish();
+}
+
+// Moving every collider a little each frame, as TransformComponent::set_position does
+fn bench_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_all");
+
+    for count in COUNTS {
+        let rects = make_rects(count);
+
+        let mut spatial_hash = SpatialHash::new(CELL_SIZE);
+        for (index, rect) in rects.iter().enumerate() {
+            spatial_hash.insert(index, *rect);
+        }
+
+        let mut offset = 0.0;
+        group.bench_function(BenchmarkId::new("spatial_hash", count), |b| {
+            b.iter(|| {
+                offset = (offset + 1.0) % CELL_SIZE;
+                for (index, rect) in rects.iter().enumerate() {
+                    spatial_hash.update(index, rect.translated(Vec2::from_xy(offset, 0.0)));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_pairs, bench_update);
+criterion_main!(benches);
@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Component, ComponentBase, ImageComponent, component};
+use crate::core::assets::TextureId;
+
+// Range of frames shown one after another
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimationClip {
+    pub name: String,
+    pub first_frame: usize,
+    pub frame_count: usize,
+    pub frame_duration: f32,
+    // Non-looping clips hold their last frame
+    pub looping: bool,
+}
+
+// Flipbook animation, swaps the texture of the ImageComponent.
+// The frames of all clips are kept in one list, so they can be saved as texture paths.
+#[component(priority = ANIMATION, requires(ImageComponent), serialize)]
+pub struct AnimationComponent {
+    #[save(textures)]
+    m_frames: Vec<TextureId>,
+    #[save]
+    m_clips: Vec<AnimationClip>,
+    #[save]
+    m_current_clip: Option<usize>,
+    #[save]
+    m_frame: usize,
+    #[save]
+    m_frame_time: f32,
+    #[save]
+    #[reflect]
+    m_speed: f32,
+}
+
+impl Component for AnimationComponent {
+    fn enter_play(&mut self) {
+        self.apply_frame();
+    }
+
+    fn tick(&mut self, delta_time: f32) {
+        let Some(clip) = self.m_current_clip.map(|index| &self.m_clips[index]) else {
+            return;
+        };
+
+        if clip.frame_duration <= 0.0 || clip.frame_count == 0 {
+            return;
+        }
+
+        self.m_frame_time += delta_time * self.m_speed;
+        let mut frame = self.m_frame;
+        while self.m_frame_time >= clip.frame_duration {
+            self.m_frame_time -= clip.frame_duration;
+            if frame + 1 < clip.frame_count {
+                frame += 1;
+            } else if clip.looping {
+                frame = 0;
+            } else {
+                self.m_frame_time = 0.0;
+                break;
+            }
+        }
+
+        if frame != self.m_frame {
+            self.m_frame = frame;
+            self.apply_frame();
+        }
+    }
+}
+
+#[component]
+impl AnimationComponent {
+    pub fn new() -> Self {
+        Self {
+            m_frames: Vec::new(),
+            m_clips: Vec::new(),
+            m_current_clip: None,
+            m_frame: 0,
+            m_frame_time: 0.0,
+            m_speed: 1.0,
+        }
+    }
+
+    // The first clip added starts playing
+    pub fn with_clip(
+        mut self,
+        name: &str,
+        frames: Vec<TextureId>,
+        frame_duration: f32,
+        looping: bool,
+    ) -> Self {
+        self.add_clip(name, frames, frame_duration, looping);
+        self
+    }
+
+    // Replaces the clip with the same name
+    pub fn add_clip(
+        &mut self,
+        name: &str,
+        frames: Vec<TextureId>,
+        frame_duration: f32,
+        looping: bool,
+    ) {
+        let clip = AnimationClip {
+            name: name.to_string(),
+            first_frame: self.m_frames.len(),
+            frame_count: frames.len(),
+            frame_duration,
+            looping,
+        };
+        self.m_frames.extend(frames);
+
+        match self.find_clip(name) {
+            Some(index) => self.m_clips[index] = clip,
+            None => self.m_clips.push(clip),
+        }
+
+        if self.m_current_clip.is_none() {
+            self.m_current_clip = Some(self.m_clips.len() - 1);
+        }
+    }
+
+    // Keeps going if the clip is already playing
+    pub fn play(&mut self, name: &str) {
+        let index = self.find_clip(name);
+        if index.is_none() {
+            eprintln!("Animation clip '{}' not found", name);
+            return;
+        }
+
+        if index != self.m_current_clip {
+            self.m_current_clip = index;
+            self.restart();
+        }
+    }
+
+    pub fn restart(&mut self) {
+        self.m_frame = 0;
+        self.m_frame_time = 0.0;
+        self.apply_frame();
+    }
+
+    pub fn get_current_clip(&self) -> Option<&str> {
+        self.m_current_clip
+            .map(|index| self.m_clips[index].name.as_str())
+    }
+
+    // A non-looping clip reached its last frame
+    pub fn is_finished(&self) -> bool {
+        self.m_current_clip.is_some_and(|index| {
+            let clip = &self.m_clips[index];
+            !clip.looping && self.m_frame + 1 >= clip.frame_count
+        })
+    }
+
+    pub fn get_speed(&self) -> f32 {
+        self.m_speed
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.m_speed = speed;
+    }
+
+    fn find_clip(&self, name: &str) -> Option<usize> {
+        self.m_clips.iter().position(|clip| clip.name == name)
+    }
+
+    fn apply_frame(&mut self) {
+        if !self.has_entity() {
+            return;
+        }
+
+        let Some(texture_id) = self
+            .m_current_clip
+            .map(|index| &self.m_clips[index])
+            .filter(|clip| self.m_frame < clip.frame_count)
+            .map(|clip| self.m_frames[clip.first_frame + self.m_frame])
+        else {
+            return;
+        };
+
+        if let Some(image) = self.get_entity_mut().get_component_mut::<ImageComponent>() {
+            image.set_texture_id(texture_id);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use std::rc::Rc;
+
+use crate::components::{
+    ColliderComponent, Component, ComponentBase, TransformComponent, component,
+};
+use crate::core::tileset::TileShape;
+use crate::math::{Rect, Vec2};
+use crate::physics::{HitTarget, LayerMask, PhysicsHit, PhysicsWorld, QueryFilter, TILE_LAYER};
+
+// Moves stop this far in front of the surface they hit
+const SKIN: f32 = 0.01;
+const MAX_SLIDES: usize = 4;
+// Surfaces whose normal points up more than this are ground, about 60° at most
+const GROUND_NORMAL_Y: f32 = 0.5;
+// One-way tiles are ignored this long after drop_through()
+const DROP_THROUGH_TIME: f32 = 0.25;
+
+// Kinematic movement for characters: slides along walls, walks up and down slopes,
+// steps over small ledges and lands on one-way platforms.
+// Only moves when asked to with move_by(), gravity and jumping are up to the caller.
+#[component(
+    requires(TransformComponent, ColliderComponent),
+    serialize,
+    manual_register
+)]
+pub struct CharacterControllerComponent {
+    m_physics_world: Rc<PhysicsWorld>,
+    #[save]
+    m_solid_layers: LayerMask,
+    // Keeps grounded characters on the ground when walking down slopes and off ledges
+    #[save]
+    #[reflect]
+    m_snap_distance: f32,
+    #[save]
+    #[reflect]
+    m_step_height: f32,
+    m_drop_through_time: f32,
+    m_is_grounded: bool,
+    m_is_on_one_way: bool,
+    m_is_on_ceiling: bool,
+    m_is_on_wall: bool,
+    m_ground_normal: Vec2,
+}
+
+impl Component for CharacterControllerComponent {
+    fn exit_play(&mut self) {
+        self.m_drop_through_time = 0.0;
+        self.clear_contacts();
+        self.m_is_grounded = false;
+    }
+
+    fn tick(&mut self, delta_time: f32) {
+        self.m_drop_through_time = (self.m_drop_through_time - delta_time).max(0.0);
+    }
+}
+
+#[component]
+impl CharacterControllerComponent {
+    pub fn new(physics_world: Rc<PhysicsWorld>) -> Self {
+        Self {
+            m_physics_world: physics_world,
+            m_solid_layers: TILE_LAYER,
+            m_snap_distance: 8.0,
+            m_step_height: 6.0,
+            m_drop_through_time: 0.0,
+            m_is_grounded: false,
+            m_is_on_one_way: false,
+            m_is_on_ceiling: false,
+            m_is_on_wall: false,
+            m_ground_normal: Vec2::zero(),
+        }
+    }
+
+    // Layers that block the character, only the tiles by default
+    pub fn with_solid_layers(mut self, solid_layers: LayerMask) -> Self {
+        self.m_solid_layers = solid_layers;
+        self
+    }
+
+    pub fn with_snap_distance(mut self, snap_distance: f32) -> Self {
+        self.m_snap_distance = snap_distance;
+        self
+    }
+
+    pub fn with_step_height(mut self, step_height: f32) -> Self {
+        self.m_step_height = step_height;
+        self
+    }
+
+    pub fn is_grounded(&self) -> bool {
+        self.m_is_grounded
+    }
+
+    // Standing on a one-way platform, i.e. drop_through() would do something
+    pub fn is_on_one_way(&self) -> bool {
+        self.m_is_on_one_way
+    }
+
+    pub fn is_on_ceiling(&self) -> bool {
+        self.m_is_on_ceiling
+    }
+
+    pub fn is_on_wall(&self) -> bool {
+        self.m_is_on_wall
+    }
+
+    // Zero while not grounded
+    pub fn get_ground_normal(&self) -> Vec2 {
+        self.m_ground_normal
+    }
+
+    // Falls through the one-way platform below
+    pub fn drop_through(&mut self) {
+        if self.m_is_on_one_way {
+            self.m_drop_through_time = DROP_THROUGH_TIME;
+            self.m_is_grounded = false;
+            self.m_is_on_one_way = false;
+            self.m_ground_normal = Vec2::zero();
+        }
+    }
+
+    // Moves the entity by motion as far as the solid layers allow, sliding along what it hits.
+    // Returns how far it actually moved.
+    pub fn move_by(&mut self, motion: Vec2) -> Vec2 {
+        let Some(start) = self
+            .get_entity()
+            .get_component::<ColliderComponent>()
+            .map(|collider| collider.get_bounds())
+        else {
+            return Vec2::zero();
+        };
+
+        let filter = self.get_filter();
+        let was_grounded = self.m_is_grounded;
+        self.clear_contacts();
+        self.m_is_grounded = false;
+
+        let mut rect = self.push_out_of_slopes(start, filter);
+        let mut remaining = motion;
+        let mut snap_distance = self.m_snap_distance;
+
+        for _ in 0..MAX_SLIDES {
+            if remaining.len_sqr() < SKIN * SKIN {
+                break;
+            }
+
+            let Some(hit) = self.m_physics_world.shape_cast(rect, remaining, filter) else {
+                rect = rect.translated(remaining);
+                break;
+            };
+
+            let remaining_len = remaining.len();
+            let direction = remaining / remaining_len;
+            rect = rect.translated(direction * (hit.distance - SKIN).max(0.0));
+            remaining = direction * (remaining_len - hit.distance);
+
+            if hit.normal.y < -GROUND_NORMAL_Y {
+                self.set_ground(&hit);
+
+                // Keep the horizontal speed and follow the ground instead of falling into it
+                let tangent = Vec2::from_xy(-hit.normal.y, hit.normal.x);
+                remaining = tangent * (remaining.x / tangent.x);
+            } else if hit.normal.y > GROUND_NORMAL_Y {
+                self.m_is_on_ceiling = true;
+                remaining -= hit.normal * Vec2::dot(remaining, hit.normal);
+            } else {
+                if (was_grounded || self.m_is_grounded)
+                    && self.try_step_up(&mut rect, remaining, filter)
+                {
+                    snap_distance += self.m_step_height;
+                    continue;
+                }
+
+                self.m_is_on_wall = true;
+                remaining -= hit.normal * Vec2::dot(remaining, hit.normal);
+            }
+        }
+
+        if was_grounded && !self.m_is_grounded && motion.y >= 0.0 {
+            self.snap_to_ground(&mut rect, snap_distance, filter);
+        }
+
+        let moved = rect.pos - start.pos;
+        if let Some(transform) = self
+            .get_entity_mut()
+            .get_component_mut::<TransformComponent>()
+        {
+            let position = transform.get_position();
+            transform.set_position(position + moved);
+        }
+
+        moved
+    }
+
+    fn get_filter(&self) -> QueryFilter {
+        let filter = QueryFilter::new(self.m_solid_layers).excluding(self.get_entity().get_id());
+        if self.m_drop_through_time > 0.0 {
+            filter.without_one_way()
+        } else {
+            filter
+        }
+    }
+
+    fn clear_contacts(&mut self) {
+        self.m_is_on_one_way = false;
+        self.m_is_on_ceiling = false;
+        self.m_is_on_wall = false;
+        self.m_ground_normal = Vec2::zero();
+    }
+
+    fn set_ground(&mut self, hit: &PhysicsHit) {
+        self.m_is_grounded = true;
+        self.m_ground_normal = hit.normal;
+        self.m_is_on_one_way = match hit.target {
+            HitTarget::Tile(tile_pos) => {
+                self.m_physics_world.get_tile_shape(tile_pos) == TileShape::OneWay
+            }
+            HitTarget::Entity(_) => false,
+        };
+    }
+
+    // A slope can end up above the bottom of the rect when the character was moved without
+    // the controller, or the ground under it changed
+    fn push_out_of_slopes(&self, rect: Rect, filter: QueryFilter) -> Rect {
+        let depth = self
+            .m_physics_world
+            .overlap_aabb(rect, filter)
+            .iter()
+            .filter_map(|hit| match hit.target {
+                HitTarget::Tile(tile_pos) => matches!(
+                    self.m_physics_world.get_tile_shape(tile_pos),
+                    TileShape::Slope { .. }
+                )
+                .then_some(hit.distance),
+                HitTarget::Entity(_) => None,
+            })
+            .fold(0.0, f32::max);
+
+        if depth > 0.0 {
+            rect.translated(Vec2::up() * (depth + SKIN))
+        } else {
+            rect
+        }
+    }
+
+    // Lifts the rect onto a ledge up to the step height when there is room above it
+    fn try_step_up(&self, rect: &mut Rect, remaining: Vec2, filter: QueryFilter) -> bool {
+        if remaining.x == 0.0 || self.m_step_height <= 0.0 {
+            return false;
+        }
+
+        let step = Vec2::up() * self.m_step_height;
+        let is_blocked_above = self
+            .m_physics_world
+            .shape_cast(*rect, step, filter)
+            .is_some();
+        let stepped = rect.translated(step + Vec2::from_xy(remaining.x, 0.0));
+        if is_blocked_above
+            || !self
+                .m_physics_world
+                .overlap_aabb(stepped, filter)
+                .is_empty()
+        {
+            return false;
+        }
+
+        *rect = rect.translated(step);
+        true
+    }
+
+    fn snap_to_ground(&mut self, rect: &mut Rect, snap_distance: f32, filter: QueryFilter) {
+        let motion = Vec2::down() * snap_distance;
+        if let Some(hit) = self.m_physics_world.shape_cast(*rect, motion, filter)
+            && hit.normal.y < -GROUND_NORMAL_Y
+        {
+            *rect = rect.translated(Vec2::down() * (hit.distance - SKIN).max(0.0));
+            self.set_ground(&hit);
+        }
+    }
+}
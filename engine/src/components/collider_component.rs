@@ -0,0 +1,165 @@
+use std::rc::Rc;
+
+use crate::components::{Component, ComponentBase, TransformComponent, component};
+use crate::math::{Rect, Vec2};
+use crate::physics::{
+    ColliderHandle, ColliderProxy, DEFAULT_LAYER, INVALID_COLLIDER_HANDLE, LayerMask, PhysicsWorld,
+};
+
+// Axis-aligned box collider. The box starts at the transform position plus the offset.
+// Triggers report overlaps through Component::on_trigger_enter/exit() instead of colliding.
+#[component(priority = COLLIDER, requires(TransformComponent), serialize, manual_register)]
+pub struct ColliderComponent {
+    m_physics_world: Rc<PhysicsWorld>,
+    m_handle: ColliderHandle,
+    #[save]
+    #[reflect]
+    m_size: Vec2,
+    #[save]
+    #[reflect]
+    m_offset: Vec2,
+    #[save]
+    m_layer: LayerMask,
+    #[save]
+    m_mask: LayerMask,
+    #[save]
+    #[reflect]
+    m_is_trigger: bool,
+}
+
+impl Component for ColliderComponent {
+    fn enter_play(&mut self) {
+        self.m_handle = self.m_physics_world.add_collider(self.get_proxy());
+    }
+
+    fn exit_play(&mut self) {
+        self.m_physics_world.remove_collider(self.m_handle);
+        self.m_handle = INVALID_COLLIDER_HANDLE;
+    }
+
+    // TransformComponent::set_position() keeps the collider in sync while the entity moves.
+    // This picks up positions written without it, e.g. by the inspector or a loaded save.
+    fn physics_tick(&mut self, _fixed_delta_time: f32) {
+        self.sync();
+    }
+}
+
+#[component]
+impl ColliderComponent {
+    pub fn new(physics_world: Rc<PhysicsWorld>, size: Vec2) -> Self {
+        let mask = physics_world
+            .get_collision_layers()
+            .get_collision_mask(DEFAULT_LAYER);
+
+        Self {
+            m_physics_world: physics_world,
+            m_handle: INVALID_COLLIDER_HANDLE,
+            m_size: size,
+            m_offset: Vec2::zero(),
+            m_layer: DEFAULT_LAYER,
+            m_mask: mask,
+            m_is_trigger: false,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.m_offset = offset;
+        self
+    }
+
+    // Also resets the mask to the layers configured to collide with this layer
+    pub fn with_layer(mut self, layer: LayerMask) -> Self {
+        self.m_layer = layer;
+        self.m_mask = self
+            .m_physics_world
+            .get_collision_layers()
+            .get_collision_mask(layer);
+        self
+    }
+
+    pub fn with_mask(mut self, mask: LayerMask) -> Self {
+        self.m_mask = mask;
+        self
+    }
+
+    pub fn as_trigger(mut self) -> Self {
+        self.m_is_trigger = true;
+        self
+    }
+
+    pub fn get_size(&self) -> Vec2 {
+        self.m_size
+    }
+
+    pub fn set_size(&mut self, size: Vec2) {
+        self.m_size = size;
+        self.sync();
+    }
+
+    pub fn get_offset(&self) -> Vec2 {
+        self.m_offset
+    }
+
+    pub fn set_offset(&mut self, offset: Vec2) {
+        self.m_offset = offset;
+        self.sync();
+    }
+
+    pub fn get_layer(&self) -> LayerMask {
+        self.m_layer
+    }
+
+    pub fn set_layer(&mut self, layer: LayerMask) {
+        self.m_layer = layer;
+        self.sync();
+    }
+
+    pub fn get_mask(&self) -> LayerMask {
+        self.m_mask
+    }
+
+    pub fn set_mask(&mut self, mask: LayerMask) {
+        self.m_mask = mask;
+        self.sync();
+    }
+
+    pub fn is_trigger(&self) -> bool {
+        self.m_is_trigger
+    }
+
+    pub fn set_trigger(&mut self, is_trigger: bool) {
+        self.m_is_trigger = is_trigger;
+        self.sync();
+    }
+
+    // World space box of the collider
+    pub fn get_bounds(&self) -> Rect {
+        let position = self
+            .get_entity()
+            .get_component::<TransformComponent>()
+            .map_or(Vec2::zero(), |transform| transform.get_position());
+
+        Rect::from_pos_size(position + self.m_offset, self.m_size)
+    }
+
+    fn get_proxy(&self) -> ColliderProxy {
+        ColliderProxy {
+            entity_id: self.get_entity().get_id(),
+            bounds: self.get_bounds(),
+            layer: self.m_layer,
+            mask: self.m_mask,
+            is_trigger: self.m_is_trigger,
+        }
+    }
+
+    pub(crate) fn get_handle(&self) -> ColliderHandle {
+        self.m_handle
+    }
+
+    pub(crate) fn sync(&mut self) {
+        if self.m_handle != INVALID_COLLIDER_HANDLE {
+            self.m_physics_world
+                .update_collider(self.m_handle, self.get_proxy());
+        }
+    }
+}
@@ -1,12 +1,21 @@
-use std::any::Any;
+use std::any::{Any, TypeId};
 
+use serde_json::Value;
+
+use crate::core::reflect::FieldValue;
 use crate::core::render::RenderQueue;
-use crate::entity::Entity;
+use crate::core::world_state::{LoadContext, SaveContext};
+use crate::entity::{Entity, EntityId};
+use crate::physics::Collision;
 
 pub mod component_priority {
     pub const INPUT: i32 = -150;
     pub const TRANSFORM: i32 = -100;
     pub const DEFAULT: i32 = 0;
+    pub const TWEEN: i32 = 50;
+    pub const COLLIDER: i32 = 75;
+    pub const RIGID_BODY: i32 = 80;
+    pub const ANIMATION: i32 = 90;
     pub const RENDER: i32 = 100;
 }
 
@@ -29,10 +38,60 @@ where
     }
 }
 
+// A sibling component that must be present on the same entity
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentRequirement {
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+}
+
+impl ComponentRequirement {
+    pub fn of<T>(type_name: &'static str) -> Self
+    where
+        T: Component,
+    {
+        Self {
+            type_id: TypeId::of::<T>(),
+            type_name,
+        }
+    }
+}
+
 pub trait ComponentBase {
     unsafe fn set_entity(&mut self, entity: *mut Entity);
+    // False until the component is added to an entity
+    fn has_entity(&self) -> bool;
     fn get_entity(&self) -> &Entity;
     fn get_entity_mut(&self) -> &mut Entity;
+    fn get_type_name(&self) -> &'static str;
+
+    // Declared with #[component(priority = ..)]
+    fn get_declared_priority(&self) -> i32 {
+        component_priority::DEFAULT
+    }
+
+    // Declared with #[component(requires(..))]
+    fn get_requirements(&self) -> Vec<ComponentRequirement> {
+        Vec::new()
+    }
+
+    // Generated from the fields marked with #[reflect]
+    fn get_fields(&self) -> Vec<(&'static str, FieldValue)> {
+        Vec::new()
+    }
+
+    fn set_field(&mut self, name: &str, _value: FieldValue) -> Result<(), String> {
+        Err(format!(
+            "'{}' has no reflected field '{}'",
+            self.get_type_name(),
+            name
+        ))
+    }
+
+    // Generated from the fields marked with #[save] or #[save(texture)].
+    // Returns None for components without saved fields.
+    fn save_state(&self, ctx: &SaveContext) -> Result<Option<Value>, String>;
+    fn load_state(&mut self, state: &Value, ctx: &mut LoadContext) -> Result<(), String>;
 }
 
 #[allow(unused_variables)]
@@ -45,7 +104,7 @@ pub trait Component: ComponentBase + AsAny + 'static {
     }
 
     fn get_priority(&self) -> i32 {
-        component_priority::DEFAULT
+        self.get_declared_priority()
     }
 
     fn enter_play(&mut self) {}
@@ -53,4 +112,10 @@ pub trait Component: ComponentBase + AsAny + 'static {
     fn tick(&mut self, _delta_time: f32) {}
     fn physics_tick(&mut self, _fixed_delta_time: f32) {}
     fn render_tick(&mut self, _delta_time: f32, render_queue: &mut RenderQueue) {}
+
+    // Contacts reported by the physics world after each physics tick.
+    // on_collision() is called once when two solid colliders start touching.
+    fn on_trigger_enter(&mut self, _other: EntityId) {}
+    fn on_trigger_exit(&mut self, _other: EntityId) {}
+    fn on_collision(&mut self, _collision: &Collision) {}
 }
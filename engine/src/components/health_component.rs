@@ -0,0 +1,188 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::components::{Component, ComponentBase, TransformComponent, component};
+use crate::core::event_bus::EventBus;
+use crate::entity::EntityId;
+use crate::math::Vec2;
+
+// Queued to hurt the target, e.g. by a projectile or a dash
+#[derive(Debug, Clone, Copy)]
+pub struct DamageEvent {
+    pub target_id: EntityId,
+    pub source_id: EntityId,
+    pub amount: i32,
+    // Velocity added to the target
+    pub knockback: Vec2,
+}
+
+// Queued when an entity took damage, e.g. for screen shake
+#[derive(Debug, Clone, Copy)]
+pub struct HurtEvent {
+    pub entity_id: EntityId,
+    pub source_id: EntityId,
+    pub amount: i32,
+}
+
+// Queued when an entity's health runs out
+#[derive(Debug, Clone, Copy)]
+pub struct DeathEvent {
+    pub entity_id: EntityId,
+    pub source_id: EntityId,
+    pub position: Vec2,
+}
+
+// Hit points of an entity. Takes damage from DamageEvents targeting its entity, or directly
+// with damage(). Each hit makes the entity invulnerable for a moment.
+// What happens on death is up to whoever listens to the DeathEvent.
+#[component(requires(TransformComponent), serialize, manual_register)]
+pub struct HealthComponent {
+    m_event_bus: Rc<EventBus>,
+    m_damage_events: Rc<RefCell<Vec<DamageEvent>>>,
+    #[save]
+    #[reflect]
+    m_max_health: i32,
+    #[save]
+    #[reflect]
+    m_health: i32,
+    // How long the entity is invulnerable after a hit
+    #[save]
+    #[reflect]
+    m_hit_invulnerability: f32,
+    #[save]
+    m_invulnerable_time: f32,
+    // Knockback not yet picked up by the movement code
+    #[save]
+    m_knockback: Vec2,
+}
+
+impl Component for HealthComponent {
+    fn enter_play(&mut self) {
+        let entity_id = self.get_entity().get_id();
+        let damage_events = self.m_damage_events.clone();
+        self.m_event_bus
+            .subscribe_for_entity(entity_id, move |event: &DamageEvent| {
+                if event.target_id == entity_id {
+                    damage_events.borrow_mut().push(*event);
+                }
+            });
+    }
+
+    fn exit_play(&mut self) {
+        self.m_damage_events.borrow_mut().clear();
+    }
+
+    fn tick(&mut self, delta_time: f32) {
+        self.m_invulnerable_time = (self.m_invulnerable_time - delta_time).max(0.0);
+
+        let damage_events = std::mem::take(&mut *self.m_damage_events.borrow_mut());
+        for event in damage_events {
+            self.damage(event.source_id, event.amount, event.knockback);
+        }
+    }
+}
+
+#[component]
+impl HealthComponent {
+    pub fn new(event_bus: Rc<EventBus>) -> Self {
+        Self {
+            m_event_bus: event_bus,
+            m_damage_events: Rc::new(RefCell::new(Vec::new())),
+            m_max_health: 1,
+            m_health: 1,
+            m_hit_invulnerability: 1.0,
+            m_invulnerable_time: 0.0,
+            m_knockback: Vec2::zero(),
+        }
+    }
+
+    // Also starts at full health
+    pub fn with_max_health(mut self, max_health: i32) -> Self {
+        self.m_max_health = max_health;
+        self.m_health = max_health;
+        self
+    }
+
+    pub fn with_hit_invulnerability(mut self, hit_invulnerability: f32) -> Self {
+        self.m_hit_invulnerability = hit_invulnerability;
+        self
+    }
+
+    pub fn get_health(&self) -> i32 {
+        self.m_health
+    }
+
+    // Clamped to the max health, without any hurt or death events
+    pub fn set_health(&mut self, health: i32) {
+        self.m_health = health.clamp(0, self.m_max_health);
+    }
+
+    pub fn get_max_health(&self) -> i32 {
+        self.m_max_health
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.m_health <= 0
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.m_invulnerable_time > 0.0
+    }
+
+    // Never shortens a longer invulnerability, e.g. dashing right after a hit
+    pub fn set_invulnerable(&mut self, duration: f32) {
+        self.m_invulnerable_time = self.m_invulnerable_time.max(duration);
+    }
+
+    // Ignored while dead or invulnerable. Returns whether the damage was taken.
+    pub fn damage(&mut self, source_id: EntityId, amount: i32, knockback: Vec2) -> bool {
+        if self.is_dead() || self.is_invulnerable() || amount <= 0 {
+            return false;
+        }
+
+        let entity_id = self.get_entity().get_id();
+        self.m_health = (self.m_health - amount).max(0);
+        self.m_knockback += knockback;
+        self.m_invulnerable_time = self.m_hit_invulnerability;
+
+        self.m_event_bus.queue(HurtEvent {
+            entity_id,
+            source_id,
+            amount,
+        });
+
+        if self.is_dead() {
+            let position = self
+                .get_entity()
+                .get_component::<TransformComponent>()
+                .unwrap()
+                .get_position();
+
+            self.m_event_bus.queue(DeathEvent {
+                entity_id,
+                source_id,
+                position,
+            });
+        }
+
+        true
+    }
+
+    pub fn heal(&mut self, amount: i32) {
+        if !self.is_dead() {
+            self.m_health = (self.m_health + amount).min(self.m_max_health);
+        }
+    }
+
+    // Back to full health, e.g. when respawning
+    pub fn revive(&mut self) {
+        self.m_health = self.m_max_health;
+        self.m_invulnerable_time = 0.0;
+        self.m_knockback = Vec2::zero();
+    }
+
+    // Returns the knockback since the last call
+    pub fn take_knockback(&mut self) -> Vec2 {
+        std::mem::replace(&mut self.m_knockback, Vec2::zero())
+    }
+}
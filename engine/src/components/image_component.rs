@@ -1,43 +1,61 @@
-use crate::components::{Component, ComponentBase, TransformComponent, component_priority};
-use crate::core::assets::TextureId;
-use crate::core::render::{RenderQueue, RenderData};
-use crate::entity::Entity;
+use sdl2::pixels::Color;
+
+use crate::components::{Component, ComponentBase, TransformComponent, component};
+use crate::core::assets::{INVALID_TEXTURE_ID, TextureId};
+use crate::core::render::{RenderData, RenderQueue};
 use crate::math::Vec2;
 
-#[derive(ComponentBase)]
+#[component(priority = RENDER, requires(TransformComponent), serialize)]
 pub struct ImageComponent {
-    m_entity: *mut Entity,
+    #[save(texture)]
     m_texture_id: TextureId,
+    #[save]
+    #[reflect]
     m_scale: Vec2,
+    #[save]
+    #[reflect]
+    m_alpha: f32,
+    m_tint: Color,
+    #[save]
+    #[reflect]
+    m_flip_x: bool,
 }
 
 impl Component for ImageComponent {
-    fn get_priority(&self) -> i32 {
-        component_priority::RENDER
-    }
-
     fn render_tick(&mut self, _delta_time: f32, render_queue: &mut RenderQueue) {
         let transform = self
             .get_entity()
             .get_component::<TransformComponent>()
             .unwrap();
 
-        let t_scale = transform.get_scale();
-        render_queue.enqueue(RenderData::new(
-            self.m_texture_id,
-            transform.get_position(),
-            transform.get_prev_position(),
-            Vec2::from_xy(self.m_scale.x * t_scale.x, self.m_scale.y * t_scale.y),
-        ));
+        render_queue.enqueue(
+            RenderData::new(
+                self.m_texture_id,
+                transform.get_position(),
+                transform.get_prev_position(),
+                self.m_scale * transform.get_scale(),
+            )
+            .with_tint(self.m_tint)
+            .with_alpha(self.m_alpha)
+            .with_flip_x(self.m_flip_x),
+        );
     }
 }
 
+#[component]
 impl ImageComponent {
-    pub fn new(texture_id: TextureId) -> Self {
+    // The texture is assigned later with set_texture_id() or restored from a save
+    pub fn new() -> Self {
+        Self::from_texture(INVALID_TEXTURE_ID)
+    }
+
+    pub fn from_texture(texture_id: TextureId) -> Self {
         Self {
-            m_entity: std::ptr::null_mut(),
             m_texture_id: texture_id,
             m_scale: Vec2::one(),
+            m_alpha: 1.0,
+            m_tint: Color::WHITE,
+            m_flip_x: false,
         }
     }
 
@@ -56,4 +74,28 @@ impl ImageComponent {
     pub fn set_scale(&mut self, scale: Vec2) {
         self.m_scale = scale;
     }
+
+    pub fn get_alpha(&self) -> f32 {
+        self.m_alpha
+    }
+
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.m_alpha = alpha;
+    }
+
+    pub fn get_tint(&self) -> Color {
+        self.m_tint
+    }
+
+    pub fn set_tint(&mut self, tint: Color) {
+        self.m_tint = tint;
+    }
+
+    pub fn is_flipped_x(&self) -> bool {
+        self.m_flip_x
+    }
+
+    pub fn set_flip_x(&mut self, flip_x: bool) {
+        self.m_flip_x = flip_x;
+    }
 }
@@ -2,18 +2,16 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
-use crate::components::{Component, ComponentBase, component_priority};
+use crate::components::{Component, component};
 use crate::core::input::{
     INVALID_INPUT_EVENT_HANDLER_ID, Input, InputEvent, InputEventHandlerId, InputEventType,
 };
-use crate::entity::Entity;
 
 pub type BindingId = i32;
 pub const INVALID_BINDING_ID: BindingId = -1;
 
-#[derive(ComponentBase)]
+#[component(priority = INPUT)]
 pub struct InputComponent {
-    m_entity: *mut Entity,
     m_input: Rc<RefCell<Input>>,
     m_input_event_handler_id: InputEventHandlerId,
     m_next_binding_id: BindingId,
@@ -23,10 +21,6 @@ pub struct InputComponent {
 }
 
 impl Component for InputComponent {
-    fn get_priority(&self) -> i32 {
-        component_priority::INPUT
-    }
-
     fn enter_play(&mut self) {
         let this: *const InputComponent = self;
         self.m_input_event_handler_id =
@@ -44,10 +38,10 @@ impl Component for InputComponent {
     }
 }
 
+#[component]
 impl InputComponent {
     pub fn new(input: Rc<RefCell<Input>>) -> Self {
         Self {
-            m_entity: std::ptr::null_mut(),
             m_input: input,
             m_input_event_handler_id: INVALID_INPUT_EVENT_HANDLER_ID,
             m_next_binding_id: 0,
@@ -1,11 +1,25 @@
+pub mod animation_component;
+pub mod character_controller_component;
+pub mod collider_component;
 pub mod component;
+pub mod health_component;
 pub mod image_component;
 pub mod input_component;
+pub mod particle_emitter_component;
+pub mod rigid_body_component;
 pub mod transform_component;
+pub mod tween_component;
 
 // Re-exports
+pub use animation_component::*;
+pub use character_controller_component::*;
+pub use collider_component::*;
 pub use component::*;
 pub use engine_derive::*;
+pub use health_component::*;
 pub use image_component::*;
 pub use input_component::*;
+pub use particle_emitter_component::*;
+pub use rigid_body_component::*;
 pub use transform_component::*;
+pub use tween_component::*;
@@ -0,0 +1,347 @@
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Component, ComponentBase, TransformComponent, component};
+use crate::core::assets::TextureId;
+use crate::core::render::{RenderData, RenderQueue};
+use crate::math::{Easing, Vec2, random_range};
+
+// Value over a particle's lifetime, from start at birth to end at death
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ParticleCurve {
+    pub start: f32,
+    pub end: f32,
+    pub easing: Easing,
+}
+
+impl ParticleCurve {
+    pub fn new(start: f32, end: f32, easing: Easing) -> Self {
+        Self { start, end, easing }
+    }
+
+    pub fn constant(value: f32) -> Self {
+        Self::new(value, value, Easing::Linear)
+    }
+
+    // t in [0, 1] is the fraction of the lifetime that has passed
+    pub fn sample(&self, t: f32) -> f32 {
+        self.start + (self.end - self.start) * self.easing.apply(t)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Particle {
+    // Relative to the emitter in local space
+    position: Vec2,
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+}
+
+// Emits particles continuously at a rate and/or in bursts. The particles are plain data
+// in one list, not entities, so thousands of them are cheap.
+//
+// Each particle gets a random lifetime, speed and angle from the configured ranges and
+// starts somewhere in the spawn area. The frames play once over its lifetime, while the
+// alpha and scale follow their curves.
+//
+// In world space particles stay where they were emitted when the entity moves,
+// e.g. a trail. In local space they move along with it.
+// Particles are not saved, only the settings are.
+#[component(priority = RENDER, requires(TransformComponent), serialize)]
+pub struct ParticleEmitterComponent {
+    #[save(textures)]
+    m_frames: Vec<TextureId>,
+    // Particles per second while emitting
+    #[save]
+    #[reflect]
+    m_rate: f32,
+    #[save]
+    #[reflect]
+    m_is_emitting: bool,
+    #[save]
+    m_min_lifetime: f32,
+    #[save]
+    m_max_lifetime: f32,
+    #[save]
+    m_min_speed: f32,
+    #[save]
+    m_max_speed: f32,
+    // Degrees, 0 is right and 90 is down
+    #[save]
+    m_min_angle: f32,
+    #[save]
+    m_max_angle: f32,
+    #[save]
+    m_gravity: Vec2,
+    // Fraction of the velocity lost per second
+    #[save]
+    #[reflect]
+    m_drag: f32,
+    // Area new particles start in, relative to the entity's position
+    #[save]
+    m_spawn_offset: Vec2,
+    #[save]
+    m_spawn_size: Vec2,
+    #[save]
+    m_alpha_curve: ParticleCurve,
+    #[save]
+    m_scale_curve: ParticleCurve,
+    #[save]
+    m_is_local_space: bool,
+    // Particles past this are not emitted
+    #[save]
+    m_max_particles: usize,
+    m_particles: Vec<Particle>,
+    m_emit_time: f32,
+}
+
+impl Component for ParticleEmitterComponent {
+    fn exit_play(&mut self) {
+        self.clear();
+    }
+
+    fn tick(&mut self, delta_time: f32) {
+        if self.m_is_emitting && self.m_rate > 0.0 {
+            let interval = 1.0 / self.m_rate;
+            self.m_emit_time += delta_time;
+            while self.m_emit_time >= interval {
+                self.m_emit_time -= interval;
+                self.emit_one(self.get_spawn_position());
+            }
+        }
+
+        let drag = (1.0 - self.m_drag * delta_time).max(0.0);
+        let mut index = 0;
+        while index < self.m_particles.len() {
+            let particle = &mut self.m_particles[index];
+            particle.age += delta_time;
+            if particle.age >= particle.lifetime {
+                self.m_particles.swap_remove(index);
+                continue;
+            }
+
+            particle.velocity = (particle.velocity + self.m_gravity * delta_time) * drag;
+            particle.position += particle.velocity * delta_time;
+            index += 1;
+        }
+    }
+
+    fn render_tick(&mut self, _delta_time: f32, render_queue: &mut RenderQueue) {
+        if self.m_frames.is_empty() || self.m_particles.is_empty() {
+            return;
+        }
+
+        let origin = self.get_origin();
+        for particle in &self.m_particles {
+            let t = particle.age / particle.lifetime;
+            let frame = ((t * self.m_frames.len() as f32) as usize).min(self.m_frames.len() - 1);
+            let position = origin + particle.position;
+            let scale = self.m_scale_curve.sample(t);
+
+            render_queue.enqueue(
+                RenderData::new(
+                    self.m_frames[frame],
+                    position,
+                    position,
+                    Vec2::from_xy(scale, scale),
+                )
+                .with_alpha(self.m_alpha_curve.sample(t))
+                .centered(),
+            );
+        }
+    }
+}
+
+#[component]
+impl ParticleEmitterComponent {
+    pub fn new() -> Self {
+        Self {
+            m_frames: Vec::new(),
+            m_rate: 10.0,
+            m_is_emitting: true,
+            m_min_lifetime: 1.0,
+            m_max_lifetime: 1.0,
+            m_min_speed: 0.0,
+            m_max_speed: 0.0,
+            m_min_angle: 0.0,
+            m_max_angle: 360.0,
+            m_gravity: Vec2::zero(),
+            m_drag: 0.0,
+            m_spawn_offset: Vec2::zero(),
+            m_spawn_size: Vec2::zero(),
+            m_alpha_curve: ParticleCurve::constant(1.0),
+            m_scale_curve: ParticleCurve::constant(1.0),
+            m_is_local_space: false,
+            m_max_particles: 1000,
+            m_particles: Vec::new(),
+            m_emit_time: 0.0,
+        }
+    }
+
+    // Played once over each particle's lifetime, a single frame stays put
+    pub fn with_frames(mut self, frames: Vec<TextureId>) -> Self {
+        self.m_frames = frames;
+        self
+    }
+
+    // Zero only emits in bursts
+    pub fn with_rate(mut self, rate: f32) -> Self {
+        self.m_rate = rate;
+        self
+    }
+
+    pub fn with_emitting(mut self, is_emitting: bool) -> Self {
+        self.m_is_emitting = is_emitting;
+        self
+    }
+
+    pub fn with_lifetime(mut self, min_lifetime: f32, max_lifetime: f32) -> Self {
+        self.m_min_lifetime = min_lifetime;
+        self.m_max_lifetime = max_lifetime;
+        self
+    }
+
+    pub fn with_speed(mut self, min_speed: f32, max_speed: f32) -> Self {
+        self.m_min_speed = min_speed;
+        self.m_max_speed = max_speed;
+        self
+    }
+
+    pub fn with_angle(mut self, min_angle: f32, max_angle: f32) -> Self {
+        self.m_min_angle = min_angle;
+        self.m_max_angle = max_angle;
+        self
+    }
+
+    pub fn with_gravity(mut self, gravity: Vec2) -> Self {
+        self.m_gravity = gravity;
+        self
+    }
+
+    pub fn with_drag(mut self, drag: f32) -> Self {
+        self.m_drag = drag;
+        self
+    }
+
+    pub fn with_spawn_area(mut self, offset: Vec2, size: Vec2) -> Self {
+        self.m_spawn_offset = offset;
+        self.m_spawn_size = size;
+        self
+    }
+
+    pub fn with_alpha_curve(mut self, alpha_curve: ParticleCurve) -> Self {
+        self.m_alpha_curve = alpha_curve;
+        self
+    }
+
+    pub fn with_scale_curve(mut self, scale_curve: ParticleCurve) -> Self {
+        self.m_scale_curve = scale_curve;
+        self
+    }
+
+    pub fn with_local_space(mut self, is_local_space: bool) -> Self {
+        self.m_is_local_space = is_local_space;
+        self
+    }
+
+    pub fn with_max_particles(mut self, max_particles: usize) -> Self {
+        self.m_max_particles = max_particles;
+        self
+    }
+
+    pub fn is_emitting(&self) -> bool {
+        self.m_is_emitting
+    }
+
+    // Stopping lets the particles that are alive play out
+    pub fn set_emitting(&mut self, is_emitting: bool) {
+        if is_emitting && !self.m_is_emitting {
+            self.m_emit_time = 0.0;
+        }
+        self.m_is_emitting = is_emitting;
+    }
+
+    pub fn get_rate(&self) -> f32 {
+        self.m_rate
+    }
+
+    pub fn set_rate(&mut self, rate: f32) {
+        self.m_rate = rate;
+    }
+
+    pub fn get_particle_count(&self) -> usize {
+        self.m_particles.len()
+    }
+
+    // Emits count particles at once in the spawn area
+    pub fn burst(&mut self, count: usize) {
+        for _ in 0..count {
+            self.emit_one(self.get_spawn_position());
+        }
+    }
+
+    // Emits count particles at once around a point in world space, e.g. where a shot hit.
+    // The spawn area is centered on the point.
+    pub fn burst_at(&mut self, position: Vec2, count: usize) {
+        let origin = self.get_origin();
+        for _ in 0..count {
+            let spawn_position = position - self.m_spawn_size / 2.0 + self.get_random_spawn_point();
+            self.emit_one(spawn_position - origin);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.m_particles.clear();
+        self.m_emit_time = 0.0;
+    }
+
+    // Where particle positions are relative to
+    fn get_origin(&self) -> Vec2 {
+        if self.m_is_local_space {
+            self.get_entity()
+                .get_component::<TransformComponent>()
+                .unwrap()
+                .get_position()
+        } else {
+            Vec2::zero()
+        }
+    }
+
+    // A random point in the spawn area, relative to the origin
+    fn get_spawn_position(&self) -> Vec2 {
+        let entity_position = self
+            .get_entity()
+            .get_component::<TransformComponent>()
+            .unwrap()
+            .get_position();
+
+        entity_position - self.get_origin() + self.m_spawn_offset + self.get_random_spawn_point()
+    }
+
+    fn get_random_spawn_point(&self) -> Vec2 {
+        Vec2::from_xy(
+            fastrand::f32() * self.m_spawn_size.x,
+            fastrand::f32() * self.m_spawn_size.y,
+        )
+    }
+
+    fn emit_one(&mut self, position: Vec2) {
+        if self.m_particles.len() >= self.m_max_particles {
+            return;
+        }
+
+        let angle = random_range(self.m_min_angle, self.m_max_angle).to_radians();
+        let speed = random_range(self.m_min_speed, self.m_max_speed);
+        let lifetime = random_range(self.m_min_lifetime, self.m_max_lifetime);
+        if lifetime <= 0.0 {
+            return;
+        }
+
+        self.m_particles.push(Particle {
+            position,
+            velocity: Vec2::from_angle(angle) * speed,
+            age: 0.0,
+            lifetime,
+        });
+    }
+}
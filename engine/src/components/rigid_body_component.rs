@@ -0,0 +1,218 @@
+use std::rc::Rc;
+
+use crate::components::{ColliderComponent, Component, ComponentBase, component};
+use crate::math::Vec2;
+use crate::physics::rigid_body::RigidBody;
+use crate::physics::{ColliderHandle, INVALID_COLLIDER_HANDLE, PhysicsWorld};
+
+// Dynamic body simulated by the PhysicsWorld in the fixed physics step: falls, bounces off
+// the tiles and pushes other bodies around. Comes to rest and sleeps when it stops moving.
+// The collider's mask decides what it collides with, the world layer is solid for it.
+#[component(
+    priority = RIGID_BODY,
+    requires(ColliderComponent),
+    serialize,
+    manual_register
+)]
+pub struct RigidBodyComponent {
+    m_physics_world: Rc<PhysicsWorld>,
+    m_handle: ColliderHandle,
+    // Zero or less makes the body immovable by other bodies
+    #[save]
+    #[reflect]
+    m_mass: f32,
+    #[save]
+    #[reflect]
+    m_gravity_scale: f32,
+    // 0 stops dead, 1 bounces back at full speed
+    #[save]
+    #[reflect]
+    m_restitution: f32,
+    #[save]
+    #[reflect]
+    m_friction: f32,
+    // Mirrors the simulation, so it gets saved
+    #[save]
+    m_velocity: Vec2,
+    #[save]
+    m_is_sleeping: bool,
+}
+
+impl Component for RigidBodyComponent {
+    fn enter_play(&mut self) {
+        self.m_handle = self
+            .get_entity()
+            .get_component::<ColliderComponent>()
+            .map_or(INVALID_COLLIDER_HANDLE, |collider| collider.get_handle());
+
+        let body = RigidBody {
+            is_sleeping: self.m_is_sleeping,
+            ..self.get_body()
+        };
+        self.m_physics_world.add_body(self.m_handle, body);
+    }
+
+    fn exit_play(&mut self) {
+        self.pull_body();
+        self.m_physics_world.remove_body(self.m_handle);
+        self.m_handle = INVALID_COLLIDER_HANDLE;
+    }
+
+    fn physics_tick(&mut self, _fixed_delta_time: f32) {
+        self.pull_body();
+
+        // Picks up settings written without the setters, e.g. by the inspector
+        if let Some(body) = self.m_physics_world.get_body(self.m_handle) {
+            let settings = RigidBody {
+                velocity: body.velocity,
+                is_sleeping: body.is_sleeping,
+                sleep_time: body.sleep_time,
+                ..self.get_body()
+            };
+            if settings != body {
+                self.m_physics_world.set_body(self.m_handle, settings);
+            }
+        }
+    }
+}
+
+#[component]
+impl RigidBodyComponent {
+    pub fn new(physics_world: Rc<PhysicsWorld>) -> Self {
+        Self {
+            m_physics_world: physics_world,
+            m_handle: INVALID_COLLIDER_HANDLE,
+            m_mass: 1.0,
+            m_gravity_scale: 1.0,
+            m_restitution: 0.2,
+            m_friction: 0.5,
+            m_velocity: Vec2::zero(),
+            m_is_sleeping: false,
+        }
+    }
+
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.m_mass = mass;
+        self
+    }
+
+    pub fn with_gravity_scale(mut self, gravity_scale: f32) -> Self {
+        self.m_gravity_scale = gravity_scale;
+        self
+    }
+
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.m_restitution = restitution;
+        self
+    }
+
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.m_friction = friction;
+        self
+    }
+
+    pub fn with_velocity(mut self, velocity: Vec2) -> Self {
+        self.m_velocity = velocity;
+        self
+    }
+
+    pub fn get_mass(&self) -> f32 {
+        self.m_mass
+    }
+
+    pub fn set_mass(&mut self, mass: f32) {
+        self.pull_body();
+        self.m_mass = mass;
+        self.push_body();
+    }
+
+    pub fn get_gravity_scale(&self) -> f32 {
+        self.m_gravity_scale
+    }
+
+    pub fn set_gravity_scale(&mut self, gravity_scale: f32) {
+        self.pull_body();
+        self.m_gravity_scale = gravity_scale;
+        self.push_body();
+    }
+
+    pub fn get_restitution(&self) -> f32 {
+        self.m_restitution
+    }
+
+    pub fn set_restitution(&mut self, restitution: f32) {
+        self.pull_body();
+        self.m_restitution = restitution;
+        self.push_body();
+    }
+
+    pub fn get_friction(&self) -> f32 {
+        self.m_friction
+    }
+
+    pub fn set_friction(&mut self, friction: f32) {
+        self.pull_body();
+        self.m_friction = friction;
+        self.push_body();
+    }
+
+    pub fn get_velocity(&self) -> Vec2 {
+        self.m_physics_world
+            .get_body(self.m_handle)
+            .map_or(self.m_velocity, |body| body.velocity)
+    }
+
+    pub fn set_velocity(&mut self, velocity: Vec2) {
+        self.m_velocity = velocity;
+        self.push_body();
+    }
+
+    // Instant change in momentum, e.g. an explosion or a hit
+    pub fn apply_impulse(&mut self, impulse: Vec2) {
+        self.pull_body();
+        self.m_velocity += impulse * get_inverse_mass(self.m_mass);
+        self.push_body();
+    }
+
+    pub fn is_sleeping(&self) -> bool {
+        self.m_physics_world
+            .get_body(self.m_handle)
+            .map_or(self.m_is_sleeping, |body| body.is_sleeping)
+    }
+
+    pub fn wake_up(&mut self) {
+        self.pull_body();
+        self.push_body();
+    }
+
+    fn get_body(&self) -> RigidBody {
+        RigidBody {
+            velocity: self.m_velocity,
+            inverse_mass: get_inverse_mass(self.m_mass),
+            gravity_scale: self.m_gravity_scale,
+            restitution: self.m_restitution,
+            friction: self.m_friction,
+            is_sleeping: false,
+            sleep_time: 0.0,
+        }
+    }
+
+    // Copies the simulated state into the component
+    fn pull_body(&mut self) {
+        if let Some(body) = self.m_physics_world.get_body(self.m_handle) {
+            self.m_velocity = body.velocity;
+            self.m_is_sleeping = body.is_sleeping;
+        }
+    }
+
+    // Hands the settings and velocity to the simulation, which wakes the body
+    fn push_body(&mut self) {
+        self.m_is_sleeping = false;
+        self.m_physics_world
+            .set_body(self.m_handle, self.get_body());
+    }
+}
+
+fn get_inverse_mass(mass: f32) -> f32 {
+    if mass > 0.0 { 1.0 / mass } else { 0.0 }
+}
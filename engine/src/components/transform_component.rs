@@ -1,25 +1,24 @@
-use crate::components::{Component, ComponentBase, component_priority};
-use crate::entity::Entity;
+use crate::components::{ColliderComponent, Component, ComponentBase, component};
 use crate::math::Vec2;
 
-#[derive(ComponentBase)]
+#[component(priority = TRANSFORM, serialize)]
 pub struct TransformComponent {
-    m_entity: *mut Entity,
+    #[save]
+    #[reflect]
     m_position: Vec2,
+    #[save]
     m_prev_position: Vec2,
+    #[save]
+    #[reflect]
     m_scale: Vec2,
 }
 
-impl Component for TransformComponent {
-    fn get_priority(&self) -> i32 {
-        component_priority::TRANSFORM
-    }
-}
+impl Component for TransformComponent {}
 
+#[component]
 impl TransformComponent {
     pub fn new() -> Self {
         Self {
-            m_entity: std::ptr::null_mut(),
             m_position: Vec2::zero(),
             m_prev_position: Vec2::zero(),
             m_scale: Vec2::one(),
@@ -33,6 +32,14 @@ impl TransformComponent {
     pub fn set_position(&mut self, pos: Vec2) {
         self.m_prev_position = self.m_position;
         self.m_position = pos;
+        self.sync_collider();
+    }
+
+    // Moves without interpolating from the old position, e.g. respawning
+    pub fn teleport(&mut self, pos: Vec2) {
+        self.m_prev_position = pos;
+        self.m_position = pos;
+        self.sync_collider();
     }
 
     pub fn get_prev_position(&self) -> Vec2 {
@@ -46,4 +53,15 @@ impl TransformComponent {
     pub fn set_scale(&mut self, scale: Vec2) {
         self.m_scale = scale;
     }
+
+    // Keeps the collider's broad phase entry up to date as the entity moves
+    fn sync_collider(&mut self) {
+        if self.has_entity()
+            && let Some(collider) = self
+                .get_entity_mut()
+                .get_component_mut::<ColliderComponent>()
+        {
+            collider.sync();
+        }
+    }
 }
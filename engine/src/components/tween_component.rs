@@ -0,0 +1,535 @@
+use std::any::Any;
+use std::collections::HashSet;
+
+use sdl2::pixels::Color;
+
+use crate::components::{Component, ComponentBase, ImageComponent, TransformComponent, component};
+use crate::entity::Entity;
+use crate::math::{Easing, Vec2};
+
+pub type TweenHandle = i32;
+pub const INVALID_TWEEN_HANDLE: TweenHandle = -1;
+
+// Values that can be interpolated by a tween
+pub trait Tweenable: Copy + 'static {
+    fn lerp(from: Self, to: Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn lerp(from: Self, to: Self, t: f32) -> Self {
+        from + (to - from) * t
+    }
+}
+
+impl Tweenable for Vec2 {
+    fn lerp(from: Self, to: Self, t: f32) -> Self {
+        from + (to - from) * t
+    }
+}
+
+impl Tweenable for Color {
+    fn lerp(from: Self, to: Self, t: f32) -> Self {
+        let channel =
+            |a: u8, b: u8| f32::lerp(a as f32, b as f32, t).round().clamp(0.0, 255.0) as u8;
+        Color::RGBA(
+            channel(from.r, to.r),
+            channel(from.g, to.g),
+            channel(from.b, to.b),
+            channel(from.a, to.a),
+        )
+    }
+}
+
+type TweenGetter<T> = Box<dyn Fn(&Entity) -> T>;
+type TweenSetter<T> = Box<dyn Fn(&mut Entity, T)>;
+
+trait TweenTrack {
+    // Called when the tween starts (after its delay), captures the start value
+    fn start(&mut self, entity: &Entity);
+    fn apply(&mut self, entity: &mut Entity, t: f32);
+    // Ignored when the value type doesn't match the track
+    fn set_from(&mut self, from: &dyn Any);
+}
+
+struct PropertyTrack<T>
+where
+    T: Tweenable,
+{
+    m_from: Option<T>,
+    m_to: T,
+    m_start: Option<T>,
+    m_get: TweenGetter<T>,
+    m_set: TweenSetter<T>,
+}
+
+impl<T> TweenTrack for PropertyTrack<T>
+where
+    T: Tweenable,
+{
+    fn start(&mut self, entity: &Entity) {
+        self.m_start = Some(self.m_from.unwrap_or_else(|| (self.m_get)(entity)));
+    }
+
+    fn apply(&mut self, entity: &mut Entity, t: f32) {
+        if let Some(start) = self.m_start {
+            (self.m_set)(entity, T::lerp(start, self.m_to, t));
+        }
+    }
+
+    fn set_from(&mut self, from: &dyn Any) {
+        if let Some(from) = from.downcast_ref::<T>() {
+            self.m_from = Some(*from);
+        }
+    }
+}
+
+// Animates a single value of an entity over time.
+// Built with Tween::position(), Tween::scale(), Tween::alpha(), Tween::tint() or Tween::custom()
+// and configured with the builder methods.
+pub struct Tween {
+    m_track: Box<dyn TweenTrack>,
+    m_duration: f32,
+    m_delay: f32,
+    m_easing: Easing,
+    m_yoyo: bool,
+    m_loops: Option<u32>, // None loops forever
+    m_on_complete: Option<Box<dyn FnMut()>>,
+
+    m_elapsed: f32,
+    m_is_started: bool,
+    m_is_reversed: bool,
+    m_completed_loops: u32,
+}
+
+impl Tween {
+    pub fn custom<T, G, S>(get: G, set: S, to: T, duration: f32) -> Self
+    where
+        T: Tweenable,
+        G: Fn(&Entity) -> T + 'static,
+        S: Fn(&mut Entity, T) + 'static,
+    {
+        Self::from_track(
+            Box::new(PropertyTrack {
+                m_from: None,
+                m_to: to,
+                m_start: None,
+                m_get: Box::new(get),
+                m_set: Box::new(set),
+            }),
+            duration,
+        )
+    }
+
+    pub fn position(to: Vec2, duration: f32) -> Self {
+        Self::custom(
+            |entity| get_transform(entity).map_or(Vec2::zero(), |t| t.get_position()),
+            |entity, value| {
+                if let Some(transform) = entity.get_component_mut::<TransformComponent>() {
+                    transform.set_position(value);
+                }
+            },
+            to,
+            duration,
+        )
+    }
+
+    pub fn scale(to: Vec2, duration: f32) -> Self {
+        Self::custom(
+            |entity| get_transform(entity).map_or(Vec2::one(), |t| t.get_scale()),
+            |entity, value| {
+                if let Some(transform) = entity.get_component_mut::<TransformComponent>() {
+                    transform.set_scale(value);
+                }
+            },
+            to,
+            duration,
+        )
+    }
+
+    pub fn alpha(to: f32, duration: f32) -> Self {
+        Self::custom(
+            |entity| get_image(entity).map_or(1.0, |image| image.get_alpha()),
+            |entity, value| {
+                if let Some(image) = entity.get_component_mut::<ImageComponent>() {
+                    image.set_alpha(value);
+                }
+            },
+            to,
+            duration,
+        )
+    }
+
+    pub fn tint(to: Color, duration: f32) -> Self {
+        Self::custom(
+            |entity| get_image(entity).map_or(Color::WHITE, |image| image.get_tint()),
+            |entity, value| {
+                if let Some(image) = entity.get_component_mut::<ImageComponent>() {
+                    image.set_tint(value);
+                }
+            },
+            to,
+            duration,
+        )
+    }
+
+    // Waits without animating anything, useful between the steps of a sequence
+    pub fn wait(duration: f32) -> Self {
+        Self::from_track(Box::new(WaitTrack), duration)
+    }
+
+    // Starts from this value instead of the current one
+    pub fn from<T>(mut self, from: T) -> Self
+    where
+        T: Tweenable,
+    {
+        self.m_track.set_from(&from);
+        self
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.m_easing = easing;
+        self
+    }
+
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.m_delay = delay;
+        self
+    }
+
+    // Plays forward, then backward. Each direction takes the full duration.
+    pub fn with_yoyo(mut self) -> Self {
+        self.m_yoyo = true;
+        self
+    }
+
+    pub fn with_loops(mut self, loops: u32) -> Self {
+        self.m_loops = Some(loops.max(1));
+        self
+    }
+
+    pub fn looping(mut self) -> Self {
+        self.m_loops = None;
+        self
+    }
+
+    pub fn on_complete<T>(mut self, callback: T) -> Self
+    where
+        T: FnMut() + 'static,
+    {
+        self.m_on_complete = Some(Box::new(callback));
+        self
+    }
+
+    fn from_track(track: Box<dyn TweenTrack>, duration: f32) -> Self {
+        Self {
+            m_track: track,
+            m_duration: duration,
+            m_delay: 0.0,
+            m_easing: Easing::Linear,
+            m_yoyo: false,
+            m_loops: Some(1),
+            m_on_complete: None,
+            m_elapsed: 0.0,
+            m_is_started: false,
+            m_is_reversed: false,
+            m_completed_loops: 0,
+        }
+    }
+
+    // Returns true when the tween has finished
+    fn advance(&mut self, entity: &mut Entity, delta_time: f32) -> bool {
+        let mut delta_time = delta_time;
+        if !self.m_is_started {
+            self.m_delay -= delta_time;
+            if self.m_delay > 0.0 {
+                return false;
+            }
+
+            delta_time = -self.m_delay;
+            self.m_track.start(entity);
+            self.m_is_started = true;
+        }
+
+        self.m_elapsed += delta_time;
+
+        let t = if self.m_duration > 0.0 {
+            (self.m_elapsed / self.m_duration).min(1.0)
+        } else {
+            1.0
+        };
+
+        let leg_t = if self.m_is_reversed { 1.0 - t } else { t };
+        self.m_track.apply(entity, self.m_easing.apply(leg_t));
+
+        if t < 1.0 {
+            return false;
+        }
+
+        self.m_elapsed = (self.m_elapsed - self.m_duration).max(0.0);
+
+        if self.m_yoyo && !self.m_is_reversed {
+            self.m_is_reversed = true;
+            return false;
+        }
+
+        self.m_is_reversed = false;
+        self.m_completed_loops += 1;
+
+        let is_finished = self
+            .m_loops
+            .is_some_and(|loops| self.m_completed_loops >= loops);
+
+        if is_finished && let Some(on_complete) = &mut self.m_on_complete {
+            on_complete();
+        }
+
+        is_finished
+    }
+}
+
+struct WaitTrack;
+
+impl TweenTrack for WaitTrack {
+    fn start(&mut self, _entity: &Entity) {}
+    fn apply(&mut self, _entity: &mut Entity, _t: f32) {}
+    fn set_from(&mut self, _from: &dyn Any) {}
+}
+
+fn get_transform(entity: &Entity) -> Option<&TransformComponent> {
+    entity.get_component::<TransformComponent>()
+}
+
+fn get_image(entity: &Entity) -> Option<&ImageComponent> {
+    entity.get_component::<ImageComponent>()
+}
+
+// Tweens played one after another
+struct TweenSequence {
+    m_handle: TweenHandle,
+    m_steps: Vec<Tween>,
+    m_current_step: usize,
+}
+
+// Saved without its tweens, which hold closures. A restored entity starts with none
+// playing and keeps the values the tweens had reached.
+#[component(priority = TWEEN, serialize)]
+pub struct TweenComponent {
+    m_sequences: Vec<TweenSequence>,
+    m_next_handle: TweenHandle,
+    // Sequences taken out of the list while ticking, and the ones stopped meanwhile
+    m_ticking_handles: Vec<TweenHandle>,
+    m_stopped_handles: HashSet<TweenHandle>,
+}
+
+impl Component for TweenComponent {
+    fn tick(&mut self, delta_time: f32) {
+        // Taken out while ticking, so custom tweens are free to play more tweens on this entity
+        let mut sequences = std::mem::take(&mut self.m_sequences);
+        self.m_ticking_handles = sequences.iter().map(|sequence| sequence.m_handle).collect();
+
+        for sequence in &mut sequences {
+            if self.m_stopped_handles.contains(&sequence.m_handle) {
+                continue;
+            }
+
+            let entity = self.get_entity_mut();
+            if let Some(step) = sequence.m_steps.get_mut(sequence.m_current_step)
+                && step.advance(entity, delta_time)
+            {
+                sequence.m_current_step += 1;
+            }
+
+            if sequence.m_current_step >= sequence.m_steps.len() {
+                self.m_ticking_handles
+                    .retain(|handle| *handle != sequence.m_handle);
+            }
+        }
+
+        self.m_ticking_handles.clear();
+        let stopped_handles = std::mem::take(&mut self.m_stopped_handles);
+        sequences.retain(|sequence| {
+            sequence.m_current_step < sequence.m_steps.len()
+                && !stopped_handles.contains(&sequence.m_handle)
+        });
+
+        sequences.append(&mut self.m_sequences);
+        self.m_sequences = sequences;
+    }
+}
+
+#[component]
+impl TweenComponent {
+    pub fn new() -> Self {
+        Self {
+            m_sequences: Vec::new(),
+            m_next_handle: 0,
+            m_ticking_handles: Vec::new(),
+            m_stopped_handles: HashSet::new(),
+        }
+    }
+
+    pub fn play(&mut self, tween: Tween) -> TweenHandle {
+        self.play_sequence(vec![tween])
+    }
+
+    // Sequences play in parallel with each other
+    pub fn play_sequence(&mut self, tweens: Vec<Tween>) -> TweenHandle {
+        let handle = self.m_next_handle;
+        self.m_next_handle += 1;
+
+        self.m_sequences.push(TweenSequence {
+            m_handle: handle,
+            m_steps: tweens,
+            m_current_step: 0,
+        });
+
+        handle
+    }
+
+    // Stops the tween where it is, without calling its completion callback
+    pub fn stop(&mut self, handle: TweenHandle) {
+        self.m_sequences
+            .retain(|sequence| sequence.m_handle != handle);
+        if self.m_ticking_handles.contains(&handle) {
+            self.m_stopped_handles.insert(handle);
+        }
+    }
+
+    pub fn stop_all(&mut self) {
+        self.m_sequences.clear();
+        self.m_stopped_handles
+            .extend(self.m_ticking_handles.iter().copied());
+    }
+
+    pub fn is_playing(&self, handle: TweenHandle) -> bool {
+        let is_ticking =
+            self.m_ticking_handles.contains(&handle) && !self.m_stopped_handles.contains(&handle);
+
+        is_ticking
+            || self
+                .m_sequences
+                .iter()
+                .any(|sequence| sequence.m_handle == handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+
+    fn create_entity() -> Box<Entity> {
+        let mut entity = Entity::new();
+        entity.add_component(TransformComponent::new()).unwrap();
+        entity.add_component(TweenComponent::new()).unwrap();
+        entity
+    }
+
+    fn get_x(entity: &Entity) -> f32 {
+        get_transform(entity).unwrap().get_position().x
+    }
+
+    fn get_tweens(entity: &mut Entity) -> &mut TweenComponent {
+        entity.get_component_mut::<TweenComponent>().unwrap()
+    }
+
+    fn move_x(x: f32, duration: f32) -> Tween {
+        Tween::position(Vec2::from_xy(x, 0.0), duration)
+    }
+
+    #[test]
+    fn delay_carries_over_into_the_tween() {
+        let mut entity = create_entity();
+        let mut tween = move_x(10.0, 1.0).with_delay(0.5);
+
+        assert!(!tween.advance(&mut entity, 0.25));
+        assert_eq!(get_x(&entity), 0.0);
+
+        assert!(!tween.advance(&mut entity, 0.5));
+        assert_eq!(get_x(&entity), 2.5);
+    }
+
+    #[test]
+    fn yoyo_returns_to_the_start_value() {
+        let mut entity = create_entity();
+        let mut tween = move_x(10.0, 1.0).from(Vec2::from_xy(2.0, 0.0)).with_yoyo();
+
+        assert!(!tween.advance(&mut entity, 1.0));
+        assert_eq!(get_x(&entity), 10.0);
+
+        assert!(!tween.advance(&mut entity, 0.5));
+        assert_eq!(get_x(&entity), 6.0);
+
+        assert!(tween.advance(&mut entity, 0.5));
+        assert_eq!(get_x(&entity), 2.0);
+    }
+
+    #[test]
+    fn loops_complete_once_after_the_last_loop() {
+        let mut entity = create_entity();
+        let completed_count = Rc::new(Cell::new(0));
+        let completed_count_copy = completed_count.clone();
+        let mut tween = move_x(10.0, 1.0)
+            .with_loops(3)
+            .on_complete(move || completed_count_copy.set(completed_count_copy.get() + 1));
+
+        assert!(!tween.advance(&mut entity, 1.0));
+        assert!(!tween.advance(&mut entity, 1.0));
+        assert_eq!(completed_count.get(), 0);
+
+        assert!(tween.advance(&mut entity, 1.0));
+        assert_eq!(completed_count.get(), 1);
+        assert_eq!(get_x(&entity), 10.0);
+    }
+
+    #[test]
+    fn sequence_moves_on_to_the_next_step() {
+        let mut entity = create_entity();
+        let handle = get_tweens(&mut entity).play_sequence(vec![
+            move_x(10.0, 1.0),
+            Tween::wait(0.5),
+            move_x(20.0, 1.0),
+        ]);
+
+        entity.tick(1.0);
+        assert_eq!(get_x(&entity), 10.0);
+        entity.tick(0.5);
+        entity.tick(0.5);
+        assert_eq!(get_x(&entity), 15.0);
+        assert!(get_tweens(&mut entity).is_playing(handle));
+
+        entity.tick(0.5);
+        assert_eq!(get_x(&entity), 20.0);
+        assert!(!get_tweens(&mut entity).is_playing(handle));
+    }
+
+    #[test]
+    fn stopping_from_a_setter_takes_effect() {
+        let mut entity = create_entity();
+        let handle = Rc::new(Cell::new(INVALID_TWEEN_HANDLE));
+        let set_count = Rc::new(Cell::new(0));
+
+        let handle_copy = handle.clone();
+        let set_count_copy = set_count.clone();
+        let tween = Tween::custom(
+            |_| 0.0,
+            move |entity, _value: f32| {
+                set_count_copy.set(set_count_copy.get() + 1);
+                let tweens = get_tweens(entity);
+                assert!(tweens.is_playing(handle_copy.get()));
+                tweens.stop(handle_copy.get());
+                assert!(!tweens.is_playing(handle_copy.get()));
+            },
+            1.0,
+            1.0,
+        );
+        handle.set(get_tweens(&mut entity).play(tween));
+
+        entity.tick(0.25);
+        entity.tick(0.25);
+        assert_eq!(set_count.get(), 1);
+        assert!(!get_tweens(&mut entity).is_playing(handle.get()));
+    }
+}
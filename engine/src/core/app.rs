@@ -1,19 +1,38 @@
 use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::path::Path;
 use std::rc::Rc;
 
 use sdl2::event::Event;
 use sdl2::image::{InitFlag, Sdl2ImageContext};
 use sdl2::pixels::Color;
 use sdl2::rect::FRect;
-use sdl2::render::{Canvas, TextureCreator};
+use sdl2::render::{BlendMode, Canvas, TextureCreator};
 use sdl2::video::{Window, WindowContext};
 use sdl2::{EventPump, Sdl, TimerSubsystem};
 
-use crate::core::assets::Assets;
+use crate::components::{
+    CharacterControllerComponent, ColliderComponent, HealthComponent, InputComponent,
+    RigidBodyComponent, TransformComponent,
+};
+use crate::core::assets::{Assets, TextureId};
+use crate::core::audio::Audio;
+use crate::core::background::Background;
+use crate::core::camera_effects::{
+    CameraEffects, CameraPunchEvent, CameraShakeEvent, HitStopEvent, ScreenFlashEvent,
+};
+use crate::core::event_bus::EventBus;
 use crate::core::input::Input;
+use crate::core::inspector::Inspector;
 use crate::core::render::RenderQueue;
+use crate::core::scene::{Scene, SceneEntry, SceneFade, SceneRequest, SceneTransition};
+use crate::core::scheduler::Scheduler;
+use crate::core::time_dilation::TimeDilation;
 use crate::core::timer::Timer;
-use crate::entity::EntitySpawner;
+use crate::core::world_state::{ComponentRegistry, WorldState};
+use crate::entity::{Entity, EntityCommands, EntityId, EntitySpawner};
+use crate::math::{Transform2D, Vec2};
+use crate::physics::{CollisionLayers, ContactEvent, PhysicsWorld};
 
 pub struct Sdl2Context {
     _m_sdl2: Sdl,
@@ -65,15 +84,40 @@ impl Sdl2Context {
     }
 }
 
+// Physics runs at a fixed rate, independent of the frame rate
+pub const PHYSICS_DELTA_TIME: f32 = 1.0 / 60.0;
+// Drops physics time after a long frame instead of trying to catch up
+const MAX_PHYSICS_STEPS_PER_FRAME: u32 = 5;
+
 pub struct App {
     // SDL context must be declared first so it is destroyed last.
     // Objects below depend on SDL resources.
     m_sdl2_context: Sdl2Context,
     m_timer: Timer,
+    m_time_dilation: Rc<TimeDilation>,
     m_input: Rc<RefCell<Input>>,
+    m_event_bus: Rc<EventBus>,
+    m_scheduler: Rc<Scheduler>,
+    m_physics_world: Rc<PhysicsWorld>,
+    m_physics_time_accumulator: f32,
     m_assets: Assets,
+    m_audio: Rc<Audio>,
     m_render_queue: RenderQueue,
+    m_background: Background,
+    m_background_queue: RenderQueue,
+    // Top-left of the view in world space
+    m_camera_position: Vec2,
+    // Scales the world around the middle of the screen, 2 shows everything twice as big
+    m_camera_zoom: f32,
+    m_camera_effects: Rc<CameraEffects>,
     m_entity_spawner: EntitySpawner,
+    m_entity_commands: Rc<EntityCommands>,
+    m_component_registry: ComponentRegistry,
+    m_scene_stack: Vec<SceneEntry>,
+    m_scene_requests: VecDeque<(SceneRequest, SceneTransition)>,
+    m_pending_scene_request: Option<SceneRequest>,
+    m_scene_fade: SceneFade,
+    m_inspector: Inspector,
 }
 
 impl App {
@@ -94,17 +138,73 @@ impl App {
         );
 
         let input = Rc::new(RefCell::new(Input::new().unwrap()));
+        let inspector = Inspector::new(input.clone());
+        let event_bus = Rc::new(EventBus::new());
         let assets = Assets::new(sdl2_context.m_texture_creator.clone());
+        let audio = Rc::new(Audio::new());
         let render_queue = RenderQueue::new();
-        let entity_spawner = EntitySpawner::new();
+        let scheduler = Rc::new(Scheduler::new());
+        let physics_world = Rc::new(PhysicsWorld::new().unwrap_or_else(|err| {
+            eprintln!("Using the default collision layers: {}", err);
+            PhysicsWorld::with_collision_layers(CollisionLayers::from_default_config())
+        }));
+        let entity_commands = Rc::new(EntityCommands::new());
+        let entity_spawner = EntitySpawner::new(
+            event_bus.clone(),
+            scheduler.clone(),
+            entity_commands.clone(),
+        );
+
+        let camera_effects = Rc::new(CameraEffects::new());
+        subscribe_camera_effects(&event_bus, &camera_effects);
+
+        let mut component_registry = ComponentRegistry::new();
+        let input_copy = input.clone();
+        component_registry.register("InputComponent", move || {
+            InputComponent::new(input_copy.clone())
+        });
+        let physics_world_copy = physics_world.clone();
+        component_registry.register("ColliderComponent", move || {
+            ColliderComponent::new(physics_world_copy.clone(), Vec2::one())
+        });
+        let physics_world_copy = physics_world.clone();
+        component_registry.register("CharacterControllerComponent", move || {
+            CharacterControllerComponent::new(physics_world_copy.clone())
+        });
+        let physics_world_copy = physics_world.clone();
+        component_registry.register("RigidBodyComponent", move || {
+            RigidBodyComponent::new(physics_world_copy.clone())
+        });
+        let event_bus_copy = event_bus.clone();
+        component_registry.register("HealthComponent", move || {
+            HealthComponent::new(event_bus_copy.clone())
+        });
 
         Self {
             m_sdl2_context: sdl2_context,
             m_timer: timer,
+            m_time_dilation: Rc::new(TimeDilation::new()),
             m_input: input,
+            m_event_bus: event_bus,
+            m_scheduler: scheduler,
+            m_physics_world: physics_world,
+            m_physics_time_accumulator: 0.0,
             m_assets: assets,
+            m_audio: audio,
             m_render_queue: render_queue,
+            m_background: Background::new(),
+            m_background_queue: RenderQueue::new(),
+            m_camera_position: Vec2::zero(),
+            m_camera_zoom: 1.0,
+            m_camera_effects: camera_effects,
             m_entity_spawner: entity_spawner,
+            m_entity_commands: entity_commands,
+            m_component_registry: component_registry,
+            m_scene_stack: Vec::new(),
+            m_scene_requests: VecDeque::new(),
+            m_pending_scene_request: None,
+            m_scene_fade: SceneFade::None,
+            m_inspector: inspector,
         }
     }
 
@@ -117,30 +217,63 @@ impl App {
                     Event::Quit { .. } => {
                         break 'running;
                     }
+                    Event::MouseWheel { precise_y, .. } => {
+                        self.m_input.borrow_mut().add_mouse_wheel(precise_y);
+                    }
                     _ => {}
                 }
             }
 
-            self.m_entity_spawner.resolve_requests();
-
             let delta_time = self.m_timer.get_delta_time();
+
+            // Slow motion drives the time scale and the pitch of new sounds.
+            // Hit-stop freezes the gameplay on top of that, the audio keeps going.
+            self.m_time_dilation.tick(delta_time);
+            self.m_camera_effects.tick(delta_time);
+            let time_scale = self.m_time_dilation.get_time_scale();
+            self.m_timer
+                .set_time_scale(time_scale * self.m_camera_effects.get_time_scale());
+            self.m_audio.set_pitch(time_scale);
+
             let scaled_delta_time = delta_time * self.m_timer.get_time_scale();
 
+            self.resolve_scene_requests(delta_time);
+
+            self.m_entity_spawner.resolve_requests();
+
             // input.tick()
             self.m_input.borrow_mut().tick(
                 delta_time,
                 &self.m_sdl2_context.m_event_pump.keyboard_state(),
+                &self.m_sdl2_context.m_event_pump.mouse_state(),
             );
 
+            // inspector.tick()
+            self.m_inspector.tick(&mut self.m_entity_spawner);
+
+            // scheduler.tick()
+            self.m_scheduler
+                .tick(delta_time, self.m_timer.get_time_scale());
+
+            // scene.update()
+            self.update_top_scene(delta_time);
+
             // entities.tick()
             for entity in self.m_entity_spawner.entity_iter_mut() {
                 if entity.is_ticking() {
-                    entity.tick(scaled_delta_time);
+                    let time_ratio = self.m_time_dilation.get_entity_time_ratio(entity.get_id());
+                    entity.tick(scaled_delta_time * time_ratio);
                 }
             }
 
             // entities.physics_tick()
-            // TODO
+            self.physics_tick(scaled_delta_time);
+
+            // background.tick()
+            self.m_background.tick(scaled_delta_time);
+
+            // Deliver events queued during this frame
+            self.m_event_bus.flush();
 
             // entities.render_tick()
             for entity in self.m_entity_spawner.entity_iter_mut() {
@@ -157,50 +290,496 @@ impl App {
         &mut self.m_timer
     }
 
+    pub fn get_time_dilation(&mut self) -> Rc<TimeDilation> {
+        self.m_time_dilation.clone()
+    }
+
     pub fn get_input(&mut self) -> Rc<RefCell<Input>> {
         self.m_input.clone()
     }
 
+    pub fn get_event_bus(&mut self) -> Rc<EventBus> {
+        self.m_event_bus.clone()
+    }
+
+    pub fn get_scheduler(&mut self) -> Rc<Scheduler> {
+        self.m_scheduler.clone()
+    }
+
+    pub fn get_physics_world(&mut self) -> Rc<PhysicsWorld> {
+        self.m_physics_world.clone()
+    }
+
     pub fn get_assets(&mut self) -> &mut Assets {
         &mut self.m_assets
     }
 
+    pub fn get_audio(&mut self) -> Rc<Audio> {
+        self.m_audio.clone()
+    }
+
+    pub fn get_background(&mut self) -> &mut Background {
+        &mut self.m_background
+    }
+
+    pub fn get_camera_position(&self) -> Vec2 {
+        self.m_camera_position
+    }
+
+    pub fn set_camera_position(&mut self, position: Vec2) {
+        self.m_camera_position = position;
+    }
+
+    pub fn get_camera_zoom(&self) -> f32 {
+        self.m_camera_zoom
+    }
+
+    pub fn set_camera_zoom(&mut self, zoom: f32) {
+        self.m_camera_zoom = zoom;
+    }
+
+    pub fn get_camera_effects(&mut self) -> Rc<CameraEffects> {
+        self.m_camera_effects.clone()
+    }
+
+    // World space to screen space, with the camera effects applied.
+    // Zooms and rotates around the middle of the screen.
+    pub fn get_view_transform(&self) -> Transform2D {
+        let half_view_size = self.get_view_size() / 2.0;
+        let camera_position = self.m_camera_position + self.m_camera_effects.get_offset();
+        let zoom = self.m_camera_zoom * self.m_camera_effects.get_zoom();
+        let angle = self.m_camera_effects.get_angle();
+
+        Transform2D::new(
+            half_view_size - ((camera_position + half_view_size) * zoom).rotate(angle),
+            angle,
+            Vec2::from_xy(zoom, zoom),
+        )
+    }
+
+    // Size of the window's drawable area in pixels
+    pub fn get_view_size(&self) -> Vec2 {
+        let (width, height) = self.m_sdl2_context.m_canvas.output_size().unwrap_or((0, 0));
+        Vec2::from_xy(width as f32, height as f32)
+    }
+
     pub fn get_entity_spawner(&mut self) -> &mut EntitySpawner {
         &mut self.m_entity_spawner
     }
 
+    pub fn get_entity_commands(&mut self) -> Rc<EntityCommands> {
+        self.m_entity_commands.clone()
+    }
+
+    pub fn get_component_registry(&mut self) -> &mut ComponentRegistry {
+        &mut self.m_component_registry
+    }
+
+    pub fn save_world_state<P>(&self, path: P) -> Result<(), String>
+    where
+        P: AsRef<Path>,
+    {
+        self.m_entity_spawner
+            .save_world_state(&self.m_assets)?
+            .to_file(path)
+    }
+
+    // Replaces all entities with the ones from the save file.
+    // The restored entities are owned by the active scene.
+    pub fn load_world_state<P>(&mut self, path: P) -> Result<(), String>
+    where
+        P: AsRef<Path>,
+    {
+        let world_state = WorldState::from_file(path)?;
+        let (entity_ids, texture_ids) = self.m_entity_spawner.restore_world_state(
+            &world_state,
+            &self.m_component_registry,
+            &mut self.m_assets,
+        )?;
+
+        for entry in &mut self.m_scene_stack {
+            entry.entities.clear();
+        }
+
+        if let Some(entry) = self.m_scene_stack.last_mut() {
+            entry.entities.extend(entity_ids);
+            entry.textures.extend(texture_ids);
+        }
+
+        Ok(())
+    }
+
+    pub fn push_scene(&mut self, scene: Box<dyn Scene>, transition: SceneTransition) {
+        self.m_scene_requests
+            .push_back((SceneRequest::Push(scene), transition));
+    }
+
+    pub fn pop_scene(&mut self, transition: SceneTransition) {
+        self.m_scene_requests
+            .push_back((SceneRequest::Pop, transition));
+    }
+
+    pub fn replace_scene(&mut self, scene: Box<dyn Scene>, transition: SceneTransition) {
+        self.m_scene_requests
+            .push_back((SceneRequest::Replace(scene), transition));
+    }
+
+    pub fn get_scene_count(&self) -> usize {
+        self.m_scene_stack.len()
+    }
+
+    pub fn is_scene_transition_active(&self) -> bool {
+        self.m_scene_fade != SceneFade::None || !self.m_scene_requests.is_empty()
+    }
+
+    // Spawns an entity that is destroyed when the active scene exits
+    pub fn spawn_scene_entity(&mut self, entity: Box<Entity>) -> EntityId {
+        let entity_id = self.m_entity_spawner.spawn_entity(entity);
+        if let Some(entry) = self.m_scene_stack.last_mut() {
+            entry.entities.push(entity_id);
+        }
+
+        entity_id
+    }
+
+    // Loads a texture that is unloaded when the active scene exits
+    pub fn load_scene_texture<P>(&mut self, path: P) -> Result<TextureId, String>
+    where
+        P: AsRef<Path>,
+    {
+        let texture_id = self.m_assets.load_texture(path)?;
+        if let Some(entry) = self.m_scene_stack.last_mut() {
+            entry.textures.push(texture_id);
+        }
+
+        Ok(texture_id)
+    }
+
+    fn resolve_scene_requests(&mut self, delta_time: f32) {
+        match self.m_scene_fade {
+            SceneFade::Out { elapsed, duration } => {
+                let elapsed = elapsed + delta_time;
+                if elapsed < duration {
+                    self.m_scene_fade = SceneFade::Out { elapsed, duration };
+                    return;
+                }
+
+                // Fully faded out, swap the scenes while the screen is black
+                if let Some(request) = self.m_pending_scene_request.take() {
+                    self.apply_scene_request(request);
+                }
+
+                self.m_scene_fade = SceneFade::In {
+                    elapsed: 0.0,
+                    duration,
+                };
+                return;
+            }
+            SceneFade::In { elapsed, duration } => {
+                let elapsed = elapsed + delta_time;
+                if elapsed < duration {
+                    self.m_scene_fade = SceneFade::In { elapsed, duration };
+                    return;
+                }
+
+                self.m_scene_fade = SceneFade::None;
+            }
+            SceneFade::None => {}
+        }
+
+        while let Some((request, transition)) = self.m_scene_requests.pop_front() {
+            match transition {
+                SceneTransition::Fade(duration) if duration > 0.0 => {
+                    self.m_pending_scene_request = Some(request);
+                    self.m_scene_fade = SceneFade::Out {
+                        elapsed: 0.0,
+                        duration,
+                    };
+                    return;
+                }
+                _ => self.apply_scene_request(request),
+            }
+        }
+    }
+
+    fn apply_scene_request(&mut self, request: SceneRequest) {
+        match request {
+            SceneRequest::Push(scene) => {
+                self.pause_top_scene();
+                self.enter_scene(scene);
+            }
+            SceneRequest::Pop => {
+                self.exit_top_scene();
+                self.resume_top_scene();
+            }
+            SceneRequest::Replace(scene) => {
+                self.exit_top_scene();
+                self.enter_scene(scene);
+            }
+        }
+    }
+
+    fn enter_scene(&mut self, mut scene: Box<dyn Scene>) {
+        // Push the entry first, so the scene owns what it spawns and loads in on_enter()
+        self.m_scene_stack.push(SceneEntry::new());
+        scene.on_enter(self);
+        self.m_scene_stack.last_mut().unwrap().scene = Some(scene);
+    }
+
+    fn exit_top_scene(&mut self) {
+        let Some(mut scene) = self.take_top_scene() else {
+            return;
+        };
+
+        scene.on_exit(self);
+
+        let entry = self.m_scene_stack.pop().unwrap();
+        for entity_id in entry.entities {
+            self.m_entity_spawner.destroy_entity(entity_id);
+        }
+
+        for texture_id in entry.textures {
+            self.m_assets.unload_texture(texture_id);
+        }
+    }
+
+    fn pause_top_scene(&mut self) {
+        let Some(mut scene) = self.take_top_scene() else {
+            return;
+        };
+
+        scene.on_pause(self);
+
+        let entry = self.m_scene_stack.last_mut().unwrap();
+        for entity_id in &entry.entities {
+            self.m_entity_spawner.set_entity_ticking(*entity_id, false);
+        }
+
+        entry.scene = Some(scene);
+    }
+
+    fn resume_top_scene(&mut self) {
+        let Some(mut scene) = self.take_top_scene() else {
+            return;
+        };
+
+        let entry = self.m_scene_stack.last_mut().unwrap();
+        for entity_id in &entry.entities {
+            self.m_entity_spawner.set_entity_ticking(*entity_id, true);
+        }
+
+        scene.on_resume(self);
+        self.m_scene_stack.last_mut().unwrap().scene = Some(scene);
+    }
+
+    fn update_top_scene(&mut self, delta_time: f32) {
+        let Some(mut scene) = self.take_top_scene() else {
+            return;
+        };
+
+        scene.update(self, delta_time);
+        self.m_scene_stack.last_mut().unwrap().scene = Some(scene);
+    }
+
+    // The scene is moved out of its entry while it is being called,
+    // so it can receive `&mut App` and still spawn entities into its entry.
+    fn take_top_scene(&mut self) -> Option<Box<dyn Scene>> {
+        self.m_scene_stack
+            .last_mut()
+            .and_then(|entry| entry.scene.take())
+    }
+
+    fn physics_tick(&mut self, delta_time: f32) {
+        self.m_physics_time_accumulator += delta_time;
+
+        let mut steps = 0;
+        while self.m_physics_time_accumulator >= PHYSICS_DELTA_TIME {
+            if steps == MAX_PHYSICS_STEPS_PER_FRAME {
+                self.m_physics_time_accumulator = 0.0;
+                break;
+            }
+
+            // Entities exempt from slow motion take longer steps
+            for entity in self.m_entity_spawner.entity_iter_mut() {
+                if entity.is_ticking() {
+                    let time_ratio = self.m_time_dilation.get_entity_time_ratio(entity.get_id());
+                    entity.physics_tick(PHYSICS_DELTA_TIME * time_ratio);
+                }
+            }
+
+            // Bodies of paused entities hold still like the rest of them
+            let entity_spawner = &self.m_entity_spawner;
+            let moved = self
+                .m_physics_world
+                .step_bodies(PHYSICS_DELTA_TIME, |entity_id| {
+                    entity_spawner
+                        .get_entity(entity_id)
+                        .is_some_and(|entity| entity.is_ticking())
+                });
+            for (entity_id, motion) in moved {
+                if let Some(transform) = self
+                    .m_entity_spawner
+                    .get_entity_mut(entity_id)
+                    .and_then(|entity| entity.get_component_mut::<TransformComponent>())
+                {
+                    transform.set_position(transform.get_position() + motion);
+                }
+            }
+
+            for event in self.m_physics_world.update_contacts() {
+                self.dispatch_contact_event(event);
+            }
+
+            self.m_physics_time_accumulator -= PHYSICS_DELTA_TIME;
+            steps += 1;
+        }
+    }
+
+    // Entities destroyed in the meantime are skipped
+    fn dispatch_contact_event(&mut self, event: ContactEvent) {
+        match event {
+            ContactEvent::TriggerEnter { entity_id, other } => {
+                if let Some(entity) = self.m_entity_spawner.get_entity_mut(entity_id) {
+                    entity.on_trigger_enter(other);
+                }
+            }
+            ContactEvent::TriggerExit { entity_id, other } => {
+                if let Some(entity) = self.m_entity_spawner.get_entity_mut(entity_id) {
+                    entity.on_trigger_exit(other);
+                }
+            }
+            ContactEvent::Collision {
+                entity_id,
+                collision,
+            } => {
+                if let Some(entity) = self.m_entity_spawner.get_entity_mut(entity_id) {
+                    entity.on_collision(&collision);
+                }
+            }
+        }
+    }
+
     fn render_frame(&mut self) {
         self.m_sdl2_context
             .m_canvas
-            .set_draw_color(Color::RGB(14, 219, 248));
+            .set_draw_color(self.m_background.get_clear_color());
 
         self.m_sdl2_context.m_canvas.clear();
 
-        for render_data in self.m_render_queue.drain() {
-            if let Some(texture) = self.m_assets.get_texture(render_data.texture_id) {
-                let query = texture.query();
-                let destination = FRect::new(
-                    render_data.position.x,
-                    render_data.position.y,
-                    (query.width as f32) * render_data.scale.x,
-                    (query.height as f32) * render_data.scale.y,
-                );
-
-                if let Err(err) = self
-                    .m_sdl2_context
-                    .m_canvas
-                    .copy_f(texture, None, destination)
-                {
-                    eprintln!("Render error: {}", err);
-                }
-            } else {
-                eprintln!(
-                    "Texture with 'texture_id={}' not found",
-                    render_data.texture_id
-                );
+        // The background is already in screen space, the world goes through the camera
+        let view_transform = self.get_view_transform();
+        self.m_background.render(
+            self.m_camera_position,
+            self.get_view_size(),
+            &self.m_assets,
+            &mut self.m_background_queue,
+        );
+        draw_render_queue(
+            &mut self.m_sdl2_context.m_canvas,
+            &mut self.m_assets,
+            &mut self.m_background_queue,
+            &Transform2D::identity(),
+        );
+        draw_render_queue(
+            &mut self.m_sdl2_context.m_canvas,
+            &mut self.m_assets,
+            &mut self.m_render_queue,
+            &view_transform,
+        );
+
+        if let Some(flash_color) = self.m_camera_effects.get_flash_color() {
+            let canvas = &mut self.m_sdl2_context.m_canvas;
+            canvas.set_blend_mode(BlendMode::Blend);
+            canvas.set_draw_color(flash_color);
+            if let Err(err) = canvas.fill_rect(None) {
+                eprintln!("Render error: {}", err);
+            }
+        }
+
+        let fade_alpha = self.m_scene_fade.get_alpha();
+        if fade_alpha > 0.0 {
+            let canvas = &mut self.m_sdl2_context.m_canvas;
+            canvas.set_blend_mode(BlendMode::Blend);
+            canvas.set_draw_color(Color::RGBA(0, 0, 0, (fade_alpha * 255.0) as u8));
+            if let Err(err) = canvas.fill_rect(None) {
+                eprintln!("Render error: {}", err);
             }
         }
 
+        if let Err(err) = self
+            .m_inspector
+            .render(&mut self.m_sdl2_context.m_canvas, &self.m_entity_spawner)
+        {
+            eprintln!("Render error: {}", err);
+        }
+
         self.m_sdl2_context.m_canvas.present();
     }
 }
+
+fn draw_render_queue(
+    canvas: &mut Canvas<Window>,
+    assets: &mut Assets,
+    render_queue: &mut RenderQueue,
+    view_transform: &Transform2D,
+) {
+    for render_data in render_queue.drain() {
+        if let Some(texture) = assets.get_texture_mut(render_data.texture_id) {
+            texture.set_color_mod(render_data.tint.r, render_data.tint.g, render_data.tint.b);
+            texture.set_alpha_mod((render_data.alpha.clamp(0.0, 1.0) * 255.0) as u8);
+
+            let query = texture.query();
+            let size = Vec2::from_xy(
+                (query.width as f32) * render_data.scale.x,
+                (query.height as f32) * render_data.scale.y,
+            );
+            let center = if render_data.is_centered {
+                render_data.position
+            } else {
+                render_data.position + size / 2.0
+            };
+
+            // Textures are rotated around their own center, so only that is transformed
+            let center = view_transform.transform_point(center);
+            let size = size * view_transform.scale;
+            let position = center - size / 2.0;
+            let destination = FRect::new(position.x, position.y, size.x, size.y);
+
+            if let Err(err) = canvas.copy_ex_f(
+                texture,
+                None,
+                destination,
+                view_transform.rotation.to_degrees() as f64,
+                None,
+                render_data.flip_x,
+                false,
+            ) {
+                eprintln!("Render error: {}", err);
+            }
+        } else {
+            eprintln!(
+                "Texture with 'texture_id={}' not found",
+                render_data.texture_id
+            );
+        }
+    }
+}
+
+fn subscribe_camera_effects(event_bus: &EventBus, camera_effects: &Rc<CameraEffects>) {
+    let camera_effects_copy = camera_effects.clone();
+    event_bus.subscribe(move |event: &CameraShakeEvent| {
+        camera_effects_copy.add_trauma(event.trauma);
+    });
+    let camera_effects_copy = camera_effects.clone();
+    event_bus.subscribe(move |event: &HitStopEvent| {
+        camera_effects_copy.hit_stop(event.duration);
+    });
+    let camera_effects_copy = camera_effects.clone();
+    event_bus.subscribe(move |event: &CameraPunchEvent| {
+        camera_effects_copy.punch(event.offset, event.zoom);
+    });
+    let camera_effects_copy = camera_effects.clone();
+    event_bus.subscribe(move |event: &ScreenFlashEvent| {
+        camera_effects_copy.flash(event.color, event.duration);
+    });
+}
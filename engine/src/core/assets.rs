@@ -15,6 +15,7 @@ pub struct Assets {
     m_assets_root: PathBuf,
     m_texture_creator: Rc<TextureCreator<WindowContext>>,
     m_textures: HashMap<TextureId, Texture<'static>>,
+    m_texture_asset_paths: HashMap<TextureId, String>,
     m_next_texture_id: TextureId,
 }
 
@@ -24,6 +25,7 @@ impl Assets {
             m_assets_root: get_assets_root_path(),
             m_texture_creator: texture_creator,
             m_textures: HashMap::new(),
+            m_texture_asset_paths: HashMap::new(),
             m_next_texture_id: 0,
         }
     }
@@ -72,17 +74,49 @@ impl Assets {
         // Rust cannot express this cross-struct lifetime relationship, so we
         // manually uphold it here. Violating ANY of the above invariants will result
         // in undefined behavior (use-after-free of GPU resources).
-        let texture: Texture<'_> = self.m_texture_creator.load_texture(path)?;
+        let texture: Texture<'_> = self.m_texture_creator.load_texture(path.as_ref())?;
         let texture: Texture<'static> = unsafe { std::mem::transmute(texture) };
 
         let texture_id = self.m_next_texture_id;
         self.m_next_texture_id += 1;
         self.m_textures.insert(texture_id, texture);
 
+        // Remember the path relative to the assets root, so the texture can be referenced
+        // by a stable name (e.g. in save files) instead of its runtime id
+        if let Ok(relative_path) = path.as_ref().strip_prefix(&self.m_assets_root) {
+            let asset_path: Vec<String> = relative_path
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            self.m_texture_asset_paths
+                .insert(texture_id, asset_path.join("/"));
+        }
+
         Ok(texture_id)
     }
 
+    pub fn unload_texture(&mut self, id: TextureId) {
+        self.m_textures.remove(&id);
+        self.m_texture_asset_paths.remove(&id);
+    }
+
+    // Path relative to the assets root, e.g. "images/entities/player/idle/00.png"
+    pub fn get_texture_asset_path(&self, id: TextureId) -> Option<&str> {
+        self.m_texture_asset_paths.get(&id).map(String::as_str)
+    }
+
+    pub fn find_texture(&self, asset_path: &str) -> Option<TextureId> {
+        self.m_texture_asset_paths
+            .iter()
+            .find(|(_, path)| path.as_str() == asset_path)
+            .map(|(id, _)| *id)
+    }
+
     pub fn get_texture(&self, id: TextureId) -> Option<&Texture<'static>> {
         self.m_textures.get(&id)
     }
+
+    pub fn get_texture_mut(&mut self, id: TextureId) -> Option<&mut Texture<'static>> {
+        self.m_textures.get_mut(&id)
+    }
 }
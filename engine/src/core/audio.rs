@@ -0,0 +1,240 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use sdl2::audio::{AudioCVT, AudioFormat, AudioSpecWAV};
+use sdl2::mixer::{Channel, Chunk, DEFAULT_CHANNELS, DEFAULT_FORMAT, MAX_VOLUME};
+
+use crate::core::path_utils::get_assets_root_path;
+
+const FREQUENCY: i32 = 44_100;
+const CHUNK_SIZE: i32 = 1024;
+// Sounds playing at the same time, more are dropped
+const MIXING_CHANNELS: i32 = 16;
+// Pitched copies of a sound are made in steps of 1 / PITCH_STEPS and cached
+const PITCH_STEPS: f32 = 20.0;
+const MIN_PITCH: f32 = 0.5;
+const MAX_PITCH: f32 = 2.0;
+
+// Sound effects, referenced by their path relative to the assets root,
+// e.g. "sfx/shoot.wav", so components can save which sounds they play.
+//
+// Shared as `Rc<Audio>`. Without an audio device everything still works, silently.
+pub struct Audio {
+    m_assets_root: PathBuf,
+    m_is_open: bool,
+    m_sounds: RefCell<HashMap<String, Chunk>>,
+    // Decoded samples in the mixer's format, to make pitched copies of the sounds
+    m_samples: RefCell<HashMap<String, Vec<i16>>>,
+    m_pitched_sounds: RefCell<HashMap<(String, i32), Chunk>>,
+    m_frequency: i32,
+    m_channels: i32,
+    m_volume: Cell<f32>,
+    m_pitch: Cell<f32>,
+}
+
+impl Audio {
+    pub fn new() -> Self {
+        let is_open = match sdl2::mixer::open_audio(
+            FREQUENCY,
+            DEFAULT_FORMAT,
+            DEFAULT_CHANNELS,
+            CHUNK_SIZE,
+        ) {
+            Ok(()) => {
+                sdl2::mixer::allocate_channels(MIXING_CHANNELS);
+                true
+            }
+            Err(err) => {
+                eprintln!("Audio disabled: {}", err);
+                false
+            }
+        };
+
+        // Pitching needs 16-bit samples, other formats are played unpitched
+        let (frequency, channels) = match sdl2::mixer::query_spec() {
+            Ok((frequency, format, channels)) if is_open && format == DEFAULT_FORMAT => {
+                (frequency, channels)
+            }
+            _ => (0, 0),
+        };
+
+        Self {
+            m_assets_root: get_assets_root_path(),
+            m_is_open: is_open,
+            m_sounds: RefCell::new(HashMap::new()),
+            m_samples: RefCell::new(HashMap::new()),
+            m_pitched_sounds: RefCell::new(HashMap::new()),
+            m_frequency: frequency,
+            m_channels: channels,
+            m_volume: Cell::new(1.0),
+            m_pitch: Cell::new(1.0),
+        }
+    }
+
+    // Sounds are also loaded on first play, this avoids the hitch
+    pub fn load_sound(&self, asset_path: &str) -> Result<(), String> {
+        if !self.m_is_open || self.m_sounds.borrow().contains_key(asset_path) {
+            return Ok(());
+        }
+
+        let mut path = self.m_assets_root.clone();
+        path.extend(asset_path.split('/'));
+        let chunk = Chunk::from_file(&path)
+            .map_err(|err| format!("Failed to load '{}': {}", path.display(), err))?;
+
+        self.m_sounds
+            .borrow_mut()
+            .insert(asset_path.to_string(), chunk);
+
+        // Without the samples the sound is still played, just never pitched
+        if self.m_channels > 0 {
+            match self.decode_samples(&path) {
+                Ok(samples) => {
+                    self.m_samples
+                        .borrow_mut()
+                        .insert(asset_path.to_string(), samples);
+                }
+                Err(err) => eprintln!("Failed to decode '{}': {}", path.display(), err),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn play_sound(&self, asset_path: &str) {
+        if !self.m_is_open {
+            return;
+        }
+
+        if let Err(err) = self.load_sound(asset_path) {
+            eprintln!("{}", err);
+            return;
+        }
+
+        let pitch_step = (self.m_pitch.get() * PITCH_STEPS).round() as i32;
+        if pitch_step != PITCH_STEPS as i32 && self.play_pitched_sound(asset_path, pitch_step) {
+            return;
+        }
+
+        // Fails when all channels are busy, the sound is skipped then
+        if let Some(chunk) = self.m_sounds.borrow().get(asset_path) {
+            let _ = Channel::all().play(chunk, 0);
+        }
+    }
+
+    pub fn unload_sounds(&self) {
+        self.m_pitched_sounds.borrow_mut().clear();
+        self.m_samples.borrow_mut().clear();
+        self.m_sounds.borrow_mut().clear();
+    }
+
+    pub fn get_pitch(&self) -> f32 {
+        self.m_pitch.get()
+    }
+
+    // Speed and pitch of the sounds played from now on, 1 is normal, e.g. slowed down in slow motion.
+    // Clamped to [0.5, 2].
+    pub fn set_pitch(&self, pitch: f32) {
+        self.m_pitch.set(pitch.clamp(MIN_PITCH, MAX_PITCH));
+    }
+
+    pub fn get_volume(&self) -> f32 {
+        self.m_volume.get()
+    }
+
+    // [0, 1], for all sounds
+    pub fn set_volume(&self, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        self.m_volume.set(volume);
+        if self.m_is_open {
+            Channel::all().set_volume((volume * MAX_VOLUME as f32) as i32);
+        }
+    }
+}
+
+impl Audio {
+    fn decode_samples(&self, path: &Path) -> Result<Vec<i16>, String> {
+        let wav = AudioSpecWAV::load_wav(path)?;
+        let cvt = AudioCVT::new(
+            wav.format,
+            wav.channels,
+            wav.freq,
+            AudioFormat::s16_sys(),
+            self.m_channels as u8,
+            self.m_frequency,
+        )?;
+
+        let bytes = cvt.convert(wav.buffer().to_vec());
+        Ok(bytes
+            .chunks_exact(2)
+            .map(|sample| i16::from_ne_bytes([sample[0], sample[1]]))
+            .collect())
+    }
+
+    // Returns false when the sound has no samples to pitch
+    fn play_pitched_sound(&self, asset_path: &str, pitch_step: i32) -> bool {
+        let key = (asset_path.to_string(), pitch_step);
+        if !self.m_pitched_sounds.borrow().contains_key(&key) {
+            let samples = self.m_samples.borrow();
+            let Some(samples) = samples.get(asset_path) else {
+                return false;
+            };
+
+            let pitch = pitch_step as f32 / PITCH_STEPS;
+            let resampled = resample(samples, self.m_channels as usize, pitch);
+            match Chunk::from_raw_buffer(resampled.into_boxed_slice()) {
+                Ok(chunk) => {
+                    self.m_pitched_sounds
+                        .borrow_mut()
+                        .insert(key.clone(), chunk);
+                }
+                Err(err) => {
+                    eprintln!("Failed to pitch '{}': {}", asset_path, err);
+                    return false;
+                }
+            }
+        }
+
+        if let Some(chunk) = self.m_pitched_sounds.borrow().get(&key) {
+            let _ = Channel::all().play(chunk, 0);
+        }
+
+        true
+    }
+}
+
+// Plays the interleaved samples `pitch` times faster, with linear interpolation between frames
+fn resample(samples: &[i16], channels: usize, pitch: f32) -> Vec<i16> {
+    let frame_count = samples.len() / channels;
+    if frame_count == 0 {
+        return Vec::new();
+    }
+
+    let resampled_frame_count = (frame_count as f32 / pitch) as usize;
+    let mut resampled = Vec::with_capacity(resampled_frame_count * channels);
+    for frame in 0..resampled_frame_count {
+        let position = frame as f32 * pitch;
+        let index = (position as usize).min(frame_count - 1);
+        let next_index = (index + 1).min(frame_count - 1);
+        let t = position - index as f32;
+
+        for channel in 0..channels {
+            let a = samples[index * channels + channel] as f32;
+            let b = samples[next_index * channels + channel] as f32;
+            resampled.push((a + (b - a) * t) as i16);
+        }
+    }
+
+    resampled
+}
+
+impl Drop for Audio {
+    fn drop(&mut self) {
+        if self.m_is_open {
+            self.m_pitched_sounds.borrow_mut().clear();
+            self.m_sounds.borrow_mut().clear();
+            sdl2::mixer::close_audio();
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::tilemap::{TilePos, Tilemap};
+use crate::core::tileset::Tileset;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Neighbor {
+    Right,
+    Down,
+    Left,
+    Up,
+}
+
+impl Neighbor {
+    pub const ALL: [Neighbor; 4] = [
+        Neighbor::Right,
+        Neighbor::Down,
+        Neighbor::Left,
+        Neighbor::Up,
+    ];
+
+    // In tiles, y grows downwards
+    pub fn get_offset(&self) -> TilePos {
+        match self {
+            Neighbor::Right => (1, 0),
+            Neighbor::Down => (0, 1),
+            Neighbor::Left => (-1, 0),
+            Neighbor::Up => (0, -1),
+        }
+    }
+
+    fn get_bit(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
+// Picks `variant` when exactly these neighbors hold a tile of the same type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutotileRule {
+    pub neighbors: Vec<Neighbor>,
+    pub variant: i32,
+}
+
+impl AutotileRule {
+    fn get_mask(&self) -> u8 {
+        self.neighbors
+            .iter()
+            .fold(0, |mask, neighbor| mask | neighbor.get_bit())
+    }
+}
+
+// Which of the four neighbors hold a tile of the same type, one bit per Neighbor
+pub fn get_neighbor_mask(tilemap: &Tilemap, tile_pos: TilePos) -> u8 {
+    let Some(tile) = tilemap.get_tile(tile_pos) else {
+        return 0;
+    };
+
+    Neighbor::ALL
+        .iter()
+        .filter(|neighbor| {
+            let offset = neighbor.get_offset();
+            tilemap
+                .get_tile((tile_pos.0 + offset.0, tile_pos.1 + offset.1))
+                .is_some_and(|other| other.tile_type == tile.tile_type)
+        })
+        .fold(0, |mask, neighbor| mask | neighbor.get_bit())
+}
+
+// The variant the tile at `tile_pos` should have. None when there is no tile, its type
+// has no autotile rules or no rule matches its neighbors, e.g. a lone tile.
+pub fn get_autotile_variant(
+    tileset: &Tileset,
+    tilemap: &Tilemap,
+    tile_pos: TilePos,
+) -> Option<i32> {
+    let tile = tilemap.get_tile(tile_pos)?;
+    let rules = tileset.get_autotile_rules(&tile.tile_type)?;
+    let mask = get_neighbor_mask(tilemap, tile_pos);
+
+    rules
+        .iter()
+        .find(|rule| rule.get_mask() == mask)
+        .map(|rule| rule.variant)
+}
+
+// Fixes up the variants of every auto-tiled tile, e.g. after loading a hand edited map.
+// Returns how many tiles changed.
+pub fn autotile_tilemap(tileset: &Tileset, tilemap: &mut Tilemap) -> usize {
+    // Only the variants change, so all of them can be worked out before applying any
+    let changes: Vec<(TilePos, i32)> = tilemap
+        .tile_iter()
+        .filter_map(|(tile_pos, tile)| {
+            let variant = get_autotile_variant(tileset, tilemap, tile_pos)?;
+            (variant != tile.variant).then_some((tile_pos, variant))
+        })
+        .collect();
+
+    for (tile_pos, variant) in &changes {
+        let mut tile = tilemap.get_tile(*tile_pos).unwrap().clone();
+        tile.variant = *variant;
+        tilemap.set_tile(*tile_pos, tile);
+    }
+
+    changes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::tilemap::Tile;
+    use crate::math::Vec2;
+    use crate::test_fixtures::{load_map, load_tileset};
+
+    #[test]
+    fn rules_match_the_shipped_maps() {
+        let tileset = load_tileset();
+        for level_index in 0..3 {
+            let tilemap = load_map(level_index);
+            let mut autotiled_count = 0;
+            for (tile_pos, tile) in tilemap.tile_iter() {
+                if tileset.get_autotile_rules(&tile.tile_type).is_none() {
+                    continue;
+                }
+
+                let variant = get_autotile_variant(&tileset, &tilemap, tile_pos);
+                assert_eq!(
+                    variant,
+                    Some(tile.variant),
+                    "maps/{}.json {:?} {}",
+                    level_index,
+                    tile_pos,
+                    tile.tile_type
+                );
+                autotiled_count += 1;
+            }
+
+            assert!(autotiled_count > 0);
+        }
+    }
+
+    #[test]
+    fn fix_up_restores_the_shipped_variants() {
+        let tileset = load_tileset();
+        for level_index in 0..3 {
+            let original = load_map(level_index);
+            let mut tilemap = load_map(level_index);
+            let tile_positions: Vec<TilePos> = tilemap
+                .tile_iter()
+                .filter(|(_, tile)| tileset.get_autotile_rules(&tile.tile_type).is_some())
+                .map(|(tile_pos, _)| tile_pos)
+                .collect();
+            for tile_pos in &tile_positions {
+                let mut tile = tilemap.get_tile(*tile_pos).unwrap().clone();
+                tile.variant = 0;
+                tilemap.set_tile(*tile_pos, tile);
+            }
+
+            let changed_count = autotile_tilemap(&tileset, &mut tilemap);
+
+            assert!(changed_count > 0);
+            assert_eq!(tilemap.to_json(), original.to_json());
+        }
+    }
+
+    #[test]
+    fn fix_up_leaves_correct_maps_alone() {
+        let tileset = load_tileset();
+        let mut tilemap = load_map(0);
+        assert_eq!(autotile_tilemap(&tileset, &mut tilemap), 0);
+    }
+
+    #[test]
+    fn only_same_type_neighbors_connect() {
+        let tileset = load_tileset();
+        let mut tilemap = Tilemap::new(16);
+        for (tile_pos, tile_type) in [((0, 0), "grass"), ((1, 0), "grass"), ((0, 1), "stone")] {
+            tilemap.set_tile(
+                tile_pos,
+                Tile {
+                    tile_type: String::from(tile_type),
+                    variant: 0,
+                    pos: Vec2::zero(),
+                },
+            );
+        }
+
+        assert_eq!(
+            get_neighbor_mask(&tilemap, (0, 0)),
+            Neighbor::Right.get_bit()
+        );
+        // Right only has no rule
+        assert_eq!(get_autotile_variant(&tileset, &tilemap, (0, 0)), None);
+        assert_eq!(get_autotile_variant(&tileset, &tilemap, (0, 1)), None);
+    }
+}
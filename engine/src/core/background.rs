@@ -0,0 +1,286 @@
+use sdl2::pixels::Color;
+
+use crate::core::assets::{Assets, TextureId};
+use crate::core::render::{RenderData, RenderQueue};
+use crate::math::{Vec2, random_range};
+
+pub const DEFAULT_CLEAR_COLOR: Color = Color::RGB(14, 219, 248);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackgroundFill {
+    Stretch, // covers the whole screen, does not scroll
+    Tile,    // repeated in both directions, scrolls with the camera
+}
+
+// The scroll factor is how far the layer moves relative to the camera.
+// 0 stays fixed on screen, 1 moves with the world, values in between look far away.
+#[derive(Debug, Clone, Copy)]
+pub struct BackgroundLayer {
+    pub texture_id: TextureId,
+    pub fill: BackgroundFill,
+    pub scroll_factor: Vec2,
+    pub offset: Vec2,
+}
+
+impl BackgroundLayer {
+    pub fn stretched(texture_id: TextureId) -> Self {
+        Self {
+            texture_id,
+            fill: BackgroundFill::Stretch,
+            scroll_factor: Vec2::zero(),
+            offset: Vec2::zero(),
+        }
+    }
+
+    pub fn tiled(texture_id: TextureId, scroll_factor: Vec2) -> Self {
+        Self {
+            texture_id,
+            fill: BackgroundFill::Tile,
+            scroll_factor,
+            offset: Vec2::zero(),
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+struct Cloud {
+    texture_id: TextureId,
+    position: Vec2,
+    depth: f32, // used as the cloud's scroll factor
+    speed: f32, // pixels per second
+}
+
+// Clouds at random depths that drift sideways and wrap around the screen
+pub struct CloudField {
+    m_textures: Vec<TextureId>,
+    m_clouds: Vec<Cloud>,
+    m_min_depth: f32,
+    m_max_depth: f32,
+    m_min_speed: f32,
+    m_max_speed: f32,
+    m_cloud_count: usize,
+}
+
+impl CloudField {
+    pub fn new(textures: Vec<TextureId>) -> Self {
+        Self {
+            m_textures: textures,
+            m_clouds: Vec::new(),
+            m_min_depth: 0.2,
+            m_max_depth: 0.8,
+            m_min_speed: 2.0,
+            m_max_speed: 6.0,
+            m_cloud_count: 16,
+        }
+    }
+
+    pub fn with_depth(mut self, min_depth: f32, max_depth: f32) -> Self {
+        self.m_min_depth = min_depth;
+        self.m_max_depth = max_depth;
+        self
+    }
+
+    pub fn with_speed(mut self, min_speed: f32, max_speed: f32) -> Self {
+        self.m_min_speed = min_speed;
+        self.m_max_speed = max_speed;
+        self
+    }
+
+    pub fn with_cloud_count(mut self, cloud_count: usize) -> Self {
+        self.m_cloud_count = cloud_count;
+        self
+    }
+
+    pub fn get_cloud_count(&self) -> usize {
+        self.m_clouds.len()
+    }
+
+    // Scatters new clouds over an area of `size`, far ones first so near ones are drawn on top
+    pub fn generate(&mut self, size: Vec2) {
+        self.m_clouds.clear();
+        if self.m_textures.is_empty() {
+            return;
+        }
+
+        for _ in 0..self.m_cloud_count {
+            self.m_clouds.push(Cloud {
+                texture_id: self.m_textures[fastrand::usize(..self.m_textures.len())],
+                position: Vec2::from_xy(fastrand::f32() * size.x, fastrand::f32() * size.y),
+                depth: random_range(self.m_min_depth, self.m_max_depth),
+                speed: random_range(self.m_min_speed, self.m_max_speed),
+            });
+        }
+
+        self.m_clouds.sort_by(|a, b| a.depth.total_cmp(&b.depth));
+    }
+
+    fn tick(&mut self, delta_time: f32) {
+        for cloud in &mut self.m_clouds {
+            cloud.position.x += cloud.speed * delta_time;
+        }
+    }
+
+    fn render(
+        &self,
+        camera_position: Vec2,
+        view_size: Vec2,
+        assets: &Assets,
+        render_queue: &mut RenderQueue,
+    ) {
+        for cloud in &self.m_clouds {
+            let Some(size) = get_texture_size(assets, cloud.texture_id) else {
+                continue;
+            };
+
+            // A cloud leaving one side of the screen comes back in on the other
+            let position = cloud.position - camera_position * cloud.depth;
+            let position = Vec2::from_xy(
+                wrap(position.x, view_size.x + size.x) - size.x,
+                wrap(position.y, view_size.y + size.y) - size.y,
+            );
+
+            render_queue.enqueue(RenderData::new(
+                cloud.texture_id,
+                position,
+                position,
+                Vec2::one(),
+            ));
+        }
+    }
+}
+
+// Everything drawn behind the world: the clear color, image layers and clouds.
+// Layers are drawn in the order they were added, the clouds are drawn last.
+pub struct Background {
+    m_clear_color: Color,
+    m_layers: Vec<BackgroundLayer>,
+    m_cloud_field: Option<CloudField>,
+}
+
+impl Background {
+    pub fn new() -> Self {
+        Self {
+            m_clear_color: DEFAULT_CLEAR_COLOR,
+            m_layers: Vec::new(),
+            m_cloud_field: None,
+        }
+    }
+
+    pub fn get_clear_color(&self) -> Color {
+        self.m_clear_color
+    }
+
+    pub fn set_clear_color(&mut self, color: Color) {
+        self.m_clear_color = color;
+    }
+
+    pub fn add_layer(&mut self, layer: BackgroundLayer) {
+        self.m_layers.push(layer);
+    }
+
+    pub fn get_layers(&self) -> &[BackgroundLayer] {
+        &self.m_layers
+    }
+
+    pub fn set_cloud_field(&mut self, cloud_field: CloudField) {
+        self.m_cloud_field = Some(cloud_field);
+    }
+
+    pub fn get_cloud_field_mut(&mut self) -> Option<&mut CloudField> {
+        self.m_cloud_field.as_mut()
+    }
+
+    // Removes the layers and clouds and restores the default clear color
+    pub fn clear(&mut self) {
+        self.m_clear_color = DEFAULT_CLEAR_COLOR;
+        self.m_layers.clear();
+        self.m_cloud_field = None;
+    }
+
+    pub fn tick(&mut self, delta_time: f32) {
+        if let Some(cloud_field) = &mut self.m_cloud_field {
+            cloud_field.tick(delta_time);
+        }
+    }
+
+    // Enqueues the layers and clouds in screen space
+    pub fn render(
+        &self,
+        camera_position: Vec2,
+        view_size: Vec2,
+        assets: &Assets,
+        render_queue: &mut RenderQueue,
+    ) {
+        for layer in &self.m_layers {
+            let Some(size) = get_texture_size(assets, layer.texture_id) else {
+                continue;
+            };
+
+            match layer.fill {
+                BackgroundFill::Stretch => {
+                    render_queue.enqueue(RenderData::new(
+                        layer.texture_id,
+                        layer.offset,
+                        layer.offset,
+                        view_size / size,
+                    ));
+                }
+                BackgroundFill::Tile => {
+                    render_tiled_layer(layer, size, camera_position, view_size, render_queue);
+                }
+            }
+        }
+
+        if let Some(cloud_field) = &self.m_cloud_field {
+            cloud_field.render(camera_position, view_size, assets, render_queue);
+        }
+    }
+}
+
+fn render_tiled_layer(
+    layer: &BackgroundLayer,
+    size: Vec2,
+    camera_position: Vec2,
+    view_size: Vec2,
+    render_queue: &mut RenderQueue,
+) {
+    let scroll = layer.offset - camera_position * layer.scroll_factor;
+    let start = Vec2::from_xy(
+        wrap(scroll.x, size.x) - size.x,
+        wrap(scroll.y, size.y) - size.y,
+    );
+
+    let mut y = start.y;
+    while y < view_size.y {
+        let mut x = start.x;
+        while x < view_size.x {
+            let position = Vec2::from_xy(x, y);
+            render_queue.enqueue(RenderData::new(
+                layer.texture_id,
+                position,
+                position,
+                Vec2::one(),
+            ));
+            x += size.x;
+        }
+        y += size.y;
+    }
+}
+
+fn get_texture_size(assets: &Assets, texture_id: TextureId) -> Option<Vec2> {
+    let query = assets.get_texture(texture_id)?.query();
+    if query.width == 0 || query.height == 0 {
+        return None;
+    }
+
+    Some(Vec2::from_xy(query.width as f32, query.height as f32))
+}
+
+// `value` wrapped into [0, range)
+fn wrap(value: f32, range: f32) -> f32 {
+    value.rem_euclid(range)
+}
@@ -0,0 +1,219 @@
+use std::cell::Cell;
+
+use sdl2::pixels::Color;
+
+use crate::math::{Vec2, value_noise};
+
+// Events for triggering the effects from gameplay code, usually queued on the EventBus
+
+// Adds trauma in [0, 1], the shake grows with the square of the total trauma
+#[derive(Debug, Clone, Copy)]
+pub struct CameraShakeEvent {
+    pub trauma: f32,
+}
+
+// Freezes gameplay time for `duration` real seconds. UI, audio and the effects keep running.
+#[derive(Debug, Clone, Copy)]
+pub struct HitStopEvent {
+    pub duration: f32,
+}
+
+// Knocks the camera by `offset` pixels and zooms in by `zoom` (0.1 is 10%), both spring back
+#[derive(Debug, Clone, Copy)]
+pub struct CameraPunchEvent {
+    pub offset: Vec2,
+    pub zoom: f32,
+}
+
+// Covers the screen with `color`, fading out over `duration` seconds
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenFlashEvent {
+    pub color: Color,
+    pub duration: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Flash {
+    color: Color,
+    elapsed: f32,
+    duration: f32,
+}
+
+const SHAKE_SEED_X: u32 = 1;
+const SHAKE_SEED_Y: u32 = 2;
+const SHAKE_SEED_ANGLE: u32 = 3;
+
+// Screen shake, hit-stop, camera punches and flashes.
+// Ticked by the App in real time and applied to the camera in App::render_frame().
+//
+// Shared as `Rc<CameraEffects>` and uses interior mutability. The App subscribes it to
+// the effect events, it can also be called directly.
+pub struct CameraEffects {
+    m_trauma: Cell<f32>,
+    // Trauma lost per second
+    m_trauma_decay: Cell<f32>,
+    // Pixels and degrees at full trauma
+    m_max_shake_offset: Cell<f32>,
+    m_max_shake_angle: Cell<f32>,
+    // Noise bumps per second, higher is more jittery
+    m_shake_frequency: Cell<f32>,
+    m_noise_time: Cell<f32>,
+    m_hit_stop_time_left: Cell<f32>,
+    m_punch_offset: Cell<Vec2>,
+    m_punch_zoom: Cell<f32>,
+    // Fraction of the punch that recovers per second
+    m_punch_recovery: Cell<f32>,
+    m_flash: Cell<Option<Flash>>,
+}
+
+impl CameraEffects {
+    pub fn new() -> Self {
+        Self {
+            m_trauma: Cell::new(0.0),
+            m_trauma_decay: Cell::new(1.5),
+            m_max_shake_offset: Cell::new(8.0),
+            m_max_shake_angle: Cell::new(2.0),
+            m_shake_frequency: Cell::new(25.0),
+            m_noise_time: Cell::new(0.0),
+            m_hit_stop_time_left: Cell::new(0.0),
+            m_punch_offset: Cell::new(Vec2::zero()),
+            m_punch_zoom: Cell::new(0.0),
+            m_punch_recovery: Cell::new(10.0),
+            m_flash: Cell::new(None),
+        }
+    }
+
+    pub fn get_trauma(&self) -> f32 {
+        self.m_trauma.get()
+    }
+
+    pub fn add_trauma(&self, trauma: f32) {
+        self.m_trauma
+            .set((self.m_trauma.get() + trauma).clamp(0.0, 1.0));
+    }
+
+    pub fn set_trauma_decay(&self, trauma_decay: f32) {
+        self.m_trauma_decay.set(trauma_decay.max(0.0));
+    }
+
+    // Angle in degrees
+    pub fn set_max_shake(&self, offset: f32, angle: f32) {
+        self.m_max_shake_offset.set(offset);
+        self.m_max_shake_angle.set(angle);
+    }
+
+    pub fn set_shake_frequency(&self, shake_frequency: f32) {
+        self.m_shake_frequency.set(shake_frequency);
+    }
+
+    // Overlapping hit-stops don't add up, the longer one wins
+    pub fn hit_stop(&self, duration: f32) {
+        self.m_hit_stop_time_left
+            .set(self.m_hit_stop_time_left.get().max(duration));
+    }
+
+    pub fn is_hit_stopped(&self) -> bool {
+        self.m_hit_stop_time_left.get() > 0.0
+    }
+
+    pub fn punch(&self, offset: Vec2, zoom: f32) {
+        self.m_punch_offset.set(self.m_punch_offset.get() + offset);
+        self.m_punch_zoom.set(self.m_punch_zoom.get() + zoom);
+    }
+
+    pub fn set_punch_recovery(&self, punch_recovery: f32) {
+        self.m_punch_recovery.set(punch_recovery.max(0.0));
+    }
+
+    // Replaces the current flash
+    pub fn flash(&self, color: Color, duration: f32) {
+        self.m_flash.set(Some(Flash {
+            color,
+            elapsed: 0.0,
+            duration,
+        }));
+    }
+
+    // Clears all effects, e.g. when changing levels
+    pub fn reset(&self) {
+        self.m_trauma.set(0.0);
+        self.m_hit_stop_time_left.set(0.0);
+        self.m_punch_offset.set(Vec2::zero());
+        self.m_punch_zoom.set(0.0);
+        self.m_flash.set(None);
+    }
+
+    // Called by the App with the real (unscaled) delta time
+    pub fn tick(&self, delta_time: f32) {
+        let trauma = self.m_trauma.get() - self.m_trauma_decay.get() * delta_time;
+        self.m_trauma.set(trauma.max(0.0));
+        self.m_noise_time.set(self.m_noise_time.get() + delta_time);
+        self.m_hit_stop_time_left
+            .set((self.m_hit_stop_time_left.get() - delta_time).max(0.0));
+
+        let recovery = (1.0 - self.m_punch_recovery.get() * delta_time).max(0.0);
+        self.m_punch_offset
+            .set(self.m_punch_offset.get() * recovery);
+        self.m_punch_zoom.set(self.m_punch_zoom.get() * recovery);
+
+        if let Some(mut flash) = self.m_flash.get() {
+            flash.elapsed += delta_time;
+            if flash.elapsed < flash.duration {
+                self.m_flash.set(Some(flash));
+            } else {
+                self.m_flash.set(None);
+            }
+        }
+    }
+
+    // 0 while hit-stopped, multiplied into the Timer's time scale
+    pub fn get_time_scale(&self) -> f32 {
+        if self.is_hit_stopped() { 0.0 } else { 1.0 }
+    }
+
+    // Camera offset in pixels from the shake and the punches
+    pub fn get_offset(&self) -> Vec2 {
+        let shake = self.get_shake();
+        let time = self.m_noise_time.get() * self.m_shake_frequency.get();
+        let max_offset = self.m_max_shake_offset.get();
+        let shake_offset = Vec2::from_xy(
+            value_noise(SHAKE_SEED_X, time) * max_offset * shake,
+            value_noise(SHAKE_SEED_Y, time) * max_offset * shake,
+        );
+
+        shake_offset + self.m_punch_offset.get()
+    }
+
+    // Camera rotation in radians from the shake
+    pub fn get_angle(&self) -> f32 {
+        let time = self.m_noise_time.get() * self.m_shake_frequency.get();
+        let max_angle = self.m_max_shake_angle.get().to_radians();
+        value_noise(SHAKE_SEED_ANGLE, time) * max_angle * self.get_shake()
+    }
+
+    pub fn get_zoom(&self) -> f32 {
+        1.0 + self.m_punch_zoom.get()
+    }
+
+    // The flash color with its current opacity, None when there is no flash
+    pub fn get_flash_color(&self) -> Option<Color> {
+        let flash = self.m_flash.get()?;
+        let alpha = if flash.duration > 0.0 {
+            1.0 - (flash.elapsed / flash.duration).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        Some(Color::RGBA(
+            flash.color.r,
+            flash.color.g,
+            flash.color.b,
+            (flash.color.a as f32 * alpha) as u8,
+        ))
+    }
+
+    fn get_shake(&self) -> f32 {
+        let trauma = self.m_trauma.get();
+        trauma * trauma
+    }
+}
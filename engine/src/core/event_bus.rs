@@ -0,0 +1,243 @@
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::entity::{EntityId, INVALID_ENTITY_ID};
+
+pub type EventSubscriptionId = i32;
+pub const INVALID_EVENT_SUBSCRIPTION_ID: EventSubscriptionId = -1;
+
+type EventHandler = Rc<dyn Fn(&dyn Any)>;
+
+struct EventSubscription {
+    id: EventSubscriptionId,
+    owner_id: EntityId,
+    handler: EventHandler,
+}
+
+// Typed publish/subscribe bus. Any `'static` type can be used as an event.
+//
+// The bus is shared as `Rc<EventBus>` and uses interior mutability, so handlers
+// are free to subscribe, unsubscribe, emit or queue events while being dispatched.
+pub struct EventBus {
+    m_next_subscription_id: Cell<EventSubscriptionId>,
+    m_subscriptions: RefCell<HashMap<TypeId, Vec<EventSubscription>>>,
+    m_queued_events: RefCell<Vec<(TypeId, Box<dyn Any>)>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self {
+            m_next_subscription_id: Cell::new(0),
+            m_subscriptions: RefCell::new(HashMap::new()),
+            m_queued_events: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn subscribe<E, T>(&self, handler: T) -> EventSubscriptionId
+    where
+        E: 'static,
+        T: Fn(&E) + 'static,
+    {
+        self.add_subscription(INVALID_ENTITY_ID, handler)
+    }
+
+    // The subscription is removed automatically when the entity exits play
+    pub fn subscribe_for_entity<E, T>(&self, entity_id: EntityId, handler: T) -> EventSubscriptionId
+    where
+        E: 'static,
+        T: Fn(&E) + 'static,
+    {
+        self.add_subscription(entity_id, handler)
+    }
+
+    pub fn unsubscribe(&self, subscription_id: EventSubscriptionId) {
+        for subscriptions in self.m_subscriptions.borrow_mut().values_mut() {
+            subscriptions.retain(|sub| sub.id != subscription_id);
+        }
+    }
+
+    pub fn unsubscribe_entity(&self, entity_id: EntityId) {
+        if entity_id == INVALID_ENTITY_ID {
+            return;
+        }
+
+        for subscriptions in self.m_subscriptions.borrow_mut().values_mut() {
+            subscriptions.retain(|sub| sub.owner_id != entity_id);
+        }
+    }
+
+    // Delivers the event to all subscribers immediately
+    pub fn emit<E>(&self, event: E)
+    where
+        E: 'static,
+    {
+        self.dispatch(TypeId::of::<E>(), &event);
+    }
+
+    // Delivers the event when the bus is flushed at the end of the frame
+    pub fn queue<E>(&self, event: E)
+    where
+        E: 'static,
+    {
+        self.m_queued_events
+            .borrow_mut()
+            .push((TypeId::of::<E>(), Box::new(event)));
+    }
+
+    pub fn flush(&self) {
+        // Take memory, because a handler might queue new events.
+        // Those are delivered on the next flush.
+        let queued_events = std::mem::take(&mut *self.m_queued_events.borrow_mut());
+
+        for (type_id, event) in queued_events {
+            self.dispatch(type_id, event.as_ref());
+        }
+    }
+
+    fn add_subscription<E, T>(&self, owner_id: EntityId, handler: T) -> EventSubscriptionId
+    where
+        E: 'static,
+        T: Fn(&E) + 'static,
+    {
+        let subscription_id = self.m_next_subscription_id.get();
+        self.m_next_subscription_id.set(subscription_id + 1);
+
+        let handler: EventHandler = Rc::new(move |event: &dyn Any| {
+            if let Some(event) = event.downcast_ref::<E>() {
+                handler(event);
+            }
+        });
+
+        self.m_subscriptions
+            .borrow_mut()
+            .entry(TypeId::of::<E>())
+            .or_default()
+            .push(EventSubscription {
+                id: subscription_id,
+                owner_id,
+                handler,
+            });
+
+        subscription_id
+    }
+
+    fn dispatch(&self, type_id: TypeId, event: &dyn Any) {
+        // Copy the handlers, so the subscriptions are not borrowed while the handlers run
+        let handlers: Vec<(EventSubscriptionId, EventHandler)> =
+            match self.m_subscriptions.borrow().get(&type_id) {
+                Some(subscriptions) => subscriptions
+                    .iter()
+                    .map(|sub| (sub.id, sub.handler.clone()))
+                    .collect(),
+                None => return,
+            };
+
+        for (subscription_id, handler) in handlers {
+            // An earlier handler may have unsubscribed this one
+            if self.is_subscribed(type_id, subscription_id) {
+                handler(event);
+            }
+        }
+    }
+
+    fn is_subscribed(&self, type_id: TypeId, subscription_id: EventSubscriptionId) -> bool {
+        self.m_subscriptions
+            .borrow()
+            .get(&type_id)
+            .is_some_and(|subscriptions| subscriptions.iter().any(|sub| sub.id == subscription_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EnemyHit {
+        damage: i32,
+    }
+
+    struct LevelCompleted;
+
+    fn record_damage(event_bus: &EventBus) -> Rc<RefCell<Vec<i32>>> {
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let received_copy = received.clone();
+        event_bus.subscribe(move |event: &EnemyHit| {
+            received_copy.borrow_mut().push(event.damage);
+        });
+        received
+    }
+
+    #[test]
+    fn emit_delivers_immediately_to_its_type_only() {
+        let event_bus = EventBus::new();
+        let received = record_damage(&event_bus);
+
+        event_bus.emit(EnemyHit { damage: 3 });
+        event_bus.emit(LevelCompleted);
+
+        assert_eq!(*received.borrow(), vec![3]);
+    }
+
+    #[test]
+    fn queued_events_wait_for_flush() {
+        let event_bus = Rc::new(EventBus::new());
+        let received = record_damage(&event_bus);
+
+        // Events queued while flushing are delivered on the next flush
+        let event_bus_copy = event_bus.clone();
+        event_bus.subscribe(move |_: &LevelCompleted| {
+            event_bus_copy.queue(EnemyHit { damage: 2 });
+        });
+
+        event_bus.queue(EnemyHit { damage: 1 });
+        event_bus.queue(LevelCompleted);
+        assert!(received.borrow().is_empty());
+
+        event_bus.flush();
+        assert_eq!(*received.borrow(), vec![1]);
+
+        event_bus.flush();
+        assert_eq!(*received.borrow(), vec![1, 2]);
+    }
+
+    #[test]
+    fn entity_subscriptions_are_removed_with_the_entity() {
+        let event_bus = EventBus::new();
+        let count = Rc::new(Cell::new(0));
+        for entity_id in [1, 2] {
+            let count_copy = count.clone();
+            event_bus.subscribe_for_entity(entity_id, move |_: &LevelCompleted| {
+                count_copy.set(count_copy.get() + 1);
+            });
+        }
+
+        event_bus.unsubscribe_entity(1);
+        event_bus.emit(LevelCompleted);
+
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn handlers_unsubscribed_during_dispatch_are_skipped() {
+        let event_bus = Rc::new(EventBus::new());
+        let later_id = Rc::new(Cell::new(INVALID_EVENT_SUBSCRIPTION_ID));
+
+        let event_bus_copy = event_bus.clone();
+        let later_id_copy = later_id.clone();
+        event_bus.subscribe(move |_: &LevelCompleted| {
+            event_bus_copy.unsubscribe(later_id_copy.get());
+        });
+
+        let has_run = Rc::new(Cell::new(false));
+        let has_run_copy = has_run.clone();
+        later_id.set(event_bus.subscribe(move |_: &LevelCompleted| {
+            has_run_copy.set(true);
+        }));
+
+        event_bus.emit(LevelCompleted);
+
+        assert!(!has_run.get());
+    }
+}
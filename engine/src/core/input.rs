@@ -2,9 +2,11 @@ use std::collections::HashMap;
 use std::path::Path;
 
 use sdl2::keyboard::Scancode;
+use sdl2::mouse::{MouseButton, MouseState};
 use serde::Deserialize;
 
 use crate::core::path_utils::get_input_config_path;
+use crate::math::Vec2;
 
 pub type InputEventHandlerId = i32;
 pub const INVALID_INPUT_EVENT_HANDLER_ID: InputEventHandlerId = -1;
@@ -33,6 +35,14 @@ pub struct Input {
     m_relevant_keys: Vec<Scancode>,
     m_pressed_keys_this_frame: Vec<bool>,
     m_pressed_keys_last_frame: Vec<bool>,
+
+    // In window pixels
+    m_mouse_position: Vec2,
+    // Left, middle and right
+    m_mouse_buttons_this_frame: [bool; 3],
+    m_mouse_buttons_last_frame: [bool; 3],
+    m_mouse_wheel: f32,
+    m_pending_mouse_wheel: f32,
 }
 
 impl Input {
@@ -55,11 +65,22 @@ impl Input {
             m_relevant_keys: relevant_keys,
             m_pressed_keys_this_frame: vec![false; num_scancodes],
             m_pressed_keys_last_frame: vec![false; num_scancodes],
+            m_mouse_position: Vec2::zero(),
+            m_mouse_buttons_this_frame: [false; 3],
+            m_mouse_buttons_last_frame: [false; 3],
+            m_mouse_wheel: 0.0,
+            m_pending_mouse_wheel: 0.0,
         })
     }
 
-    pub fn tick(&mut self, delta_time: f32, keyboard_state: &sdl2::keyboard::KeyboardState) {
+    pub fn tick(
+        &mut self,
+        delta_time: f32,
+        keyboard_state: &sdl2::keyboard::KeyboardState,
+        mouse_state: &MouseState,
+    ) {
         self.update_pressed_keys(keyboard_state);
+        self.update_mouse(mouse_state);
 
         // Dispatch action events
         for (action, keys) in &self.m_input_mappings.actions {
@@ -133,6 +154,31 @@ impl Input {
         }
     }
 
+    pub fn get_mouse_position(&self) -> Vec2 {
+        self.m_mouse_position
+    }
+
+    pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        get_mouse_button_index(button).is_some_and(|index| self.m_mouse_buttons_this_frame[index])
+    }
+
+    // Went down this frame
+    pub fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        get_mouse_button_index(button).is_some_and(|index| {
+            self.m_mouse_buttons_this_frame[index] && !self.m_mouse_buttons_last_frame[index]
+        })
+    }
+
+    // Wheel notches scrolled this frame, positive away from the user
+    pub fn get_mouse_wheel(&self) -> f32 {
+        self.m_mouse_wheel
+    }
+
+    // Wheel motion only arrives as window events, the App forwards them here
+    pub fn add_mouse_wheel(&mut self, amount: f32) {
+        self.m_pending_mouse_wheel += amount;
+    }
+
     pub fn add_input_event_handler<T>(&mut self, handler: T) -> InputEventHandlerId
     where
         T: Fn(&InputEvent) + 'static,
@@ -162,6 +208,26 @@ impl Input {
             self.m_pressed_keys_this_frame[key_index] = keyboard_state.is_scancode_pressed(*key);
         }
     }
+
+    fn update_mouse(&mut self, mouse_state: &MouseState) {
+        self.m_mouse_position = Vec2::from_xy(mouse_state.x() as f32, mouse_state.y() as f32);
+        self.m_mouse_buttons_last_frame = self.m_mouse_buttons_this_frame;
+        self.m_mouse_buttons_this_frame = [
+            mouse_state.left(),
+            mouse_state.middle(),
+            mouse_state.right(),
+        ];
+        self.m_mouse_wheel = std::mem::take(&mut self.m_pending_mouse_wheel);
+    }
+}
+
+fn get_mouse_button_index(button: MouseButton) -> Option<usize> {
+    match button {
+        MouseButton::Left => Some(0),
+        MouseButton::Middle => Some(1),
+        MouseButton::Right => Some(2),
+        _ => None,
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,299 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Canvas};
+use sdl2::video::Window;
+
+use crate::core::debug_text::{draw_text, get_line_height, get_text_width};
+use crate::core::input::{Input, InputEventHandlerId, InputEventType};
+use crate::core::reflect::FieldValue;
+use crate::entity::{Entity, EntitySpawner};
+
+const TEXT_SCALE: i32 = 2;
+const PANEL_PADDING: i32 = 8;
+
+// A single editable line in the inspector. Vec2 fields are split into x and y rows.
+struct InspectorRow {
+    component_index: usize,
+    field_name: &'static str,
+    value: FieldValue,
+    axis: Option<usize>,
+}
+
+impl InspectorRow {
+    fn get_label(&self) -> String {
+        match self.axis {
+            Some(0) => format!("{}.x", self.field_name),
+            Some(_) => format!("{}.y", self.field_name),
+            None => self.field_name.to_string(),
+        }
+    }
+
+    fn get_display_value(&self) -> String {
+        match (&self.value, self.axis) {
+            (FieldValue::Vec2(v), Some(0)) => format!("{:.2}", v.x),
+            (FieldValue::Vec2(v), Some(_)) => format!("{:.2}", v.y),
+            (value, _) => value.to_string(),
+        }
+    }
+
+    // Returns the new value, or None if the field can't be edited
+    fn step(&self, direction: f32) -> Option<FieldValue> {
+        let step_f32 = |value: f32| value + (value.abs() * 0.1).max(0.1) * direction;
+
+        match &self.value {
+            FieldValue::Bool(value) => Some(FieldValue::Bool(!value)),
+            FieldValue::I32(value) => Some(FieldValue::I32(value + direction as i32)),
+            FieldValue::F32(value) => Some(FieldValue::F32(step_f32(*value))),
+            FieldValue::Vec2(value) => {
+                let mut value = *value;
+                if self.axis == Some(0) {
+                    value.x = step_f32(value.x);
+                } else {
+                    value.y = step_f32(value.y);
+                }
+
+                Some(FieldValue::Vec2(value))
+            }
+            FieldValue::String(_) => None,
+        }
+    }
+}
+
+// Debug overlay that lists the entities in play and lets you view and edit
+// the #[reflect] fields of their components at runtime.
+pub struct Inspector {
+    m_input: Rc<RefCell<Input>>,
+    m_input_event_handler_id: InputEventHandlerId,
+    m_pressed_actions: Rc<RefCell<Vec<String>>>,
+    m_is_visible: bool,
+    m_selected_entity: usize,
+    m_selected_row: usize,
+}
+
+impl Inspector {
+    pub fn new(input: Rc<RefCell<Input>>) -> Self {
+        let pressed_actions: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+        let pressed_actions_copy = pressed_actions.clone();
+        let input_event_handler_id = input.borrow_mut().add_input_event_handler(move |event| {
+            if event.ev_type == InputEventType::Pressed && event.ev_name.starts_with("inspector_") {
+                pressed_actions_copy
+                    .borrow_mut()
+                    .push(event.ev_name.to_string());
+            }
+        });
+
+        Self {
+            m_input: input,
+            m_input_event_handler_id: input_event_handler_id,
+            m_pressed_actions: pressed_actions,
+            m_is_visible: false,
+            m_selected_entity: 0,
+            m_selected_row: 0,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.m_is_visible
+    }
+
+    pub fn tick(&mut self, entity_spawner: &mut EntitySpawner) {
+        let pressed_actions = std::mem::take(&mut *self.m_pressed_actions.borrow_mut());
+        for action in pressed_actions {
+            if action == "inspector_toggle" {
+                self.m_is_visible = !self.m_is_visible;
+                continue;
+            }
+
+            if !self.m_is_visible {
+                continue;
+            }
+
+            match action.as_str() {
+                "inspector_prev_entity" => {
+                    self.m_selected_entity = self.m_selected_entity.saturating_sub(1);
+                    self.m_selected_row = 0;
+                }
+                "inspector_next_entity" => {
+                    self.m_selected_entity += 1;
+                    self.m_selected_row = 0;
+                }
+                "inspector_prev_field" => {
+                    self.m_selected_row = self.m_selected_row.saturating_sub(1);
+                }
+                "inspector_next_field" => {
+                    self.m_selected_row += 1;
+                }
+                "inspector_decrease" => self.edit_selected_row(entity_spawner, -1.0),
+                "inspector_increase" => self.edit_selected_row(entity_spawner, 1.0),
+                _ => {}
+            }
+        }
+
+        // Keep the selection valid when entities are spawned or destroyed
+        let entity_count = entity_spawner.entity_iter().count();
+        self.m_selected_entity = self.m_selected_entity.min(entity_count.saturating_sub(1));
+
+        let row_count = entity_spawner
+            .entity_iter()
+            .nth(self.m_selected_entity)
+            .map_or(0, |entity| get_rows(entity).len());
+        self.m_selected_row = self.m_selected_row.min(row_count.saturating_sub(1));
+    }
+
+    pub fn render(
+        &self,
+        canvas: &mut Canvas<Window>,
+        entity_spawner: &EntitySpawner,
+    ) -> Result<(), String> {
+        if !self.m_is_visible {
+            return Ok(());
+        }
+
+        let mut lines: Vec<(String, Color)> = Vec::new();
+        let header_color = Color::RGB(255, 220, 80);
+        let text_color = Color::RGB(230, 230, 230);
+        let selected_color = Color::RGB(120, 255, 120);
+
+        lines.push((
+            "INSPECTOR  F1 HIDE  PGUP/PGDN ENTITY  [ ] FIELD  - = EDIT".to_string(),
+            header_color,
+        ));
+
+        let entity_count = entity_spawner.entity_iter().count();
+        match entity_spawner.entity_iter().nth(self.m_selected_entity) {
+            Some(entity) => {
+                let tags: Vec<&str> = entity.get_tags().collect();
+                lines.push((
+                    format!(
+                        "ENTITY {}/{}  ID {}  NAME {}  TAGS {}",
+                        self.m_selected_entity + 1,
+                        entity_count,
+                        entity.get_id(),
+                        entity.get_name().unwrap_or("-"),
+                        if tags.is_empty() {
+                            "-".to_string()
+                        } else {
+                            tags.join(",")
+                        }
+                    ),
+                    header_color,
+                ));
+
+                let rows = get_rows(entity);
+                for (comp_index, comp) in entity.component_iter().enumerate() {
+                    lines.push((comp.get_type_name().to_string(), header_color));
+
+                    for (row_index, row) in rows.iter().enumerate() {
+                        if row.component_index != comp_index {
+                            continue;
+                        }
+
+                        let is_selected = row_index == self.m_selected_row;
+                        lines.push((
+                            format!(
+                                "{} {} = {}",
+                                if is_selected { ">" } else { " " },
+                                row.get_label(),
+                                row.get_display_value()
+                            ),
+                            if is_selected {
+                                selected_color
+                            } else {
+                                text_color
+                            },
+                        ));
+                    }
+                }
+
+                if entity.component_iter().next().is_none() {
+                    lines.push(("NO COMPONENTS".to_string(), text_color));
+                }
+            }
+            None => {
+                lines.push(("NO ENTITIES".to_string(), text_color));
+            }
+        }
+
+        // Background panel
+        let line_height = get_line_height(TEXT_SCALE);
+        let panel_width = lines
+            .iter()
+            .map(|(line, _)| get_text_width(line, TEXT_SCALE))
+            .max()
+            .unwrap_or(0)
+            + PANEL_PADDING * 2;
+        let panel_height = (lines.len() as i32) * line_height + PANEL_PADDING * 2;
+
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(Color::RGBA(0, 0, 0, 180));
+        canvas.fill_rect(Rect::new(0, 0, panel_width as u32, panel_height as u32))?;
+
+        for (index, (line, color)) in lines.iter().enumerate() {
+            draw_text(
+                canvas,
+                line,
+                PANEL_PADDING,
+                PANEL_PADDING + (index as i32) * line_height,
+                TEXT_SCALE,
+                *color,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn edit_selected_row(&self, entity_spawner: &mut EntitySpawner, direction: f32) {
+        let Some(entity) = entity_spawner.entity_iter_mut().nth(self.m_selected_entity) else {
+            return;
+        };
+
+        let rows = get_rows(entity);
+        let Some(row) = rows.get(self.m_selected_row) else {
+            return;
+        };
+
+        let Some(new_value) = row.step(direction) else {
+            return;
+        };
+
+        if let Some(comp) = entity.component_iter_mut().nth(row.component_index)
+            && let Err(err) = comp.set_field(row.field_name, new_value)
+        {
+            eprintln!("Inspector error: {}", err);
+        }
+    }
+}
+
+impl Drop for Inspector {
+    fn drop(&mut self) {
+        self.m_input
+            .borrow_mut()
+            .remove_input_event_handler(self.m_input_event_handler_id);
+    }
+}
+
+fn get_rows(entity: &Entity) -> Vec<InspectorRow> {
+    let mut rows: Vec<InspectorRow> = Vec::new();
+    for (comp_index, comp) in entity.component_iter().enumerate() {
+        for (field_name, value) in comp.get_fields() {
+            let axes: &[Option<usize>] = match value {
+                FieldValue::Vec2(_) => &[Some(0), Some(1)],
+                _ => &[None],
+            };
+
+            for axis in axes {
+                rows.push(InspectorRow {
+                    component_index: comp_index,
+                    field_name,
+                    value: value.clone(),
+                    axis: *axis,
+                });
+            }
+        }
+    }
+
+    rows
+}
@@ -1,6 +1,20 @@
 pub mod app;
 pub mod assets;
+pub mod audio;
+pub mod autotile;
+pub mod background;
+pub mod camera_effects;
+pub mod debug_text;
+pub mod event_bus;
 pub mod input;
+pub mod inspector;
 pub mod path_utils;
+pub mod reflect;
 pub mod render;
+pub mod scene;
+pub mod scheduler;
+pub mod tilemap;
+pub mod tileset;
+pub mod time_dilation;
 pub mod timer;
+pub mod world_state;
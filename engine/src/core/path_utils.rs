@@ -49,3 +49,17 @@ pub fn get_assets_root_path() -> PathBuf {
 
     assets_root_path
 }
+
+pub fn get_save_dir_path() -> PathBuf {
+    get_root_path().join("saves")
+}
+
+pub fn get_collision_layers_config_path() -> PathBuf {
+    let root_path = get_root_path();
+
+    if cfg!(debug_assertions) {
+        root_path.join("engine/config/collision_layers.json")
+    } else {
+        root_path.join("config/collision_layers.json")
+    }
+}
@@ -0,0 +1,114 @@
+use crate::math::Vec2;
+
+// A field value exposed by the reflection layer generated for #[reflect] fields
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Bool(bool),
+    I32(i32),
+    F32(f32),
+    Vec2(Vec2),
+    String(String),
+}
+
+impl FieldValue {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            FieldValue::Bool(_) => "bool",
+            FieldValue::I32(_) => "i32",
+            FieldValue::F32(_) => "f32",
+            FieldValue::Vec2(_) => "Vec2",
+            FieldValue::String(_) => "String",
+        }
+    }
+}
+
+impl std::fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldValue::Bool(value) => write!(f, "{}", value),
+            FieldValue::I32(value) => write!(f, "{}", value),
+            FieldValue::F32(value) => write!(f, "{:.2}", value),
+            FieldValue::Vec2(value) => write!(f, "({:.2}, {:.2})", value.x, value.y),
+            FieldValue::String(value) => write!(f, "'{}'", value),
+        }
+    }
+}
+
+// Types that can be used in #[reflect] fields
+pub trait Reflect: Sized {
+    fn to_field_value(&self) -> FieldValue;
+    fn from_field_value(value: FieldValue) -> Result<Self, String>;
+}
+
+fn type_mismatch<T>(expected: &str, value: &FieldValue) -> Result<T, String> {
+    Err(format!(
+        "Expected a '{}' value, got '{}'",
+        expected,
+        value.type_name()
+    ))
+}
+
+impl Reflect for bool {
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::Bool(*self)
+    }
+
+    fn from_field_value(value: FieldValue) -> Result<Self, String> {
+        match value {
+            FieldValue::Bool(value) => Ok(value),
+            other => type_mismatch("bool", &other),
+        }
+    }
+}
+
+impl Reflect for i32 {
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::I32(*self)
+    }
+
+    fn from_field_value(value: FieldValue) -> Result<Self, String> {
+        match value {
+            FieldValue::I32(value) => Ok(value),
+            other => type_mismatch("i32", &other),
+        }
+    }
+}
+
+impl Reflect for f32 {
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::F32(*self)
+    }
+
+    fn from_field_value(value: FieldValue) -> Result<Self, String> {
+        match value {
+            FieldValue::F32(value) => Ok(value),
+            other => type_mismatch("f32", &other),
+        }
+    }
+}
+
+impl Reflect for Vec2 {
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::Vec2(*self)
+    }
+
+    fn from_field_value(value: FieldValue) -> Result<Self, String> {
+        match value {
+            FieldValue::Vec2(value) => Ok(value),
+            other => type_mismatch("Vec2", &other),
+        }
+    }
+}
+
+impl Reflect for String {
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::String(self.clone())
+    }
+
+    fn from_field_value(value: FieldValue) -> Result<Self, String> {
+        match value {
+            FieldValue::String(value) => Ok(value),
+            other => type_mismatch("String", &other),
+        }
+    }
+}
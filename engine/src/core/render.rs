@@ -1,5 +1,7 @@
 use std::collections::{VecDeque, vec_deque::Drain};
 
+use sdl2::pixels::Color;
+
 use crate::core::assets::TextureId;
 use crate::math::Vec2;
 
@@ -8,6 +10,11 @@ pub struct RenderData {
     pub position: Vec2,
     pub prev_position: Vec2, // needed for physics interpolation
     pub scale: Vec2,
+    pub tint: Color,
+    pub alpha: f32, // [0, 1]
+    pub flip_x: bool,
+    // The position is the center of the texture instead of its top-left
+    pub is_centered: bool,
 }
 
 impl RenderData {
@@ -17,8 +24,33 @@ impl RenderData {
             position: position,
             prev_position: prev_position,
             scale: scale,
+            tint: Color::WHITE,
+            alpha: 1.0,
+            flip_x: false,
+            is_centered: false,
         }
     }
+
+    pub fn with_tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    // Mirrored horizontally, e.g. a character facing left
+    pub fn with_flip_x(mut self, flip_x: bool) -> Self {
+        self.flip_x = flip_x;
+        self
+    }
+
+    pub fn centered(mut self) -> Self {
+        self.is_centered = true;
+        self
+    }
 }
 
 pub struct RenderQueue {
@@ -0,0 +1,65 @@
+use crate::core::app::App;
+use crate::core::assets::TextureId;
+use crate::entity::EntityId;
+
+// A scene is a game state such as a title screen, a level, a pause menu or a game over screen.
+// Scenes live on a stack owned by the App. Only the top scene is updated.
+// Entities and textures created through App::spawn_scene_entity() and App::load_scene_texture()
+// are owned by the active scene and released when the scene exits.
+#[allow(unused_variables)]
+pub trait Scene: 'static {
+    fn on_enter(&mut self, app: &mut App) {}
+    fn on_exit(&mut self, app: &mut App) {}
+
+    // Called when another scene is pushed on top of this one and when it is popped again
+    fn on_pause(&mut self, app: &mut App) {}
+    fn on_resume(&mut self, app: &mut App) {}
+
+    fn update(&mut self, app: &mut App, delta_time: f32) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SceneTransition {
+    Cut,
+    Fade(f32), // seconds for each of the fade-out and fade-in
+}
+
+pub enum SceneRequest {
+    Push(Box<dyn Scene>),
+    Pop,
+    Replace(Box<dyn Scene>),
+}
+
+pub(crate) struct SceneEntry {
+    pub scene: Option<Box<dyn Scene>>,
+    pub entities: Vec<EntityId>,
+    pub textures: Vec<TextureId>,
+}
+
+impl SceneEntry {
+    pub fn new() -> Self {
+        Self {
+            scene: None,
+            entities: Vec::new(),
+            textures: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SceneFade {
+    None,
+    Out { elapsed: f32, duration: f32 },
+    In { elapsed: f32, duration: f32 },
+}
+
+impl SceneFade {
+    // Opacity of the fade overlay in [0, 1]
+    pub fn get_alpha(&self) -> f32 {
+        match *self {
+            SceneFade::None => 0.0,
+            SceneFade::Out { elapsed, duration } => (elapsed / duration).clamp(0.0, 1.0),
+            SceneFade::In { elapsed, duration } => (1.0 - elapsed / duration).clamp(0.0, 1.0),
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+
+use crate::entity::{EntityId, INVALID_ENTITY_ID};
+
+pub type ScheduleHandle = i32;
+pub const INVALID_SCHEDULE_HANDLE: ScheduleHandle = -1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeMode {
+    Scaled,   // follows Timer::get_time_scale(), e.g. gameplay cooldowns
+    Unscaled, // real time, e.g. UI or hit-stop recovery
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScheduleOptions {
+    pub owner_id: EntityId,
+    pub time_mode: TimeMode,
+}
+
+impl ScheduleOptions {
+    // Cancelled automatically when the entity is destroyed
+    pub fn for_entity(entity_id: EntityId) -> Self {
+        Self {
+            owner_id: entity_id,
+            ..Self::default()
+        }
+    }
+
+    pub fn unscaled(mut self) -> Self {
+        self.time_mode = TimeMode::Unscaled;
+        self
+    }
+}
+
+impl Default for ScheduleOptions {
+    fn default() -> Self {
+        Self {
+            owner_id: INVALID_ENTITY_ID,
+            time_mode: TimeMode::Scaled,
+        }
+    }
+}
+
+struct ScheduledAction {
+    handle: ScheduleHandle,
+    options: ScheduleOptions,
+    remaining: f32,
+    interval: Option<f32>,
+    callback: Box<dyn FnMut()>,
+}
+
+// Runs callbacks after a delay or at a fixed interval.
+//
+// The scheduler is shared as `Rc<Scheduler>` and uses interior mutability, so callbacks
+// are free to schedule or cancel actions (including themselves) while they run.
+pub struct Scheduler {
+    m_next_handle: Cell<ScheduleHandle>,
+    m_actions: RefCell<Vec<ScheduledAction>>,
+    // Owner and remaining time of the actions taken out of the list while they tick
+    m_ticking_actions: RefCell<HashMap<ScheduleHandle, (EntityId, f32)>>,
+    m_cancelled: RefCell<HashSet<ScheduleHandle>>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            m_next_handle: Cell::new(0),
+            m_actions: RefCell::new(Vec::new()),
+            m_ticking_actions: RefCell::new(HashMap::new()),
+            m_cancelled: RefCell::new(HashSet::new()),
+        }
+    }
+
+    pub fn after<T>(&self, seconds: f32, callback: T) -> ScheduleHandle
+    where
+        T: FnMut() + 'static,
+    {
+        self.after_with(seconds, ScheduleOptions::default(), callback)
+    }
+
+    pub fn every<T>(&self, seconds: f32, callback: T) -> ScheduleHandle
+    where
+        T: FnMut() + 'static,
+    {
+        self.every_with(seconds, ScheduleOptions::default(), callback)
+    }
+
+    pub fn after_with<T>(
+        &self,
+        seconds: f32,
+        options: ScheduleOptions,
+        callback: T,
+    ) -> ScheduleHandle
+    where
+        T: FnMut() + 'static,
+    {
+        self.add_action(seconds, None, options, Box::new(callback))
+    }
+
+    // The first call happens after `seconds`
+    pub fn every_with<T>(
+        &self,
+        seconds: f32,
+        options: ScheduleOptions,
+        callback: T,
+    ) -> ScheduleHandle
+    where
+        T: FnMut() + 'static,
+    {
+        self.add_action(seconds, Some(seconds), options, Box::new(callback))
+    }
+
+    pub fn cancel(&self, handle: ScheduleHandle) {
+        if handle == INVALID_SCHEDULE_HANDLE {
+            return;
+        }
+
+        self.m_actions
+            .borrow_mut()
+            .retain(|action| action.handle != handle);
+        self.m_ticking_actions.borrow_mut().remove(&handle);
+
+        // The action might be running right now and is not in the list
+        self.m_cancelled.borrow_mut().insert(handle);
+    }
+
+    pub fn cancel_entity(&self, entity_id: EntityId) {
+        if entity_id == INVALID_ENTITY_ID {
+            return;
+        }
+
+        let mut cancelled = self.m_cancelled.borrow_mut();
+        self.m_actions.borrow_mut().retain(|action| {
+            let is_owned = action.options.owner_id == entity_id;
+            if is_owned {
+                cancelled.insert(action.handle);
+            }
+
+            !is_owned
+        });
+        self.m_ticking_actions
+            .borrow_mut()
+            .retain(|handle, (owner_id, _)| {
+                let is_owned = *owner_id == entity_id;
+                if is_owned {
+                    cancelled.insert(*handle);
+                }
+
+                !is_owned
+            });
+    }
+
+    pub fn is_scheduled(&self, handle: ScheduleHandle) -> bool {
+        self.get_remaining_time(handle).is_some()
+    }
+
+    // Seconds until the action runs next, or None if it is not scheduled.
+    // Inside a callback, the time of this tick has already passed for every action.
+    pub fn get_remaining_time(&self, handle: ScheduleHandle) -> Option<f32> {
+        let remaining = match self.m_ticking_actions.borrow().get(&handle) {
+            Some((_, remaining)) => Some(*remaining),
+            None => self
+                .m_actions
+                .borrow()
+                .iter()
+                .find(|action| action.handle == handle)
+                .map(|action| action.remaining),
+        };
+
+        remaining.map(|remaining| remaining.max(0.0))
+    }
+
+    pub fn tick(&self, delta_time: f32, time_scale: f32) {
+        // Take memory, because a callback might schedule or cancel actions
+        let mut actions = std::mem::take(&mut *self.m_actions.borrow_mut());
+        self.m_cancelled.borrow_mut().clear();
+
+        for action in &mut actions {
+            action.remaining -= match action.options.time_mode {
+                TimeMode::Scaled => delta_time * time_scale,
+                TimeMode::Unscaled => delta_time,
+            };
+        }
+        *self.m_ticking_actions.borrow_mut() = actions
+            .iter()
+            .map(|action| (action.handle, (action.options.owner_id, action.remaining)))
+            .collect();
+
+        let mut finished: HashSet<ScheduleHandle> = HashSet::new();
+        for action in &mut actions {
+            if action.remaining > 0.0 || self.m_cancelled.borrow().contains(&action.handle) {
+                continue;
+            }
+
+            match action.interval {
+                // Repeating actions run at most once per tick and carry the remainder over
+                Some(interval) => {
+                    action.remaining = (action.remaining + interval).max(0.0);
+                    if let Some((_, remaining)) =
+                        self.m_ticking_actions.borrow_mut().get_mut(&action.handle)
+                    {
+                        *remaining = action.remaining;
+                    }
+                }
+                None => {
+                    finished.insert(action.handle);
+                    self.m_ticking_actions.borrow_mut().remove(&action.handle);
+                }
+            }
+
+            (action.callback)();
+        }
+
+        self.m_ticking_actions.borrow_mut().clear();
+        let cancelled = std::mem::take(&mut *self.m_cancelled.borrow_mut());
+        actions.retain(|action| {
+            !finished.contains(&action.handle) && !cancelled.contains(&action.handle)
+        });
+
+        // Actions scheduled by the callbacks run from the next tick
+        let mut current_actions = self.m_actions.borrow_mut();
+        let added_actions = std::mem::replace(&mut *current_actions, actions);
+        current_actions.extend(added_actions);
+    }
+
+    fn add_action(
+        &self,
+        seconds: f32,
+        interval: Option<f32>,
+        options: ScheduleOptions,
+        callback: Box<dyn FnMut()>,
+    ) -> ScheduleHandle {
+        let handle = self.m_next_handle.get();
+        self.m_next_handle.set(handle + 1);
+
+        self.m_actions.borrow_mut().push(ScheduledAction {
+            handle,
+            options,
+            remaining: seconds,
+            interval,
+            callback,
+        });
+
+        handle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    fn counter() -> (Rc<Cell<i32>>, impl FnMut() + 'static) {
+        let count = Rc::new(Cell::new(0));
+        let count_copy = count.clone();
+        (count, move || count_copy.set(count_copy.get() + 1))
+    }
+
+    #[test]
+    fn after_runs_once_when_the_delay_has_passed() {
+        let scheduler = Scheduler::new();
+        let (count, callback) = counter();
+        let handle = scheduler.after(1.0, callback);
+
+        scheduler.tick(0.75, 1.0);
+        assert_eq!(count.get(), 0);
+        assert_eq!(scheduler.get_remaining_time(handle), Some(0.25));
+
+        scheduler.tick(0.25, 1.0);
+        scheduler.tick(1.0, 1.0);
+        assert_eq!(count.get(), 1);
+        assert!(!scheduler.is_scheduled(handle));
+    }
+
+    #[test]
+    fn every_carries_the_remainder_over() {
+        let scheduler = Scheduler::new();
+        let (count, callback) = counter();
+        let handle = scheduler.every(1.0, callback);
+
+        scheduler.tick(1.25, 1.0);
+        assert_eq!(count.get(), 1);
+        assert_eq!(scheduler.get_remaining_time(handle), Some(0.75));
+
+        scheduler.tick(0.75, 1.0);
+        assert_eq!(count.get(), 2);
+
+        // At most once per tick, however long it was
+        scheduler.tick(3.5, 1.0);
+        assert_eq!(count.get(), 3);
+        assert_eq!(scheduler.get_remaining_time(handle), Some(0.0));
+    }
+
+    #[test]
+    fn scaled_actions_follow_the_time_scale() {
+        let scheduler = Scheduler::new();
+        let (scaled_count, scaled_callback) = counter();
+        let (unscaled_count, unscaled_callback) = counter();
+        scheduler.after(1.0, scaled_callback);
+        scheduler.after_with(
+            1.0,
+            ScheduleOptions::default().unscaled(),
+            unscaled_callback,
+        );
+
+        scheduler.tick(1.0, 0.5);
+        assert_eq!(scaled_count.get(), 0);
+        assert_eq!(unscaled_count.get(), 1);
+
+        scheduler.tick(1.0, 0.5);
+        assert_eq!(scaled_count.get(), 1);
+    }
+
+    #[test]
+    fn actions_added_by_a_callback_run_from_the_next_tick() {
+        let scheduler = Rc::new(Scheduler::new());
+        let (count, callback) = counter();
+        let mut callback = Some(callback);
+        let scheduler_copy = scheduler.clone();
+        scheduler.after(0.0, move || {
+            scheduler_copy.after(0.0, callback.take().unwrap());
+        });
+
+        scheduler.tick(1.0, 1.0);
+        assert_eq!(count.get(), 0);
+
+        scheduler.tick(0.0, 1.0);
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn cancelling_during_a_tick_stops_the_action() {
+        let scheduler = Rc::new(Scheduler::new());
+        let (count, callback) = counter();
+        let other_handle = Rc::new(Cell::new(INVALID_SCHEDULE_HANDLE));
+
+        // Cancels an action that is due later in the same tick, and itself
+        let scheduler_copy = scheduler.clone();
+        let other_handle_copy = other_handle.clone();
+        let handle = Rc::new(Cell::new(INVALID_SCHEDULE_HANDLE));
+        let handle_copy = handle.clone();
+        handle.set(scheduler.every(1.0, move || {
+            let other_handle = other_handle_copy.get();
+            assert!(scheduler_copy.is_scheduled(other_handle));
+            assert_eq!(scheduler_copy.get_remaining_time(other_handle), Some(0.0));
+            assert!(scheduler_copy.is_scheduled(handle_copy.get()));
+
+            scheduler_copy.cancel(other_handle);
+            scheduler_copy.cancel(handle_copy.get());
+            assert!(!scheduler_copy.is_scheduled(other_handle));
+        }));
+        other_handle.set(scheduler.after(1.0, callback));
+
+        scheduler.tick(1.0, 1.0);
+        scheduler.tick(1.0, 1.0);
+        assert_eq!(count.get(), 0);
+        assert!(!scheduler.is_scheduled(handle.get()));
+        assert!(!scheduler.is_scheduled(other_handle.get()));
+    }
+
+    #[test]
+    fn cancel_entity_stops_its_actions_only() {
+        let scheduler = Rc::new(Scheduler::new());
+        let (owned_count, owned_callback) = counter();
+        let (other_count, other_callback) = counter();
+        let owned_handle =
+            scheduler.every_with(1.0, ScheduleOptions::for_entity(7), owned_callback);
+        scheduler.every_with(1.0, ScheduleOptions::for_entity(8), other_callback);
+
+        scheduler.tick(1.0, 1.0);
+        scheduler.cancel_entity(7);
+        assert!(!scheduler.is_scheduled(owned_handle));
+
+        scheduler.tick(1.0, 1.0);
+        assert_eq!(owned_count.get(), 1);
+        assert_eq!(other_count.get(), 2);
+
+        // From inside a callback, e.g. when an entity is destroyed by another's action
+        let (count, callback) = counter();
+        scheduler.after_with(2.0, ScheduleOptions::for_entity(9), callback);
+        let scheduler_copy = scheduler.clone();
+        scheduler.after(1.0, move || scheduler_copy.cancel_entity(9));
+
+        scheduler.tick(1.0, 1.0);
+        scheduler.tick(1.0, 1.0);
+        assert_eq!(count.get(), 0);
+    }
+}
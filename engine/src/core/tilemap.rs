@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use crate::math::{Rect, Vec2};
+
+// Grid coordinates of a tile, in tiles
+pub type TilePos = (i32, i32);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tile {
+    pub tile_type: String,
+    pub variant: i32,
+    // In tiles for grid tiles, in pixels for offgrid tiles
+    pub pos: Vec2,
+}
+
+// Grid tiles plus freely placed (offgrid) tiles such as decorations and spawners.
+// Uses the map format in assets/maps. The order of the tiles is kept,
+// so a map that is loaded and saved again comes out byte for byte the same.
+pub struct Tilemap {
+    m_tile_size: i32,
+    m_tiles: HashMap<TilePos, Tile>,
+    m_tile_order: Vec<TilePos>,
+    m_offgrid_tiles: Vec<Tile>,
+}
+
+impl Tilemap {
+    pub fn new(tile_size: i32) -> Self {
+        Self {
+            m_tile_size: tile_size,
+            m_tiles: HashMap::new(),
+            m_tile_order: Vec::new(),
+            m_offgrid_tiles: Vec::new(),
+        }
+    }
+
+    pub fn from_file<P>(path: P) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read '{}': {}", path.display(), err))?;
+        let data: MapData = serde_json::from_str(&json)
+            .map_err(|err| format!("Failed to parse '{}': {}", path.display(), err))?;
+
+        let mut tilemap = Tilemap::new(data.tile_size);
+        for tile in data.tilemap.0 {
+            let tile = Tile::from(tile);
+            tilemap.set_tile(to_tile_pos(tile.pos), tile);
+        }
+        tilemap.m_offgrid_tiles = data.offgrid.into_iter().map(Tile::from).collect();
+
+        Ok(tilemap)
+    }
+
+    pub fn to_file<P>(&self, path: P) -> Result<(), String>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json())
+            .map_err(|err| format!("Failed to write '{}': {}", path.display(), err))
+    }
+
+    // Laid out like Python's json.dumps(), which the maps were first written with
+    pub fn to_json(&self) -> String {
+        let tiles: Vec<String> = self
+            .tile_iter()
+            .map(|(tile_pos, tile)| {
+                format!(
+                    "\"{};{}\": {}",
+                    tile_pos.0,
+                    tile_pos.1,
+                    tile_to_json(tile, &format!("[{}, {}]", tile_pos.0, tile_pos.1))
+                )
+            })
+            .collect();
+
+        let offgrid_tiles: Vec<String> = self
+            .m_offgrid_tiles
+            .iter()
+            .map(|tile| {
+                let pos = format!(
+                    "[{}, {}]",
+                    float_to_json(tile.pos.x),
+                    float_to_json(tile.pos.y)
+                );
+                tile_to_json(tile, &pos)
+            })
+            .collect();
+
+        format!(
+            "{{\"tilemap\": {{{}}}, \"tile_size\": {}, \"offgrid\": [{}]}}",
+            tiles.join(", "),
+            self.m_tile_size,
+            offgrid_tiles.join(", ")
+        )
+    }
+
+    pub fn get_tile_size(&self) -> i32 {
+        self.m_tile_size
+    }
+
+    pub fn get_tile(&self, tile_pos: TilePos) -> Option<&Tile> {
+        self.m_tiles.get(&tile_pos)
+    }
+
+    // The tile's pos is set to match tile_pos. Replacing a tile keeps its place in the order.
+    pub fn set_tile(&mut self, tile_pos: TilePos, mut tile: Tile) {
+        tile.pos = Vec2::from_xy(tile_pos.0 as f32, tile_pos.1 as f32);
+        if self.m_tiles.insert(tile_pos, tile).is_none() {
+            self.m_tile_order.push(tile_pos);
+        }
+    }
+
+    pub fn remove_tile(&mut self, tile_pos: TilePos) -> Option<Tile> {
+        let tile = self.m_tiles.remove(&tile_pos)?;
+        self.m_tile_order.retain(|pos| *pos != tile_pos);
+        Some(tile)
+    }
+
+    // In the order the tiles were added
+    pub fn tile_iter(&self) -> impl Iterator<Item = (TilePos, &Tile)> {
+        self.m_tile_order
+            .iter()
+            .map(|tile_pos| (*tile_pos, &self.m_tiles[tile_pos]))
+    }
+
+    pub fn get_tile_count(&self) -> usize {
+        self.m_tile_order.len()
+    }
+
+    pub fn get_offgrid_tiles(&self) -> &[Tile] {
+        &self.m_offgrid_tiles
+    }
+
+    pub fn add_offgrid_tile(&mut self, tile: Tile) {
+        self.m_offgrid_tiles.push(tile);
+    }
+
+    // Puts the tile back at its old place, e.g. when undoing a removal
+    pub fn insert_offgrid_tile(&mut self, index: usize, tile: Tile) {
+        self.m_offgrid_tiles.insert(index, tile);
+    }
+
+    pub fn remove_offgrid_tile(&mut self, index: usize) -> Tile {
+        self.m_offgrid_tiles.remove(index)
+    }
+
+    // Tile containing the world position
+    pub fn world_to_tile(&self, position: Vec2) -> TilePos {
+        let tile_size = self.m_tile_size as f32;
+        (
+            (position.x / tile_size).floor() as i32,
+            (position.y / tile_size).floor() as i32,
+        )
+    }
+
+    pub fn get_tile_rect(&self, tile_pos: TilePos) -> Rect {
+        let tile_size = self.m_tile_size as f32;
+        Rect::new(
+            tile_pos.0 as f32 * tile_size,
+            tile_pos.1 as f32 * tile_size,
+            tile_size,
+            tile_size,
+        )
+    }
+}
+
+fn to_tile_pos(pos: Vec2) -> TilePos {
+    (pos.x.floor() as i32, pos.y.floor() as i32)
+}
+
+fn tile_to_json(tile: &Tile, pos: &str) -> String {
+    let tile_type = serde_json::to_string(&tile.tile_type).unwrap_or_else(|_| String::from("\"\""));
+    format!(
+        "{{\"type\": {}, \"variant\": {}, \"pos\": {}}}",
+        tile_type, tile.variant, pos
+    )
+}
+
+// Shortest text that reads back as the same value, always with a decimal point like Python
+fn float_to_json(value: f32) -> String {
+    let text = value.to_string();
+    if text.contains('.') || !value.is_finite() {
+        text
+    } else {
+        format!("{}.0", text)
+    }
+}
+
+// On-disk layout
+#[derive(Deserialize)]
+struct MapData {
+    tilemap: OrderedTiles,
+    tile_size: i32,
+    #[serde(default)]
+    offgrid: Vec<TileData>,
+}
+
+#[derive(Deserialize)]
+struct TileData {
+    #[serde(rename = "type")]
+    tile_type: String,
+    variant: i32,
+    pos: [f32; 2],
+}
+
+// The "tilemap" object's values in file order, the keys repeat each tile's pos
+struct OrderedTiles(Vec<TileData>);
+
+impl<'de> Deserialize<'de> for OrderedTiles {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct OrderedTilesVisitor;
+
+        impl<'de> Visitor<'de> for OrderedTilesVisitor {
+            type Value = OrderedTiles;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map of tiles")
+            }
+
+            fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut tiles = Vec::new();
+                while let Some((_, tile)) = access.next_entry::<String, TileData>()? {
+                    tiles.push(tile);
+                }
+
+                Ok(OrderedTiles(tiles))
+            }
+        }
+
+        deserializer.deserialize_map(OrderedTilesVisitor)
+    }
+}
+
+impl From<TileData> for Tile {
+    fn from(data: TileData) -> Self {
+        Self {
+            tile_type: data.tile_type,
+            variant: data.variant,
+            pos: Vec2::from_xy(data.pos[0], data.pos[1]),
+        }
+    }
+}
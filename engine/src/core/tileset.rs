@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::autotile::AutotileRule;
+
+// Collision shape of a tile, in the tile's own space
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TileShape {
+    #[default]
+    None,
+    Solid,
+    // Solid only from above, e.g. jump-through platforms
+    OneWay,
+    // Solid below a straight surface. The heights are fractions of the tile size,
+    // measured up from the bottom edge, e.g. 0 and 1 for a 45° slope rising to the right
+    // or 0 and 0.5 for the lower half of a 22.5° slope.
+    Slope {
+        left: f32,
+        right: f32,
+    },
+}
+
+impl TileShape {
+    // Solid area under the surface, used by the character controller and queries
+    pub fn get_surface_height(&self, local_x: f32) -> Option<f32> {
+        match self {
+            TileShape::None => None,
+            TileShape::Solid | TileShape::OneWay => Some(1.0),
+            TileShape::Slope { left, right } => {
+                let t = local_x.clamp(0.0, 1.0);
+                Some(left + (right - left) * t)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TileTypeDefinition {
+    #[serde(default)]
+    pub shape: TileShape,
+    // Overrides the shape of single variants
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variants: HashMap<i32, TileShape>,
+    // Name of the autotile rules that pick the variant from the neighbors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autotile: Option<String>,
+}
+
+// Per tile type settings shared by all maps, loaded from assets/tilesets.
+// Tile types without a definition have no collision.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Tileset {
+    #[serde(default)]
+    pub tile_types: HashMap<String, TileTypeDefinition>,
+    // Named rule sets, shared by tile types with the same layout of variants
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub autotile_rules: HashMap<String, Vec<AutotileRule>>,
+}
+
+impl Tileset {
+    pub fn from_file<P>(path: P) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read '{}': {}", path.display(), err))?;
+        let tileset: Tileset = serde_json::from_str(&json)
+            .map_err(|err| format!("Failed to parse '{}': {}", path.display(), err))?;
+
+        for (tile_type, definition) in &tileset.tile_types {
+            if let Some(rules_name) = &definition.autotile
+                && !tileset.autotile_rules.contains_key(rules_name)
+            {
+                return Err(format!(
+                    "Unknown autotile rules '{}' for tile type '{}' in '{}'",
+                    rules_name,
+                    tile_type,
+                    path.display()
+                ));
+            }
+        }
+
+        Ok(tileset)
+    }
+
+    pub fn get_shape(&self, tile_type: &str, variant: i32) -> TileShape {
+        self.tile_types
+            .get(tile_type)
+            .map_or(TileShape::None, |definition| {
+                definition
+                    .variants
+                    .get(&variant)
+                    .copied()
+                    .unwrap_or(definition.shape)
+            })
+    }
+
+    // None when the tile type isn't auto-tiled
+    pub fn get_autotile_rules(&self, tile_type: &str) -> Option<&[AutotileRule]> {
+        let rules_name = self.tile_types.get(tile_type)?.autotile.as_ref()?;
+        self.autotile_rules.get(rules_name).map(Vec::as_slice)
+    }
+}
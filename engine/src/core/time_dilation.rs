@@ -0,0 +1,167 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use crate::entity::EntityId;
+
+// Slower than this and exempt entities would need huge physics steps to keep up
+const MIN_TIME_SCALE: f32 = 0.05;
+
+// Limits how long slow motion can be used. Drains in real time while slow motion is on,
+// turns it off when empty and recharges while it is off.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeDilationMeter {
+    pub capacity: f32,      // seconds of slow motion when full
+    pub recharge_rate: f32, // seconds of slow motion regained per second
+    pub value: f32,
+}
+
+impl TimeDilationMeter {
+    // Starts full
+    pub fn new(capacity: f32, recharge_rate: f32) -> Self {
+        Self {
+            capacity,
+            recharge_rate,
+            value: capacity,
+        }
+    }
+
+    // [0, 1], e.g. for a UI bar
+    pub fn get_fraction(&self) -> f32 {
+        if self.capacity > 0.0 {
+            (self.value / self.capacity).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value <= 0.0
+    }
+}
+
+// Slow motion for the gameplay. Ramps the time scale towards a target instead of snapping to it.
+// The App feeds the time scale to the Timer, the entities, the physics and the audio pitch.
+//
+// Shared as `Rc<TimeDilation>` and uses interior mutability, so components can start and stop
+// slow motion and exempt their entity from it.
+pub struct TimeDilation {
+    m_time_scale: Cell<f32>,
+    m_target_time_scale: Cell<f32>,
+    // Seconds to go from normal speed to a full stop, shorter changes take proportionally less
+    m_ramp_duration: Cell<f32>,
+    // How much of the slow down an entity ignores, 1 is real time
+    m_exemptions: RefCell<HashMap<EntityId, f32>>,
+    m_meter: Cell<Option<TimeDilationMeter>>,
+}
+
+impl TimeDilation {
+    pub fn new() -> Self {
+        Self {
+            m_time_scale: Cell::new(1.0),
+            m_target_time_scale: Cell::new(1.0),
+            m_ramp_duration: Cell::new(0.25),
+            m_exemptions: RefCell::new(HashMap::new()),
+            m_meter: Cell::new(None),
+        }
+    }
+
+    pub fn get_time_scale(&self) -> f32 {
+        self.m_time_scale.get()
+    }
+
+    pub fn get_target_time_scale(&self) -> f32 {
+        self.m_target_time_scale.get()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.m_target_time_scale.get() < 1.0
+    }
+
+    // Ramps towards `time_scale`. Ignored while the meter is empty.
+    pub fn start(&self, time_scale: f32) {
+        if self.m_meter.get().is_some_and(|meter| meter.is_empty()) {
+            return;
+        }
+
+        self.m_target_time_scale
+            .set(time_scale.clamp(MIN_TIME_SCALE, 1.0));
+    }
+
+    // Ramps back to normal speed
+    pub fn stop(&self) {
+        self.m_target_time_scale.set(1.0);
+    }
+
+    pub fn toggle(&self, time_scale: f32) {
+        if self.is_active() {
+            self.stop();
+        } else {
+            self.start(time_scale);
+        }
+    }
+
+    pub fn set_ramp_duration(&self, ramp_duration: f32) {
+        self.m_ramp_duration.set(ramp_duration.max(0.0));
+    }
+
+    // 0 follows the slow down fully, 1 keeps the entity in real time
+    pub fn set_exemption(&self, entity_id: EntityId, exemption: f32) {
+        self.m_exemptions
+            .borrow_mut()
+            .insert(entity_id, exemption.clamp(0.0, 1.0));
+    }
+
+    pub fn remove_exemption(&self, entity_id: EntityId) {
+        self.m_exemptions.borrow_mut().remove(&entity_id);
+    }
+
+    pub fn get_entity_time_scale(&self, entity_id: EntityId) -> f32 {
+        let time_scale = self.get_time_scale();
+        match self.m_exemptions.borrow().get(&entity_id) {
+            Some(exemption) => time_scale + (1.0 - time_scale) * exemption,
+            None => time_scale,
+        }
+    }
+
+    // The entity's time scale relative to the global one, for steps of scaled time such as physics
+    pub fn get_entity_time_ratio(&self, entity_id: EntityId) -> f32 {
+        self.get_entity_time_scale(entity_id) / self.get_time_scale()
+    }
+
+    pub fn get_meter(&self) -> Option<TimeDilationMeter> {
+        self.m_meter.get()
+    }
+
+    // None allows unlimited slow motion
+    pub fn set_meter(&self, meter: Option<TimeDilationMeter>) {
+        self.m_meter.set(meter);
+    }
+
+    // Called by the App with the real (unscaled) delta time
+    pub fn tick(&self, delta_time: f32) {
+        if let Some(mut meter) = self.m_meter.get() {
+            if self.is_active() {
+                meter.value = (meter.value - delta_time).max(0.0);
+                if meter.is_empty() {
+                    self.stop();
+                }
+            } else {
+                meter.value = (meter.value + meter.recharge_rate * delta_time).min(meter.capacity);
+            }
+
+            self.m_meter.set(Some(meter));
+        }
+
+        let time_scale = self.m_time_scale.get();
+        let target_time_scale = self.m_target_time_scale.get();
+        let ramp_duration = self.m_ramp_duration.get();
+        if ramp_duration <= 0.0 {
+            self.m_time_scale.set(target_time_scale);
+            return;
+        }
+
+        let max_change = delta_time / ramp_duration;
+        let change = (target_time_scale - time_scale).clamp(-max_change, max_change);
+        self.m_time_scale.set(time_scale + change);
+    }
+}
@@ -0,0 +1,254 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::components::Component;
+use crate::core::assets::{Assets, TextureId};
+use crate::entity::EntityId;
+
+// Bump when the layout of WorldState changes in an incompatible way
+pub const WORLD_STATE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComponentState {
+    pub type_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntityState {
+    pub id: EntityId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub components: Vec<ComponentState>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorldState {
+    pub version: u32,
+    pub entities: Vec<EntityState>,
+}
+
+impl WorldState {
+    pub fn new() -> Self {
+        Self {
+            version: WORLD_STATE_VERSION,
+            entities: Vec::new(),
+        }
+    }
+
+    pub fn from_file<P>(path: P) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read '{}': {}", path.display(), err))?;
+        let state: WorldState = serde_json::from_str(&json)
+            .map_err(|err| format!("Failed to parse '{}': {}", path.display(), err))?;
+
+        if state.version != WORLD_STATE_VERSION {
+            return Err(format!(
+                "Unsupported world state version {} in '{}' (expected {})",
+                state.version,
+                path.display(),
+                WORLD_STATE_VERSION
+            ));
+        }
+
+        Ok(state)
+    }
+
+    pub fn to_file<P>(&self, path: P) -> Result<(), String>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|err| format!("Failed to serialize world state: {}", err))?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create '{}': {}", parent.display(), err))?;
+        }
+
+        std::fs::write(path, json)
+            .map_err(|err| format!("Failed to write '{}': {}", path.display(), err))
+    }
+}
+
+// Passed to Component::save_state(). Runtime handles such as TextureId
+// are saved as stable asset paths.
+pub struct SaveContext<'a> {
+    m_assets: &'a Assets,
+}
+
+impl<'a> SaveContext<'a> {
+    pub fn new(assets: &'a Assets) -> Self {
+        Self { m_assets: assets }
+    }
+
+    pub fn save_value<T>(&self, value: &T) -> Result<Value, String>
+    where
+        T: Serialize,
+    {
+        serde_json::to_value(value).map_err(|err| err.to_string())
+    }
+
+    pub fn save_texture(&self, texture_id: TextureId) -> Result<Value, String> {
+        let path = self
+            .m_assets
+            .get_texture_asset_path(texture_id)
+            .ok_or_else(|| format!("Texture with 'texture_id={}' has no asset path", texture_id))?;
+
+        Ok(Value::String(path.to_string()))
+    }
+
+    pub fn save_textures(&self, texture_ids: &[TextureId]) -> Result<Value, String> {
+        texture_ids
+            .iter()
+            .map(|texture_id| self.save_texture(*texture_id))
+            .collect::<Result<Vec<Value>, String>>()
+            .map(Value::Array)
+    }
+}
+
+// Passed to Component::load_state()
+pub struct LoadContext<'a> {
+    m_assets: &'a mut Assets,
+    m_loaded_textures: Vec<TextureId>,
+}
+
+impl<'a> LoadContext<'a> {
+    pub fn new(assets: &'a mut Assets) -> Self {
+        Self {
+            m_assets: assets,
+            m_loaded_textures: Vec::new(),
+        }
+    }
+
+    pub fn load_value<T>(&mut self, value: &Value) -> Result<T, String>
+    where
+        T: DeserializeOwned,
+    {
+        T::deserialize(value).map_err(|err| err.to_string())
+    }
+
+    pub fn load_texture(&mut self, value: &Value) -> Result<TextureId, String> {
+        let asset_path = value
+            .as_str()
+            .ok_or_else(|| format!("Expected a texture asset path, got '{}'", value))?;
+
+        if let Some(texture_id) = self.m_assets.find_texture(asset_path) {
+            return Ok(texture_id);
+        }
+
+        let path = self.m_assets.get_asset_path(asset_path.split('/'))?;
+        let texture_id = self.m_assets.load_texture(path)?;
+        self.m_loaded_textures.push(texture_id);
+
+        Ok(texture_id)
+    }
+
+    pub fn load_textures(&mut self, value: &Value) -> Result<Vec<TextureId>, String> {
+        value
+            .as_array()
+            .ok_or_else(|| format!("Expected a list of texture asset paths, got '{}'", value))?
+            .iter()
+            .map(|value| self.load_texture(value))
+            .collect()
+    }
+
+    // Textures that were not loaded before the restore
+    pub fn take_loaded_textures(&mut self) -> Vec<TextureId> {
+        std::mem::take(&mut self.m_loaded_textures)
+    }
+}
+
+type ComponentFactory = Box<dyn Fn() -> Box<dyn Component>>;
+
+// Submitted by #[component(serialize)] and collected by every new ComponentRegistry
+pub struct ComponentRegistration {
+    m_type_name: &'static str,
+    m_type_id: fn() -> TypeId,
+    m_create: fn() -> Box<dyn Component>,
+}
+
+impl ComponentRegistration {
+    pub const fn new(
+        type_name: &'static str,
+        type_id: fn() -> TypeId,
+        create: fn() -> Box<dyn Component>,
+    ) -> Self {
+        Self {
+            m_type_name: type_name,
+            m_type_id: type_id,
+            m_create: create,
+        }
+    }
+
+    // Creates a default instance of a registered component type
+    pub fn create_default(type_id: TypeId) -> Option<Box<dyn Component>> {
+        inventory::iter::<ComponentRegistration>
+            .into_iter()
+            .find(|registration| (registration.m_type_id)() == type_id)
+            .map(|registration| (registration.m_create)())
+    }
+}
+
+inventory::collect!(ComponentRegistration);
+
+// Creates components by type name when a WorldState is restored.
+// Components declared with #[component(serialize)] are registered automatically.
+// Components that need runtime dependencies (e.g. Input) are registered by hand
+// and capture them in the factory.
+pub struct ComponentRegistry {
+    m_factories: HashMap<String, ComponentFactory>,
+}
+
+impl ComponentRegistry {
+    pub fn new() -> Self {
+        let mut factories: HashMap<String, ComponentFactory> = HashMap::new();
+        for registration in inventory::iter::<ComponentRegistration> {
+            factories.insert(
+                registration.m_type_name.to_string(),
+                Box::new(registration.m_create),
+            );
+        }
+
+        Self {
+            m_factories: factories,
+        }
+    }
+
+    pub fn register<T, F>(&mut self, type_name: &str, factory: F)
+    where
+        T: Component,
+        F: Fn() -> T + 'static,
+    {
+        self.m_factories.insert(
+            type_name.to_string(),
+            Box::new(move || factory().into_box()),
+        );
+    }
+
+    pub fn is_registered(&self, type_name: &str) -> bool {
+        self.m_factories.contains_key(type_name)
+    }
+
+    pub fn create(&self, type_name: &str) -> Result<Box<dyn Component>, String> {
+        let factory = self
+            .m_factories
+            .get(type_name)
+            .ok_or_else(|| format!("Component '{}' is not registered", type_name))?;
+
+        Ok(factory())
+    }
+}
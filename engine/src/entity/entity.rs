@@ -1,12 +1,21 @@
+use std::any::{Any, TypeId};
+use std::collections::HashSet;
+
 use crate::components::Component;
 use crate::core::render::RenderQueue;
+use crate::core::world_state::ComponentRegistration;
+use crate::physics::Collision;
 
 pub type EntityId = i32;
 pub const INVALID_ENTITY_ID: EntityId = -1;
 
 pub struct Entity {
     m_id: EntityId,
+    m_name: Option<String>,
+    m_tags: HashSet<String>,
     m_components: Vec<Box<dyn Component>>,
+    // Added as defaults for requirements, replaced when the component is added explicitly
+    m_default_component_types: HashSet<TypeId>,
     m_is_in_play: bool,
     m_is_ticking: bool,
 }
@@ -15,7 +24,10 @@ impl Entity {
     pub fn new() -> Box<Self> {
         Box::new(Self {
             m_id: INVALID_ENTITY_ID,
+            m_name: None,
+            m_tags: HashSet::new(),
             m_components: Vec::new(),
+            m_default_component_types: HashSet::new(),
             m_is_in_play: false,
             m_is_ticking: true,
         })
@@ -53,6 +65,24 @@ impl Entity {
         }
     }
 
+    pub fn on_trigger_enter(&mut self, other: EntityId) {
+        for comp in &mut self.m_components {
+            comp.on_trigger_enter(other);
+        }
+    }
+
+    pub fn on_trigger_exit(&mut self, other: EntityId) {
+        for comp in &mut self.m_components {
+            comp.on_trigger_exit(other);
+        }
+    }
+
+    pub fn on_collision(&mut self, collision: &Collision) {
+        for comp in &mut self.m_components {
+            comp.on_collision(collision);
+        }
+    }
+
     pub fn get_id(&self) -> EntityId {
         self.m_id
     }
@@ -61,6 +91,52 @@ impl Entity {
         self.m_id = id;
     }
 
+    pub fn get_name(&self) -> Option<&str> {
+        self.m_name.as_deref()
+    }
+
+    // The EntitySpawner indexes names and tags when the entity is spawned.
+    // Once in play, use EntitySpawner::set_entity_name/add_entity_tag/remove_entity_tag instead.
+    pub fn set_name(&mut self, name: &str) {
+        assert!(
+            !self.is_in_play(),
+            "Use EntitySpawner::set_entity_name() for entities in play"
+        );
+        self.m_name = Some(name.to_string());
+    }
+
+    pub fn get_tags(&self) -> impl Iterator<Item = &str> {
+        self.m_tags.iter().map(String::as_str)
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.m_tags.contains(tag)
+    }
+
+    pub fn add_tag(&mut self, tag: &str) {
+        assert!(
+            !self.is_in_play(),
+            "Use EntitySpawner::add_entity_tag() for entities in play"
+        );
+        self.m_tags.insert(tag.to_string());
+    }
+
+    pub fn remove_tag(&mut self, tag: &str) {
+        assert!(
+            !self.is_in_play(),
+            "Use EntitySpawner::remove_entity_tag() for entities in play"
+        );
+        self.m_tags.remove(tag);
+    }
+
+    pub(crate) fn set_name_unchecked(&mut self, name: Option<String>) {
+        self.m_name = name;
+    }
+
+    pub(crate) fn get_tags_mut_unchecked(&mut self) -> &mut HashSet<String> {
+        &mut self.m_tags
+    }
+
     pub fn is_in_play(&self) -> bool {
         self.m_is_in_play
     }
@@ -73,22 +149,128 @@ impl Entity {
         self.m_is_ticking = is_ticking;
     }
 
-    pub fn add_component<T>(&mut self, mut comp: T)
+    // Missing required components are added with their defaults if they are registered
+    // with #[component(serialize)]. Otherwise the component is not added and an error is returned.
+    // Adding a component type twice is an error, unless the first one was added as a default.
+    pub fn add_component<T>(&mut self, comp: T) -> Result<(), String>
     where
         T: Component,
     {
-        if self.is_in_play() {
-            comp.enter_play();
+        self.add_boxed_component(comp.into_box())
+    }
+
+    pub fn add_boxed_component(&mut self, comp: Box<dyn Component>) -> Result<(), String> {
+        self.check_duplicate(comp.as_ref())?;
+
+        for requirement in comp.get_requirements() {
+            if self.has_component_type(requirement.type_id) {
+                continue;
+            }
+
+            let default_comp = ComponentRegistration::create_default(requirement.type_id)
+                .ok_or_else(|| {
+                    format!(
+                        "'{}' requires '{}', which must be added first (it has no default)",
+                        comp.get_type_name(),
+                        requirement.type_name
+                    )
+                })?;
+
+            self.add_boxed_component(default_comp)?;
+            self.m_default_component_types.insert(requirement.type_id);
+        }
+
+        self.insert_component(comp);
+
+        Ok(())
+    }
+
+    // Adds the component without adding its missing requirements, e.g. while restoring
+    // a saved entity whose components come in priority order. Call check_requirements()
+    // once all of them are added.
+    pub(crate) fn add_boxed_component_unchecked(
+        &mut self,
+        comp: Box<dyn Component>,
+    ) -> Result<(), String> {
+        self.check_duplicate(comp.as_ref())?;
+        self.insert_component(comp);
+
+        Ok(())
+    }
+
+    fn check_duplicate(&self, comp: &dyn Component) -> Result<(), String> {
+        let type_id = Any::type_id(comp.as_any());
+        if self.has_component_type(type_id) && !self.m_default_component_types.contains(&type_id) {
+            return Err(format!("'{}' was already added", comp.get_type_name()));
+        }
+
+        Ok(())
+    }
+
+    // Replaces a default of the same type
+    fn insert_component(&mut self, mut comp: Box<dyn Component>) {
+        let type_id = Any::type_id(comp.as_any());
+        if self.m_default_component_types.remove(&type_id)
+            && let Some(index) = self
+                .m_components
+                .iter()
+                .position(|c| Any::type_id(c.as_any()) == type_id)
+        {
+            let mut default_comp = self.m_components.remove(index);
+            if self.is_in_play() {
+                default_comp.exit_play();
+            }
         }
 
         unsafe {
             comp.set_entity(self as *mut Entity);
         }
 
-        self.m_components.push(comp.into_box());
+        if self.is_in_play() {
+            comp.enter_play();
+        }
+
+        self.m_components.push(comp);
         self.m_components.sort_by_key(|c| c.get_priority());
     }
 
+    pub fn check_requirements(&self) -> Result<(), String> {
+        for comp in &self.m_components {
+            for requirement in comp.get_requirements() {
+                if !self.has_component_type(requirement.type_id) {
+                    return Err(format!(
+                        "'{}' requires '{}'",
+                        comp.get_type_name(),
+                        requirement.type_name
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn has_component<T>(&self) -> bool
+    where
+        T: Component,
+    {
+        self.has_component_type(TypeId::of::<T>())
+    }
+
+    fn has_component_type(&self, type_id: TypeId) -> bool {
+        self.m_components
+            .iter()
+            .any(|c| Any::type_id(c.as_any()) == type_id)
+    }
+
+    pub fn component_iter(&self) -> impl Iterator<Item = &dyn Component> {
+        self.m_components.iter().map(Box::as_ref)
+    }
+
+    pub fn component_iter_mut(&mut self) -> impl Iterator<Item = &mut dyn Component> {
+        self.m_components.iter_mut().map(Box::as_mut)
+    }
+
     pub fn get_component<T>(&self) -> Option<&T>
     where
         T: Component,
@@ -107,3 +289,89 @@ impl Entity {
             .find_map(|c| c.as_any_mut().downcast_mut::<T>())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{AnimationComponent, ImageComponent, TransformComponent};
+
+    const TEXTURE_ID: i32 = 7;
+
+    fn count_components<T>(entity: &Entity) -> usize
+    where
+        T: Component,
+    {
+        entity
+            .component_iter()
+            .filter(|comp| comp.as_any().is::<T>())
+            .count()
+    }
+
+    fn get_texture_id(entity: &Entity) -> i32 {
+        entity
+            .get_component::<ImageComponent>()
+            .unwrap()
+            .get_texture_id()
+    }
+
+    #[test]
+    fn requirements_added_first_are_kept() {
+        let mut entity = Entity::new();
+        entity
+            .add_component(ImageComponent::from_texture(TEXTURE_ID))
+            .unwrap();
+        entity.add_component(AnimationComponent::new()).unwrap();
+
+        assert_eq!(count_components::<ImageComponent>(&entity), 1);
+        assert_eq!(count_components::<TransformComponent>(&entity), 1);
+        assert_eq!(get_texture_id(&entity), TEXTURE_ID);
+    }
+
+    #[test]
+    fn requirements_added_later_replace_their_defaults() {
+        let mut entity = Entity::new();
+        entity.add_component(AnimationComponent::new()).unwrap();
+        entity
+            .add_component(ImageComponent::from_texture(TEXTURE_ID))
+            .unwrap();
+
+        assert_eq!(count_components::<ImageComponent>(&entity), 1);
+        assert_eq!(count_components::<TransformComponent>(&entity), 1);
+        assert_eq!(get_texture_id(&entity), TEXTURE_ID);
+    }
+
+    #[test]
+    fn components_can_be_added_once() {
+        let mut entity = Entity::new();
+        entity.add_component(ImageComponent::new()).unwrap();
+
+        assert!(entity.add_component(ImageComponent::new()).is_err());
+        assert!(
+            entity
+                .add_boxed_component_unchecked(ImageComponent::new().into_box())
+                .is_err()
+        );
+        assert_eq!(count_components::<ImageComponent>(&entity), 1);
+    }
+
+    #[test]
+    fn unchecked_components_are_checked_once_complete() {
+        // Priority order, as in a saved world
+        let mut entity = Entity::new();
+        entity
+            .add_boxed_component_unchecked(AnimationComponent::new().into_box())
+            .unwrap();
+        assert!(entity.check_requirements().is_err());
+
+        entity
+            .add_boxed_component_unchecked(ImageComponent::from_texture(TEXTURE_ID).into_box())
+            .unwrap();
+        entity
+            .add_boxed_component_unchecked(TransformComponent::new().into_box())
+            .unwrap();
+
+        assert!(entity.check_requirements().is_ok());
+        assert_eq!(count_components::<ImageComponent>(&entity), 1);
+        assert_eq!(get_texture_id(&entity), TEXTURE_ID);
+    }
+}
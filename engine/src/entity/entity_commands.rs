@@ -0,0 +1,45 @@
+use std::cell::RefCell;
+
+use crate::entity::{Entity, EntityId};
+
+// Spawn and destroy requests from places without access to the EntitySpawner, e.g. components.
+//
+// Shared as `Rc<EntityCommands>`. The EntitySpawner picks the requests up the next time it
+// resolves its own, at the start of the frame.
+pub struct EntityCommands {
+    // Boxed because components point back at their entity, so it must not move
+    #[allow(clippy::vec_box)]
+    m_spawn_requests: RefCell<Vec<Box<Entity>>>,
+    m_destroy_requests: RefCell<Vec<EntityId>>,
+}
+
+impl EntityCommands {
+    pub fn new() -> Self {
+        Self {
+            m_spawn_requests: RefCell::new(Vec::new()),
+            m_destroy_requests: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn spawn(&self, entity: Box<Entity>) {
+        self.m_spawn_requests.borrow_mut().push(entity);
+    }
+
+    pub fn destroy(&self, entity_id: EntityId) {
+        self.m_destroy_requests.borrow_mut().push(entity_id);
+    }
+
+    #[allow(clippy::vec_box)]
+    pub(crate) fn take_spawn_requests(&self) -> Vec<Box<Entity>> {
+        std::mem::take(&mut *self.m_spawn_requests.borrow_mut())
+    }
+
+    pub(crate) fn take_destroy_requests(&self) -> Vec<EntityId> {
+        std::mem::take(&mut *self.m_destroy_requests.borrow_mut())
+    }
+
+    pub(crate) fn clear(&self) {
+        self.m_spawn_requests.borrow_mut().clear();
+        self.m_destroy_requests.borrow_mut().clear();
+    }
+}
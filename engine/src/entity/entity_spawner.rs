@@ -1,22 +1,46 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::mem;
+use std::rc::Rc;
 
-use crate::entity::{Entity, EntityId};
+use crate::core::assets::{Assets, TextureId};
+use crate::core::event_bus::EventBus;
+use crate::core::scheduler::Scheduler;
+use crate::core::world_state::{
+    ComponentRegistry, ComponentState, EntityState, LoadContext, SaveContext, WorldState,
+};
+use crate::entity::{Entity, EntityCommands, EntityId};
 
 pub struct EntitySpawner {
     m_next_entity_id: EntityId,
     m_entities: Vec<Box<Entity>>,
+    // Where each entity in play is in m_entities
+    m_id_index: HashMap<EntityId, usize>,
     m_entity_spawn_requests: Vec<Box<Entity>>,
     m_entity_destroy_requests: HashSet<EntityId>,
+    m_name_index: HashMap<String, EntityId>,
+    m_tag_index: HashMap<String, HashSet<EntityId>>,
+    m_event_bus: Rc<EventBus>,
+    m_scheduler: Rc<Scheduler>,
+    m_entity_commands: Rc<EntityCommands>,
 }
 
 impl EntitySpawner {
-    pub fn new() -> Self {
+    pub fn new(
+        event_bus: Rc<EventBus>,
+        scheduler: Rc<Scheduler>,
+        entity_commands: Rc<EntityCommands>,
+    ) -> Self {
         Self {
             m_next_entity_id: 0,
             m_entities: Vec::new(),
+            m_id_index: HashMap::new(),
             m_entity_spawn_requests: Vec::new(),
             m_entity_destroy_requests: HashSet::new(),
+            m_name_index: HashMap::new(),
+            m_tag_index: HashMap::new(),
+            m_event_bus: event_bus,
+            m_scheduler: scheduler,
+            m_entity_commands: entity_commands,
         }
     }
 
@@ -28,6 +52,112 @@ impl EntitySpawner {
         self.m_entities.iter_mut().map(Box::as_mut)
     }
 
+    pub fn get_entity(&self, entity_id: EntityId) -> Option<&Entity> {
+        let index = *self.m_id_index.get(&entity_id)?;
+        Some(self.m_entities[index].as_ref())
+    }
+
+    pub fn get_entity_mut(&mut self, entity_id: EntityId) -> Option<&mut Entity> {
+        let index = *self.m_id_index.get(&entity_id)?;
+        Some(self.m_entities[index].as_mut())
+    }
+
+    // Also applies to entities that are waiting to be spawned
+    pub fn set_entity_ticking(&mut self, entity_id: EntityId, is_ticking: bool) {
+        let entity = self
+            .m_entities
+            .iter_mut()
+            .chain(self.m_entity_spawn_requests.iter_mut())
+            .find(|entity| entity.get_id() == entity_id);
+
+        if let Some(entity) = entity {
+            entity.set_is_ticking(is_ticking);
+        }
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<&Entity> {
+        let entity_id = *self.m_name_index.get(name)?;
+        self.get_entity(entity_id)
+    }
+
+    pub fn find_by_name_mut(&mut self, name: &str) -> Option<&mut Entity> {
+        let entity_id = *self.m_name_index.get(name)?;
+        self.get_entity_mut(entity_id)
+    }
+
+    // In spawn order, like entity_iter()
+    pub fn iter_tagged(&self, tag: &str) -> impl Iterator<Item = &Entity> {
+        self.get_tagged_indices(tag)
+            .into_iter()
+            .map(|index| self.m_entities[index].as_ref())
+    }
+
+    pub fn iter_tagged_mut(&mut self, tag: &str) -> impl Iterator<Item = &mut Entity> {
+        let indices = self.get_tagged_indices(tag);
+
+        // The indices are sorted, so each entity is split off the front of the rest
+        let mut rest = self.m_entities.as_mut_slice();
+        let mut offset = 0;
+        indices.into_iter().map(move |index| {
+            let (_, tail) = mem::take(&mut rest).split_at_mut(index - offset);
+            let (entity, tail) = tail.split_first_mut().unwrap();
+            rest = tail;
+            offset = index + 1;
+            entity.as_mut()
+        })
+    }
+
+    pub fn set_entity_name(
+        &mut self,
+        entity_id: EntityId,
+        name: Option<&str>,
+    ) -> Result<(), String> {
+        if let Some(name) = name
+            && let Some(owner_id) = self.m_name_index.get(name)
+            && *owner_id != entity_id
+        {
+            return Err(format!(
+                "Entity name '{}' is already used by 'entity_id={}'",
+                name, owner_id
+            ));
+        }
+
+        let index = *self
+            .m_id_index
+            .get(&entity_id)
+            .ok_or_else(|| format!("Entity with 'entity_id={}' not found", entity_id))?;
+        let entity = &mut self.m_entities[index];
+
+        if let Some(old_name) = entity.get_name() {
+            self.m_name_index.remove(old_name);
+        }
+
+        if let Some(name) = name {
+            self.m_name_index.insert(name.to_string(), entity_id);
+        }
+
+        entity.set_name_unchecked(name.map(str::to_string));
+
+        Ok(())
+    }
+
+    pub fn add_entity_tag(&mut self, entity_id: EntityId, tag: &str) {
+        if let Some(entity) = self.get_entity_mut(entity_id) {
+            entity.get_tags_mut_unchecked().insert(tag.to_string());
+            self.m_tag_index
+                .entry(tag.to_string())
+                .or_default()
+                .insert(entity_id);
+        }
+    }
+
+    pub fn remove_entity_tag(&mut self, entity_id: EntityId, tag: &str) {
+        if let Some(entity) = self.get_entity_mut(entity_id) {
+            entity.get_tags_mut_unchecked().remove(tag);
+            self.unindex_tag(tag, entity_id);
+        }
+    }
+
     pub fn spawn_entity(&mut self, mut entity: Box<Entity>) -> EntityId {
         let entity_id = self.m_next_entity_id;
         self.m_next_entity_id += 1;
@@ -47,7 +177,117 @@ impl EntitySpawner {
         self.m_entity_destroy_requests.insert(entity_id);
     }
 
+    // Captures the entities in play. Pending spawn requests are not included.
+    pub fn save_world_state(&self, assets: &Assets) -> Result<WorldState, String> {
+        let ctx = SaveContext::new(assets);
+        let mut world_state = WorldState::new();
+
+        for entity in self.entity_iter() {
+            let mut components: Vec<ComponentState> = Vec::new();
+            for comp in entity.component_iter() {
+                let data = comp.save_state(&ctx).map_err(|err| {
+                    format!(
+                        "Failed to save '{}' of 'entity_id={}': {}",
+                        comp.get_type_name(),
+                        entity.get_id(),
+                        err
+                    )
+                })?;
+
+                components.push(ComponentState {
+                    type_name: comp.get_type_name().to_string(),
+                    data,
+                });
+            }
+
+            let mut tags: Vec<String> = entity.get_tags().map(str::to_string).collect();
+            tags.sort();
+
+            world_state.entities.push(EntityState {
+                id: entity.get_id(),
+                name: entity.get_name().map(str::to_string),
+                tags,
+                components,
+            });
+        }
+
+        Ok(world_state)
+    }
+
+    // Destroys all entities and spawns the ones from the world state with their saved ids.
+    // Must not be called while the entities are being ticked.
+    // Returns the ids of the restored entities and the textures loaded to restore them.
+    pub fn restore_world_state(
+        &mut self,
+        world_state: &WorldState,
+        registry: &ComponentRegistry,
+        assets: &mut Assets,
+    ) -> Result<(Vec<EntityId>, Vec<TextureId>), String> {
+        let mut ctx = LoadContext::new(assets);
+
+        // Build everything first, so a failed restore leaves the world untouched
+        let mut entities: Vec<Box<Entity>> = Vec::new();
+        for entity_state in &world_state.entities {
+            let mut entity = Entity::new();
+            entity.set_id(entity_state.id);
+
+            if let Some(name) = &entity_state.name {
+                entity.set_name(name);
+            }
+
+            for tag in &entity_state.tags {
+                entity.add_tag(tag);
+            }
+
+            for comp_state in &entity_state.components {
+                let mut comp = registry.create(&comp_state.type_name)?;
+                if let Some(data) = &comp_state.data {
+                    comp.load_state(data, &mut ctx).map_err(|err| {
+                        format!(
+                            "Failed to load '{}' of 'entity_id={}': {}",
+                            comp_state.type_name, entity_state.id, err
+                        )
+                    })?;
+                }
+
+                // Saved in priority order, so requirements may come after their dependents
+                entity.add_boxed_component_unchecked(comp).map_err(|err| {
+                    format!("Failed to restore 'entity_id={}': {}", entity_state.id, err)
+                })?;
+            }
+
+            entity.check_requirements().map_err(|err| {
+                format!("Failed to restore 'entity_id={}': {}", entity_state.id, err)
+            })?;
+
+            entities.push(entity);
+        }
+
+        // Destroy the current entities right away, so their ids can be reused
+        self.m_entity_spawn_requests.clear();
+        self.m_entity_commands.clear();
+        self.m_entity_destroy_requests
+            .extend(self.m_entities.iter().map(|entity| entity.get_id()));
+        self.resolve_destroy_requests();
+
+        let entity_ids: Vec<EntityId> = entities.iter().map(|entity| entity.get_id()).collect();
+        if let Some(max_id) = entity_ids.iter().max() {
+            self.m_next_entity_id = self.m_next_entity_id.max(max_id + 1);
+        }
+
+        self.m_entity_spawn_requests.extend(entities);
+
+        Ok((entity_ids, ctx.take_loaded_textures()))
+    }
+
     pub fn resolve_requests(&mut self) {
+        for entity in self.m_entity_commands.take_spawn_requests() {
+            self.spawn_entity(entity);
+        }
+        for entity_id in self.m_entity_commands.take_destroy_requests() {
+            self.destroy_entity(entity_id);
+        }
+
         self.resolve_spawn_requests();
         self.resolve_destroy_requests();
     }
@@ -56,7 +296,19 @@ impl EntitySpawner {
         // Take memory, because an entity might make a new spawn request in enter_play()
         let mut spawn_requests = mem::take(&mut self.m_entity_spawn_requests);
 
-        for entity in spawn_requests.drain(..) {
+        for mut entity in spawn_requests.drain(..) {
+            if let Err(err) = entity.check_requirements() {
+                eprintln!(
+                    "Entity with 'entity_id={}' was not spawned: {}",
+                    entity.get_id(),
+                    err
+                );
+                continue;
+            }
+
+            self.index_entity(&mut entity);
+            self.m_id_index
+                .insert(entity.get_id(), self.m_entities.len());
             self.m_entities.push(entity);
             self.m_entities.last_mut().unwrap().enter_play();
         }
@@ -66,13 +318,169 @@ impl EntitySpawner {
         // Take memory, because an entity might make a new destroy request in exit_play()
         let destroy_requests = mem::take(&mut self.m_entity_destroy_requests);
 
-        self.m_entities.retain_mut(|entity| {
-            let should_destroy = destroy_requests.contains(&entity.get_id());
-            if should_destroy {
-                entity.exit_play();
+        let destroyed: Vec<Box<Entity>> = self
+            .m_entities
+            .extract_if(.., |entity| destroy_requests.contains(&entity.get_id()))
+            .collect();
+
+        if !destroyed.is_empty() {
+            self.rebuild_id_index();
+        }
+
+        for mut entity in destroyed {
+            entity.exit_play();
+            self.m_event_bus.unsubscribe_entity(entity.get_id());
+            self.m_scheduler.cancel_entity(entity.get_id());
+            self.unindex_entity(&entity);
+        }
+    }
+
+    // The entities after a destroyed one have moved
+    fn rebuild_id_index(&mut self) {
+        self.m_id_index.clear();
+        for (index, entity) in self.m_entities.iter().enumerate() {
+            self.m_id_index.insert(entity.get_id(), index);
+        }
+    }
+
+    fn get_tagged_indices(&self, tag: &str) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .m_tag_index
+            .get(tag)
+            .into_iter()
+            .flatten()
+            .filter_map(|entity_id| self.m_id_index.get(entity_id).copied())
+            .collect();
+        indices.sort_unstable();
+        indices
+    }
+
+    fn index_entity(&mut self, entity: &mut Entity) {
+        let entity_id = entity.get_id();
+
+        if let Some(name) = entity.get_name() {
+            // A name may be reused by an entity that is being destroyed this frame
+            // (spawns are resolved before destroys), so only live owners conflict.
+            let conflict = self.m_name_index.get(name).copied().filter(|owner_id| {
+                *owner_id != entity_id && !self.m_entity_destroy_requests.contains(owner_id)
+            });
+
+            if let Some(owner_id) = conflict {
+                eprintln!(
+                    "Entity name '{}' is already used by 'entity_id={}', clearing the name of 'entity_id={}'",
+                    name, owner_id, entity_id
+                );
+                entity.set_name_unchecked(None);
+            } else {
+                self.m_name_index.insert(name.to_string(), entity_id);
             }
+        }
+
+        for tag in entity.get_tags() {
+            self.m_tag_index
+                .entry(tag.to_string())
+                .or_default()
+                .insert(entity_id);
+        }
+    }
+
+    fn unindex_entity(&mut self, entity: &Entity) {
+        let entity_id = entity.get_id();
+
+        // The name may already belong to a newer entity
+        if let Some(name) = entity.get_name()
+            && self.m_name_index.get(name) == Some(&entity_id)
+        {
+            self.m_name_index.remove(name);
+        }
+
+        for tag in entity.get_tags() {
+            self.unindex_tag(tag, entity_id);
+        }
+    }
+
+    fn unindex_tag(&mut self, tag: &str, entity_id: EntityId) {
+        if let Some(ids) = self.m_tag_index.get_mut(tag) {
+            ids.remove(&entity_id);
+
+            if ids.is_empty() {
+                self.m_tag_index.remove(tag);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_spawner() -> EntitySpawner {
+        EntitySpawner::new(
+            Rc::new(EventBus::new()),
+            Rc::new(Scheduler::new()),
+            Rc::new(EntityCommands::new()),
+        )
+    }
+
+    fn spawn_tagged(spawner: &mut EntitySpawner, tag: &str) -> EntityId {
+        let mut entity = Entity::new();
+        entity.add_tag(tag);
+        spawner.spawn_entity(entity)
+    }
+
+    fn get_tagged_ids(spawner: &EntitySpawner, tag: &str) -> Vec<EntityId> {
+        spawner.iter_tagged(tag).map(Entity::get_id).collect()
+    }
+
+    #[test]
+    fn tag_and_id_lookups_follow_spawns_and_destroys() {
+        let mut spawner = create_spawner();
+        let enemy_a = spawn_tagged(&mut spawner, "enemy");
+        let player = spawn_tagged(&mut spawner, "player");
+        let enemy_b = spawn_tagged(&mut spawner, "enemy");
+        let enemy_c = spawn_tagged(&mut spawner, "enemy");
+        spawner.resolve_requests();
+
+        assert_eq!(
+            get_tagged_ids(&spawner, "enemy"),
+            vec![enemy_a, enemy_b, enemy_c]
+        );
+        assert_eq!(get_tagged_ids(&spawner, "player"), vec![player]);
+        assert!(get_tagged_ids(&spawner, "missing").is_empty());
+
+        spawner.destroy_entity(enemy_a);
+        spawner.resolve_requests();
+
+        assert!(spawner.get_entity(enemy_a).is_none());
+        assert_eq!(
+            spawner.get_entity(enemy_c).map(Entity::get_id),
+            Some(enemy_c)
+        );
+        assert_eq!(spawner.get_entity(player).map(Entity::get_id), Some(player));
+        assert_eq!(get_tagged_ids(&spawner, "enemy"), vec![enemy_b, enemy_c]);
+
+        let mutated: Vec<EntityId> = spawner
+            .iter_tagged_mut("enemy")
+            .map(|entity| {
+                entity.set_is_ticking(false);
+                entity.get_id()
+            })
+            .collect();
+        assert_eq!(mutated, vec![enemy_b, enemy_c]);
+        assert!(!spawner.get_entity(enemy_c).unwrap().is_ticking());
+        assert!(spawner.get_entity(player).unwrap().is_ticking());
+    }
+
+    #[test]
+    fn runtime_tag_changes_are_indexed() {
+        let mut spawner = create_spawner();
+        let entity_id = spawn_tagged(&mut spawner, "enemy");
+        spawner.resolve_requests();
+
+        spawner.add_entity_tag(entity_id, "boss");
+        assert_eq!(get_tagged_ids(&spawner, "boss"), vec![entity_id]);
 
-            !should_destroy
-        });
+        spawner.remove_entity_tag(entity_id, "enemy");
+        assert!(get_tagged_ids(&spawner, "enemy").is_empty());
     }
 }
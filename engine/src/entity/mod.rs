@@ -1,6 +1,8 @@
 pub mod entity;
+pub mod entity_commands;
 pub mod entity_spawner;
 
 // Re-exports
 pub use entity::*;
+pub use entity_commands::*;
 pub use entity_spawner::*;
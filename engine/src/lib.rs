@@ -1,4 +1,15 @@
+// Lets the code generated by engine_derive refer to `::engine` from inside this crate as well
+extern crate self as engine;
+
 pub mod components;
 pub mod core;
 pub mod entity;
 pub mod math;
+pub mod physics;
+#[cfg(any(test, feature = "test-fixtures"))]
+pub mod test_fixtures;
+
+// Re-exports
+pub use inventory;
+pub use sdl2;
+pub use serde_json;
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::math::{Rect, Vec2};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Circle {
+    pub center: Vec2,
+    pub radius: f32,
+}
+
+impl Circle {
+    pub fn new(center: Vec2, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::from_center_size(self.center, Vec2::one() * (self.radius * 2.0))
+    }
+
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        Vec2::distance_sqr(self.center, point) <= self.radius * self.radius
+    }
+
+    pub fn intersects_circle(&self, other: &Circle) -> bool {
+        let radius_sum = self.radius + other.radius;
+        Vec2::distance_sqr(self.center, other.center) < radius_sum * radius_sum
+    }
+
+    pub fn intersects_rect(&self, rect: &Rect) -> bool {
+        let closest = rect.closest_point(self.center);
+        Vec2::distance_sqr(self.center, closest) < self.radius * self.radius
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::property_test::{check, random_circle, random_f32, random_rect};
+
+    // Cases this close to touching are left to rounding
+    const EDGE_TOLERANCE: f32 = 1e-2;
+
+    #[test]
+    fn circle_intersection_is_symmetric_and_matches_the_distance() {
+        let mut hit_count = 0;
+        check(|rng| {
+            let a = random_circle(rng);
+            let b = random_circle(rng);
+
+            assert_eq!(a.intersects_circle(&b), b.intersects_circle(&a));
+
+            let gap = Vec2::distance(a.center, b.center) - (a.radius + b.radius);
+            if gap.abs() > EDGE_TOLERANCE {
+                assert_eq!(a.intersects_circle(&b), gap < 0.0, "{:?} {:?}", a, b);
+            }
+            if a.contains_point(b.center) {
+                assert!(a.intersects_circle(&b));
+            }
+            if a.intersects_circle(&b) {
+                hit_count += 1;
+            }
+        });
+
+        assert!(hit_count > 0);
+    }
+
+    #[test]
+    fn rect_intersection_matches_the_closest_point() {
+        let mut hit_count = 0;
+        check(|rng| {
+            let circle = random_circle(rng);
+            let rect = random_rect(rng);
+            let intersects = circle.intersects_rect(&rect);
+
+            if rect.contains_point(circle.center) {
+                assert!(intersects);
+            }
+            if intersects {
+                hit_count += 1;
+                assert!(circle.contains_point(rect.closest_point(circle.center)));
+                assert!(circle.bounds().intersects(&rect));
+            }
+
+            // A point of the rect well inside the circle means they intersect
+            let point = rect.pos
+                + rect.size * Vec2::from_xy(random_f32(rng, 0.0, 1.0), random_f32(rng, 0.0, 1.0));
+            if Vec2::distance(circle.center, point) < circle.radius - EDGE_TOLERANCE {
+                assert!(intersects, "{:?} {:?}", circle, rect);
+            }
+        });
+
+        assert!(hit_count > 0);
+    }
+}
@@ -0,0 +1,165 @@
+use std::f32::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BackIn,
+    BackOut,
+    BackInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+impl Easing {
+    // Maps t in [0, 1] to the eased progress. Elastic and back overshoot outside of [0, 1].
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        // Exact at the ends, which the formulas only reach up to rounding
+        if t == 0.0 || t == 1.0 {
+            return t;
+        }
+
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::ElasticIn => {
+                let c4 = (2.0 * PI) / 3.0;
+                -(2.0_f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * c4).sin()
+            }
+            Easing::ElasticOut => {
+                let c4 = (2.0 * PI) / 3.0;
+                2.0_f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * c4).sin() + 1.0
+            }
+            Easing::ElasticInOut => {
+                let c5 = (2.0 * PI) / 4.5;
+                if t < 0.5 {
+                    -(2.0_f32.powf(20.0 * t - 10.0) * ((20.0 * t - 11.125) * c5).sin()) / 2.0
+                } else {
+                    (2.0_f32.powf(-20.0 * t + 10.0) * ((20.0 * t - 11.125) * c5).sin()) / 2.0 + 1.0
+                }
+            }
+            Easing::BackIn => {
+                let c1 = 1.70158;
+                let c3 = c1 + 1.0;
+                c3 * t * t * t - c1 * t * t
+            }
+            Easing::BackOut => {
+                let c1 = 1.70158;
+                let c3 = c1 + 1.0;
+                1.0 + c3 * (t - 1.0).powi(3) + c1 * (t - 1.0).powi(2)
+            }
+            Easing::BackInOut => {
+                let c2 = 1.70158 * 1.525;
+                if t < 0.5 {
+                    ((2.0 * t).powi(2) * ((c2 + 1.0) * 2.0 * t - c2)) / 2.0
+                } else {
+                    ((2.0 * t - 2.0).powi(2) * ((c2 + 1.0) * (t * 2.0 - 2.0) + c2) + 2.0) / 2.0
+                }
+            }
+            Easing::BounceIn => 1.0 - bounce_out(1.0 - t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => {
+                if t < 0.5 {
+                    (1.0 - bounce_out(1.0 - 2.0 * t)) / 2.0
+                } else {
+                    (1.0 + bounce_out(2.0 * t - 1.0)) / 2.0
+                }
+            }
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    let n1 = 7.5625;
+    let d1 = 2.75;
+
+    if t < 1.0 / d1 {
+        n1 * t * t
+    } else if t < 2.0 / d1 {
+        let t = t - 1.5 / d1;
+        n1 * t * t + 0.75
+    } else if t < 2.5 / d1 {
+        let t = t - 2.25 / d1;
+        n1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / d1;
+        n1 * t * t + 0.984375
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_EASINGS: [Easing; 16] = [
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::ElasticIn,
+        Easing::ElasticOut,
+        Easing::ElasticInOut,
+        Easing::BackIn,
+        Easing::BackOut,
+        Easing::BackInOut,
+        Easing::BounceIn,
+        Easing::BounceOut,
+        Easing::BounceInOut,
+    ];
+
+    #[test]
+    fn every_easing_starts_at_0_and_ends_at_1() {
+        for easing in ALL_EASINGS {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+            // Clamped outside of [0, 1]
+            assert_eq!(easing.apply(-1.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(2.0), 1.0, "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn in_out_easings_are_halfway_at_the_middle() {
+        for easing in [
+            Easing::Linear,
+            Easing::QuadInOut,
+            Easing::CubicInOut,
+            Easing::BackInOut,
+            Easing::BounceInOut,
+        ] {
+            assert!((easing.apply(0.5) - 0.5).abs() < 1e-5, "{:?}", easing);
+        }
+    }
+}
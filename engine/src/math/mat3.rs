@@ -0,0 +1,194 @@
+use std::ops::{Mul, MulAssign};
+
+use serde::{Deserialize, Serialize};
+
+use crate::math::Vec2;
+
+// 3x3 row-major matrix for 2D affine transforms. Points are column vectors, so
+// a * b applies b first.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Mat3 {
+    pub m: [[f32; 3]; 3],
+}
+
+impl Mat3 {
+    pub fn identity() -> Self {
+        Self {
+            m: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    pub fn from_translation(translation: Vec2) -> Self {
+        Self {
+            m: [
+                [1.0, 0.0, translation.x],
+                [0.0, 1.0, translation.y],
+                [0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    // Positive angles rotate clockwise on screen, since y points down
+    pub fn from_rotation(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self {
+            m: [[cos, -sin, 0.0], [sin, cos, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    pub fn from_scale(scale: Vec2) -> Self {
+        Self {
+            m: [[scale.x, 0.0, 0.0], [0.0, scale.y, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    // Scale, then rotation, then translation
+    pub fn from_trs(translation: Vec2, rotation: f32, scale: Vec2) -> Self {
+        Self::from_translation(translation)
+            * Self::from_rotation(rotation)
+            * Self::from_scale(scale)
+    }
+
+    pub fn get_translation(&self) -> Vec2 {
+        Vec2::from_xy(self.m[0][2], self.m[1][2])
+    }
+
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        Vec2::from_xy(
+            self.m[0][0] * point.x + self.m[0][1] * point.y + self.m[0][2],
+            self.m[1][0] * point.x + self.m[1][1] * point.y + self.m[1][2],
+        )
+    }
+
+    // Ignores the translation
+    pub fn transform_vector(&self, vector: Vec2) -> Vec2 {
+        Vec2::from_xy(
+            self.m[0][0] * vector.x + self.m[0][1] * vector.y,
+            self.m[1][0] * vector.x + self.m[1][1] * vector.y,
+        )
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let m = &self.m;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    // None for singular matrices, e.g. with a zero scale
+    pub fn inverse(&self) -> Option<Mat3> {
+        let det = self.determinant();
+        if det.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let m = &self.m;
+        let inv_det = 1.0 / det;
+        let mut result = [[0.0; 3]; 3];
+        for (row, result_row) in result.iter_mut().enumerate() {
+            for (col, value) in result_row.iter_mut().enumerate() {
+                // Transposed cofactor
+                let (r0, r1) = other_indices(col);
+                let (c0, c1) = other_indices(row);
+                let minor = m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0];
+                let sign = if (row + col) % 2 == 0 { 1.0 } else { -1.0 };
+                *value = sign * minor * inv_det;
+            }
+        }
+
+        Some(Mat3 { m: result })
+    }
+
+    pub fn transposed(&self) -> Mat3 {
+        let mut result = [[0.0; 3]; 3];
+        for (row, result_row) in result.iter_mut().enumerate() {
+            for (col, value) in result_row.iter_mut().enumerate() {
+                *value = self.m[col][row];
+            }
+        }
+        Mat3 { m: result }
+    }
+}
+
+fn other_indices(index: usize) -> (usize, usize) {
+    match index {
+        0 => (1, 2),
+        1 => (0, 2),
+        _ => (0, 1),
+    }
+}
+
+// Mat3 * Mat3
+impl Mul for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, other: Mat3) -> Mat3 {
+        let mut result = [[0.0; 3]; 3];
+        for (row, result_row) in result.iter_mut().enumerate() {
+            for (col, value) in result_row.iter_mut().enumerate() {
+                *value = (0..3).map(|i| self.m[row][i] * other.m[i][col]).sum();
+            }
+        }
+        Mat3 { m: result }
+    }
+}
+
+// Mat3 * Vec2, treats the vector as a point
+impl Mul<Vec2> for Mat3 {
+    type Output = Vec2;
+
+    fn mul(self, point: Vec2) -> Vec2 {
+        self.transform_point(point)
+    }
+}
+
+// Mat3 *= Mat3
+impl MulAssign for Mat3 {
+    fn mul_assign(&mut self, other: Mat3) {
+        *self = *self * other;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::property_test::{check, random_angle, random_scale, random_vec2};
+
+    #[test]
+    fn product_with_inverse_is_identity() {
+        check(|rng| {
+            let translation = random_vec2(rng);
+            let mat = Mat3::from_trs(translation, random_angle(rng), random_scale(rng));
+            let identity = Mat3::identity();
+            // Rounding grows with the size of the translation
+            let tolerance = 1e-5 * (1.0 + translation.len());
+
+            for product in [mat * mat.inverse().unwrap(), mat.inverse().unwrap() * mat] {
+                for row in 0..3 {
+                    for col in 0..3 {
+                        assert!(
+                            (product.m[row][col] - identity.m[row][col]).abs() <= tolerance,
+                            "{:?}",
+                            product
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn zero_scale_has_no_inverse() {
+        let mat = Mat3::from_trs(Vec2::one(), 1.0, Vec2::from_xy(0.0, 2.0));
+        assert!(mat.inverse().is_none());
+    }
+
+    #[test]
+    fn serializes_as_rows() {
+        let mat = Mat3::from_translation(Vec2::from_xy(3.0, 4.0));
+        let json = serde_json::to_string(&mat).unwrap();
+
+        assert_eq!(json, r#"{"m":[[1.0,0.0,3.0],[0.0,1.0,4.0],[0.0,0.0,1.0]]}"#);
+        assert_eq!(serde_json::from_str::<Mat3>(&json).unwrap(), mat);
+    }
+}
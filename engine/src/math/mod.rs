@@ -1,4 +1,22 @@
+pub mod circle;
+pub mod easing;
+pub mod mat3;
+pub mod noise;
+#[cfg(test)]
+mod property_test;
+pub mod random;
+pub mod rect;
+pub mod segment;
+pub mod transform2d;
 pub mod vec2;
 
 // Re-exports
+pub use circle::*;
+pub use easing::*;
+pub use mat3::*;
+pub use noise::*;
+pub use random::*;
+pub use rect::*;
+pub use segment::*;
+pub use transform2d::*;
 pub use vec2::*;
@@ -0,0 +1,24 @@
+// Smooth 1D value noise in [-1, 1]. Continuous in `x`, with about one bump per unit.
+// Different seeds give unrelated curves, e.g. one per shake axis.
+pub fn value_noise(seed: u32, x: f32) -> f32 {
+    let cell = x.floor();
+    let t = x - cell;
+    let a = hash_to_unit(seed, cell as i32);
+    let b = hash_to_unit(seed, cell as i32 + 1);
+
+    // Smoothstep, so the curve has no kinks at the lattice points
+    let t = t * t * (3.0 - 2.0 * t);
+    a + (b - a) * t
+}
+
+// Pseudo random value in [-1, 1] for a lattice point
+fn hash_to_unit(seed: u32, index: i32) -> f32 {
+    let mut hash = (index as u32).wrapping_mul(0x27d4_eb2d) ^ seed.wrapping_mul(0x1656_67b1);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x2c1b_3c6d);
+    hash ^= hash >> 12;
+    hash = hash.wrapping_mul(0x297a_2d39);
+    hash ^= hash >> 15;
+
+    (hash as f32 / u32::MAX as f32) * 2.0 - 1.0
+}
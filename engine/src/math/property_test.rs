@@ -0,0 +1,74 @@
+// Seeded random inputs for the math property tests, so failures are reproducible
+use fastrand::Rng;
+
+use crate::math::{Circle, Rect, Vec2};
+
+const CASE_COUNT: usize = 1000;
+const SEED: u64 = 0x5eed;
+// Coordinates stay within a few screens, like the game's world positions
+const COORD_RANGE: f32 = 1000.0;
+const MAX_SIZE: f32 = 200.0;
+
+// Runs the property once per generated case
+pub fn check<F>(mut property: F)
+where
+    F: FnMut(&mut Rng),
+{
+    let mut rng = Rng::with_seed(SEED);
+    for _ in 0..CASE_COUNT {
+        property(&mut rng);
+    }
+}
+
+pub fn random_f32(rng: &mut Rng, min: f32, max: f32) -> f32 {
+    min + rng.f32() * (max - min)
+}
+
+pub fn random_vec2(rng: &mut Rng) -> Vec2 {
+    Vec2::from_xy(
+        random_f32(rng, -COORD_RANGE, COORD_RANGE),
+        random_f32(rng, -COORD_RANGE, COORD_RANGE),
+    )
+}
+
+pub fn random_unit_vec2(rng: &mut Rng) -> Vec2 {
+    Vec2::from_angle(random_f32(rng, -std::f32::consts::PI, std::f32::consts::PI))
+}
+
+pub fn random_angle(rng: &mut Rng) -> f32 {
+    random_f32(rng, -std::f32::consts::TAU, std::f32::consts::TAU)
+}
+
+// Never empty
+pub fn random_rect(rng: &mut Rng) -> Rect {
+    Rect::from_pos_size(
+        random_vec2(rng) * 0.1,
+        Vec2::from_xy(
+            random_f32(rng, 1.0, MAX_SIZE),
+            random_f32(rng, 1.0, MAX_SIZE),
+        ),
+    )
+}
+
+// Radius of at least 1, placed like random_rect so the two often overlap
+pub fn random_circle(rng: &mut Rng) -> Circle {
+    Circle::new(random_vec2(rng) * 0.1, random_f32(rng, 1.0, MAX_SIZE * 0.5))
+}
+
+// Non-zero on both axes and possibly mirrored
+pub fn random_scale(rng: &mut Rng) -> Vec2 {
+    let mut random_axis = || {
+        let scale = random_f32(rng, 0.25, 4.0);
+        if rng.bool() { scale } else { -scale }
+    };
+    Vec2::from_xy(random_axis(), random_axis())
+}
+
+// Relative to the magnitude, since f32 loses absolute precision on large values
+pub fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() <= 1e-3 * (1.0 + a.abs().max(b.abs()))
+}
+
+pub fn approx_eq_vec2(a: Vec2, b: Vec2) -> bool {
+    approx_eq(a.x, b.x) && approx_eq(a.y, b.y)
+}
@@ -0,0 +1,4 @@
+// Uniform random value in [min, max)
+pub fn random_range(min: f32, max: f32) -> f32 {
+    min + fastrand::f32() * (max - min)
+}
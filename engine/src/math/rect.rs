@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+
+use crate::math::Vec2;
+
+// Axis-aligned rectangle, positioned by its top-left corner
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub pos: Vec2,
+    pub size: Vec2,
+}
+
+impl Rect {
+    pub fn new(x: f32, y: f32, w: f32, h: f32) -> Self {
+        Self {
+            pos: Vec2::from_xy(x, y),
+            size: Vec2::from_xy(w, h),
+        }
+    }
+
+    pub fn from_pos_size(pos: Vec2, size: Vec2) -> Self {
+        Self { pos, size }
+    }
+
+    pub fn from_center_size(center: Vec2, size: Vec2) -> Self {
+        Self {
+            pos: center - size * 0.5,
+            size,
+        }
+    }
+
+    // The corners can be given in any order
+    pub fn from_corners(a: Vec2, b: Vec2) -> Self {
+        let min = Vec2::min(a, b);
+        Self {
+            pos: min,
+            size: Vec2::max(a, b) - min,
+        }
+    }
+
+    pub fn min(&self) -> Vec2 {
+        self.pos
+    }
+
+    pub fn max(&self) -> Vec2 {
+        self.pos + self.size
+    }
+
+    pub fn center(&self) -> Vec2 {
+        self.pos + self.size * 0.5
+    }
+
+    pub fn half_size(&self) -> Vec2 {
+        self.size * 0.5
+    }
+
+    pub fn left(&self) -> f32 {
+        self.pos.x
+    }
+
+    pub fn right(&self) -> f32 {
+        self.pos.x + self.size.x
+    }
+
+    pub fn top(&self) -> f32 {
+        self.pos.y
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.pos.y + self.size.y
+    }
+
+    pub fn area(&self) -> f32 {
+        self.size.x * self.size.y
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size.x <= 0.0 || self.size.y <= 0.0
+    }
+
+    // The max edges are exclusive, so a point is never inside two adjacent rects
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        point.x >= self.left()
+            && point.x < self.right()
+            && point.y >= self.top()
+            && point.y < self.bottom()
+    }
+
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.left() >= self.left()
+            && other.right() <= self.right()
+            && other.top() >= self.top()
+            && other.bottom() <= self.bottom()
+    }
+
+    // Rects that only touch at an edge don't intersect
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.left() < other.right()
+            && other.left() < self.right()
+            && self.top() < other.bottom()
+            && other.top() < self.bottom()
+    }
+
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        if !self.intersects(other) {
+            return None;
+        }
+
+        Some(Rect::from_corners(
+            Vec2::max(self.min(), other.min()),
+            Vec2::min(self.max(), other.max()),
+        ))
+    }
+
+    // Smallest rect containing both
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect::from_corners(
+            Vec2::min(self.min(), other.min()),
+            Vec2::max(self.max(), other.max()),
+        )
+    }
+
+    // Smallest translation that moves this rect out of the other one, along a single axis.
+    // None when the rects don't intersect.
+    pub fn overlap_depth(&self, other: &Rect) -> Option<Vec2> {
+        if !self.intersects(other) {
+            return None;
+        }
+
+        let push_left = other.left() - self.right();
+        let push_right = other.right() - self.left();
+        let push_up = other.top() - self.bottom();
+        let push_down = other.bottom() - self.top();
+
+        let x = if -push_left < push_right {
+            push_left
+        } else {
+            push_right
+        };
+        let y = if -push_up < push_down {
+            push_up
+        } else {
+            push_down
+        };
+
+        if x.abs() < y.abs() {
+            Some(Vec2::from_xy(x, 0.0))
+        } else {
+            Some(Vec2::from_xy(0.0, y))
+        }
+    }
+
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        Vec2::from_xy(
+            point.x.clamp(self.left(), self.right()),
+            point.y.clamp(self.top(), self.bottom()),
+        )
+    }
+
+    pub fn translated(&self, offset: Vec2) -> Rect {
+        Rect::from_pos_size(self.pos + offset, self.size)
+    }
+
+    // Grows every edge outwards, negative amounts shrink
+    pub fn expanded(&self, amount: Vec2) -> Rect {
+        Rect::from_pos_size(self.pos - amount, self.size + amount * 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::property_test::{check, random_rect, random_vec2};
+
+    // Allows for the rounding of edges computed as pos + size
+    const EDGE_TOLERANCE: f32 = 1e-3;
+
+    #[test]
+    fn intersection_is_inside_both_rects() {
+        check(|rng| {
+            let a = random_rect(rng);
+            let b = random_rect(rng);
+
+            assert_eq!(a.intersection(&b), b.intersection(&a));
+            match a.intersection(&b) {
+                Some(intersection) => {
+                    assert!(a.intersects(&b));
+                    assert!(!intersection.is_empty());
+                    assert!(
+                        a.expanded(Vec2::one() * EDGE_TOLERANCE)
+                            .contains_rect(&intersection)
+                    );
+                    assert!(
+                        b.expanded(Vec2::one() * EDGE_TOLERANCE)
+                            .contains_rect(&intersection)
+                    );
+                    assert!(a.contains_point(intersection.pos));
+                    assert!(b.contains_point(intersection.pos));
+                }
+                None => assert!(!a.intersects(&b)),
+            }
+        });
+    }
+
+    #[test]
+    fn union_contains_both_rects() {
+        check(|rng| {
+            let a = random_rect(rng);
+            let b = random_rect(rng);
+            let union = a.union(&b);
+
+            assert_eq!(union, b.union(&a));
+            assert!(
+                union
+                    .expanded(Vec2::one() * EDGE_TOLERANCE)
+                    .contains_rect(&a)
+            );
+            assert!(
+                union
+                    .expanded(Vec2::one() * EDGE_TOLERANCE)
+                    .contains_rect(&b)
+            );
+
+            let point = random_vec2(rng) * 0.1;
+            if a.contains_point(point) || b.contains_point(point) {
+                assert!(union.contains_point(point));
+            }
+        });
+    }
+
+    #[test]
+    fn contains_point_matches_closest_point() {
+        check(|rng| {
+            let rect = random_rect(rng);
+            let point = random_vec2(rng) * 0.1;
+
+            if rect.contains_point(point) {
+                assert_eq!(rect.closest_point(point), point);
+            } else {
+                assert!(
+                    rect.closest_point(point) != point
+                        || point.x == rect.right()
+                        || point.y == rect.bottom()
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn overlap_depth_is_the_smallest_single_axis_push_out() {
+        check(|rng| {
+            let a = random_rect(rng);
+            let b = random_rect(rng);
+            let Some(depth) = a.overlap_depth(&b) else {
+                assert!(!a.intersects(&b));
+                return;
+            };
+
+            assert!(depth.x == 0.0 || depth.y == 0.0);
+
+            let remaining_area = a
+                .translated(depth)
+                .intersection(&b)
+                .map_or(0.0, |intersection| intersection.area());
+            assert!(remaining_area <= EDGE_TOLERANCE * (a.size.x + a.size.y));
+
+            // Any shorter push leaves them overlapping
+            if depth.len() > EDGE_TOLERANCE {
+                assert!(a.translated(depth * 0.9).intersects(&b));
+            }
+        });
+    }
+}
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+
+use crate::math::{Rect, Vec2};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    pub start: Vec2,
+    pub end: Vec2,
+}
+
+// Where a segment enters a shape
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentHit {
+    pub point: Vec2,
+    pub normal: Vec2,
+    pub t: f32, // fraction of the segment, in [0, 1]
+}
+
+impl Segment {
+    pub fn new(start: Vec2, end: Vec2) -> Self {
+        Self { start, end }
+    }
+
+    pub fn len(&self) -> f32 {
+        Vec2::distance(self.start, self.end)
+    }
+
+    pub fn delta(&self) -> Vec2 {
+        self.end - self.start
+    }
+
+    pub fn direction(&self) -> Vec2 {
+        self.delta().normalized()
+    }
+
+    pub fn point_at(&self, t: f32) -> Vec2 {
+        Vec2::lerp(self.start, self.end, t)
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::from_corners(self.start, self.end)
+    }
+
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        let delta = self.delta();
+        let len_sqr = delta.len_sqr();
+        if len_sqr <= f32::EPSILON {
+            return self.start;
+        }
+
+        let t = (Vec2::dot(point - self.start, delta) / len_sqr).clamp(0.0, 1.0);
+        self.point_at(t)
+    }
+
+    // Parallel segments never intersect, even when they overlap
+    pub fn intersect_segment(&self, other: &Segment) -> Option<Vec2> {
+        let d1 = self.delta();
+        let d2 = other.delta();
+        let denom = Vec2::cross(d1, d2);
+        if denom.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let offset = other.start - self.start;
+        let t = Vec2::cross(offset, d2) / denom;
+        let u = Vec2::cross(offset, d1) / denom;
+        if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+            Some(self.point_at(t))
+        } else {
+            None
+        }
+    }
+
+    // Slab test. A segment starting inside the rect hits it at t = 0 with a zero normal.
+    pub fn intersect_rect(&self, rect: &Rect) -> Option<SegmentHit> {
+        let delta = self.delta();
+        let mut t_enter = 0.0_f32;
+        let mut t_exit = 1.0_f32;
+        let mut normal = Vec2::zero();
+
+        let axes = [
+            (
+                self.start.x,
+                delta.x,
+                rect.left(),
+                rect.right(),
+                Vec2::left(),
+            ),
+            (self.start.y, delta.y, rect.top(), rect.bottom(), Vec2::up()),
+        ];
+
+        for (start, delta, min, max, min_normal) in axes {
+            if delta.abs() <= f32::EPSILON {
+                if start < min || start > max {
+                    return None;
+                }
+                continue;
+            }
+
+            let mut t_min = (min - start) / delta;
+            let mut t_max = (max - start) / delta;
+            let mut axis_normal = min_normal;
+            if t_min > t_max {
+                std::mem::swap(&mut t_min, &mut t_max);
+                axis_normal = -min_normal;
+            }
+
+            if t_min > t_enter {
+                t_enter = t_min;
+                normal = axis_normal;
+            }
+            t_exit = t_exit.min(t_max);
+
+            if t_enter > t_exit {
+                return None;
+            }
+        }
+
+        Some(SegmentHit {
+            point: self.point_at(t_enter),
+            normal,
+            t: t_enter,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::property_test::{check, random_rect, random_vec2};
+
+    // Points sampled along a segment when looking for a closer one
+    const SAMPLE_COUNT: usize = 20;
+
+    // Allows for the rounding of the hit point
+    const EDGE_TOLERANCE: f32 = 1e-2;
+
+    #[test]
+    fn rect_hits_are_on_the_segment_and_the_rect() {
+        let mut hit_count = 0;
+        check(|rng| {
+            let segment = Segment::new(random_vec2(rng) * 0.2, random_vec2(rng) * 0.2);
+            let rect = random_rect(rng);
+            let Some(hit) = segment.intersect_rect(&rect) else {
+                return;
+            };
+
+            hit_count += 1;
+            assert!((0.0..=1.0).contains(&hit.t), "{:?}", hit);
+            assert_eq!(hit.point, segment.point_at(hit.t));
+            assert!(
+                rect.expanded(Vec2::one() * EDGE_TOLERANCE)
+                    .contains_point(hit.point),
+                "{:?} {:?}",
+                hit,
+                rect
+            );
+
+            // Zero only when the segment starts inside
+            if hit.normal == Vec2::zero() {
+                assert_eq!(hit.t, 0.0);
+            } else {
+                assert_eq!(hit.normal.len(), 1.0);
+                assert!(Vec2::dot(hit.normal, segment.delta()) <= 0.0);
+            }
+        });
+
+        assert!(hit_count > 0);
+    }
+
+    #[test]
+    fn closest_point_is_on_the_segment_and_closest() {
+        check(|rng| {
+            let segment = Segment::new(random_vec2(rng) * 0.2, random_vec2(rng) * 0.2);
+            let point = random_vec2(rng) * 0.2;
+            let closest = segment.closest_point(point);
+            let distance = Vec2::distance(point, closest);
+
+            assert!(
+                segment
+                    .bounds()
+                    .expanded(Vec2::one() * EDGE_TOLERANCE)
+                    .contains_point(closest)
+            );
+            assert!(
+                Vec2::cross(closest - segment.start, segment.direction()).abs() < EDGE_TOLERANCE
+            );
+            for index in 0..=SAMPLE_COUNT {
+                let sample = segment.point_at(index as f32 / SAMPLE_COUNT as f32);
+                assert!(Vec2::distance(point, sample) >= distance - EDGE_TOLERANCE);
+            }
+        });
+    }
+
+    #[test]
+    fn segment_hits_are_on_both_segments() {
+        let mut hit_count = 0;
+        check(|rng| {
+            let a = Segment::new(random_vec2(rng) * 0.2, random_vec2(rng) * 0.2);
+            let b = Segment::new(random_vec2(rng) * 0.2, random_vec2(rng) * 0.2);
+            let Some(point) = a.intersect_segment(&b) else {
+                return;
+            };
+
+            hit_count += 1;
+            assert!(Vec2::distance(a.closest_point(point), point) < EDGE_TOLERANCE);
+            assert!(Vec2::distance(b.closest_point(point), point) < EDGE_TOLERANCE);
+        });
+
+        assert!(hit_count > 0);
+
+        // Parallel, even when overlapping
+        let a = Segment::new(Vec2::zero(), Vec2::right() * 10.0);
+        let b = Segment::new(Vec2::right() * 5.0, Vec2::right() * 15.0);
+        assert_eq!(a.intersect_segment(&b), None);
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+use crate::math::{Mat3, Vec2};
+
+// Position, rotation and scale, applied as scale, then rotation, then translation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Transform2D {
+    pub position: Vec2,
+    pub rotation: f32, // radians
+    pub scale: Vec2,
+}
+
+impl Transform2D {
+    pub fn new(position: Vec2, rotation: f32, scale: Vec2) -> Self {
+        Self {
+            position,
+            rotation,
+            scale,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(Vec2::zero(), 0.0, Vec2::one())
+    }
+
+    pub fn from_position(position: Vec2) -> Self {
+        Self::new(position, 0.0, Vec2::one())
+    }
+
+    pub fn to_matrix(&self) -> Mat3 {
+        Mat3::from_trs(self.position, self.rotation, self.scale)
+    }
+
+    // Local space to the space the transform is defined in
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        (point * self.scale).rotate(self.rotation) + self.position
+    }
+
+    pub fn transform_vector(&self, vector: Vec2) -> Vec2 {
+        (vector * self.scale).rotate(self.rotation)
+    }
+
+    // Undoes transform_point(), points map to zero on a zero scale axis
+    pub fn inverse_transform_point(&self, point: Vec2) -> Vec2 {
+        let local = (point - self.position).rotate(-self.rotation);
+        Vec2::from_xy(
+            safe_div(local.x, self.scale.x),
+            safe_div(local.y, self.scale.y),
+        )
+    }
+
+    // This transform placed inside a parent. Exact as long as the parent scale is uniform,
+    // otherwise the result can't be expressed without shear.
+    pub fn combine(&self, parent: &Transform2D) -> Transform2D {
+        Transform2D {
+            position: parent.transform_point(self.position),
+            rotation: self.rotation + parent.rotation,
+            scale: self.scale * parent.scale,
+        }
+    }
+}
+
+fn safe_div(value: f32, divisor: f32) -> f32 {
+    if divisor.abs() <= f32::EPSILON {
+        0.0
+    } else {
+        value / divisor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::property_test::{
+        approx_eq_vec2, check, random_angle, random_scale, random_vec2,
+    };
+
+    #[test]
+    fn inverse_transform_point_round_trips() {
+        check(|rng| {
+            let transform =
+                Transform2D::new(random_vec2(rng), random_angle(rng), random_scale(rng));
+            let point = random_vec2(rng);
+
+            let round_trip = transform.inverse_transform_point(transform.transform_point(point));
+            assert!(
+                approx_eq_vec2(round_trip, point),
+                "{:?} {:?}",
+                round_trip,
+                point
+            );
+        });
+    }
+
+    #[test]
+    fn matrix_transforms_like_the_transform() {
+        check(|rng| {
+            let transform =
+                Transform2D::new(random_vec2(rng), random_angle(rng), random_scale(rng));
+            let point = random_vec2(rng);
+
+            assert!(approx_eq_vec2(
+                transform.to_matrix().transform_point(point),
+                transform.transform_point(point)
+            ));
+            assert!(approx_eq_vec2(
+                transform.to_matrix().transform_vector(point),
+                transform.transform_vector(point)
+            ));
+        });
+    }
+}
@@ -1,7 +1,8 @@
-use std::f32::EPSILON;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32,
@@ -46,7 +47,7 @@ impl Vec2 {
 
     pub fn normalized(&self) -> Vec2 {
         let len: f32 = self.len();
-        if len < EPSILON {
+        if len < f32::EPSILON {
             Vec2::zero()
         } else {
             Vec2::from_xy(self.x / len, self.y / len)
@@ -56,6 +57,87 @@ impl Vec2 {
     pub fn dot(a: Vec2, b: Vec2) -> f32 {
         a.x * b.x + a.y * b.y
     }
+
+    // Z component of the 3D cross product, positive when b is clockwise from a on screen
+    pub fn cross(a: Vec2, b: Vec2) -> f32 {
+        a.x * b.y - a.y * b.x
+    }
+
+    pub fn lerp(a: Vec2, b: Vec2, t: f32) -> Vec2 {
+        a + (b - a) * t
+    }
+
+    pub fn distance(a: Vec2, b: Vec2) -> f32 {
+        (b - a).len()
+    }
+
+    pub fn distance_sqr(a: Vec2, b: Vec2) -> f32 {
+        (b - a).len_sqr()
+    }
+
+    pub fn from_angle(angle: f32) -> Vec2 {
+        Vec2::from_xy(angle.cos(), angle.sin())
+    }
+
+    // Angle from the positive x axis in radians, in the range [-PI, PI]
+    pub fn angle(&self) -> f32 {
+        self.y.atan2(self.x)
+    }
+
+    // Signed angle in radians to rotate a onto b
+    pub fn angle_between(a: Vec2, b: Vec2) -> f32 {
+        Vec2::cross(a, b).atan2(Vec2::dot(a, b))
+    }
+
+    pub fn rotate(&self, angle: f32) -> Vec2 {
+        let (sin, cos) = angle.sin_cos();
+        Vec2::from_xy(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    // Rotated by 90 degrees
+    pub fn perp(&self) -> Vec2 {
+        Vec2::from_xy(-self.y, self.x)
+    }
+
+    // Projection onto another vector, zero if that vector is zero
+    pub fn project(&self, onto: Vec2) -> Vec2 {
+        let onto_len_sqr = onto.len_sqr();
+        if onto_len_sqr < f32::EPSILON {
+            Vec2::zero()
+        } else {
+            onto * (Vec2::dot(*self, onto) / onto_len_sqr)
+        }
+    }
+
+    // Reflection off a surface with the given unit normal
+    pub fn reflect(&self, normal: Vec2) -> Vec2 {
+        *self - normal * (2.0 * Vec2::dot(*self, normal))
+    }
+
+    pub fn clamp_len(&self, max_len: f32) -> Vec2 {
+        let len_sqr = self.len_sqr();
+        if len_sqr > max_len * max_len {
+            *self * (max_len / len_sqr.sqrt())
+        } else {
+            *self
+        }
+    }
+
+    pub fn abs(&self) -> Vec2 {
+        Vec2::from_xy(self.x.abs(), self.y.abs())
+    }
+
+    pub fn min(a: Vec2, b: Vec2) -> Vec2 {
+        Vec2::from_xy(a.x.min(b.x), a.y.min(b.y))
+    }
+
+    pub fn max(a: Vec2, b: Vec2) -> Vec2 {
+        Vec2::from_xy(a.x.max(b.x), a.y.max(b.y))
+    }
+
+    pub fn approx_eq(a: Vec2, b: Vec2, tolerance: f32) -> bool {
+        (a.x - b.x).abs() <= tolerance && (a.y - b.y).abs() <= tolerance
+    }
 }
 
 // Vec2 + Vec2
@@ -94,6 +176,39 @@ impl Mul<f32> for Vec2 {
     }
 }
 
+// Vec2 * Vec2 (component-wise)
+impl Mul for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, other: Vec2) -> Vec2 {
+        Vec2 {
+            x: self.x * other.x,
+            y: self.y * other.y,
+        }
+    }
+}
+
+// scalar * Vec2
+impl Mul<Vec2> for f32 {
+    type Output = Vec2;
+
+    fn mul(self, vec: Vec2) -> Vec2 {
+        vec * self
+    }
+}
+
+// Vec2 / Vec2 (component-wise)
+impl Div for Vec2 {
+    type Output = Vec2;
+
+    fn div(self, other: Vec2) -> Vec2 {
+        Vec2 {
+            x: self.x / other.x,
+            y: self.y / other.y,
+        }
+    }
+}
+
 // Vec2 / scalar
 impl Div<f32> for Vec2 {
     type Output = Vec2;
@@ -130,6 +245,14 @@ impl MulAssign<f32> for Vec2 {
     }
 }
 
+// Vec2 *= Vec2 (component-wise)
+impl MulAssign for Vec2 {
+    fn mul_assign(&mut self, other: Vec2) {
+        self.x *= other.x;
+        self.y *= other.y;
+    }
+}
+
 // Vec2 /= scalar
 impl DivAssign<f32> for Vec2 {
     fn div_assign(&mut self, scalar: f32) {
@@ -149,3 +272,144 @@ impl Neg for Vec2 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::property_test::{
+        approx_eq, approx_eq_vec2, check, random_angle, random_f32, random_unit_vec2, random_vec2,
+    };
+
+    #[test]
+    fn rotate_preserves_length() {
+        check(|rng| {
+            let vec = random_vec2(rng);
+            let rotated = vec.rotate(random_angle(rng));
+
+            assert!(approx_eq(rotated.len(), vec.len()));
+        });
+    }
+
+    #[test]
+    fn reflect_twice_is_the_original() {
+        check(|rng| {
+            let vec = random_vec2(rng);
+            let normal = random_unit_vec2(rng);
+
+            assert!(approx_eq_vec2(vec.reflect(normal).reflect(normal), vec));
+            assert!(approx_eq(vec.reflect(normal).len(), vec.len()));
+        });
+    }
+
+    #[test]
+    fn project_is_idempotent() {
+        check(|rng| {
+            let vec = random_vec2(rng);
+            let onto = random_vec2(rng);
+            let projected = vec.project(onto);
+
+            assert!(approx_eq_vec2(projected.project(onto), projected));
+            assert!(approx_eq(Vec2::cross(projected, onto) / onto.len(), 0.0));
+        });
+
+        assert_eq!(Vec2::one().project(Vec2::zero()), Vec2::zero());
+    }
+
+    #[test]
+    fn lerp_ends_at_the_endpoints() {
+        check(|rng| {
+            let a = random_vec2(rng);
+            let b = random_vec2(rng);
+            let t = random_f32(rng, 0.0, 1.0);
+
+            assert_eq!(Vec2::lerp(a, b, 0.0), a);
+            assert!(approx_eq_vec2(Vec2::lerp(a, b, 1.0), b));
+            assert!(approx_eq(
+                Vec2::distance(a, Vec2::lerp(a, b, t)),
+                Vec2::distance(a, b) * t
+            ));
+        });
+    }
+
+    #[test]
+    fn distance_is_symmetric_and_obeys_the_triangle_inequality() {
+        check(|rng| {
+            let a = random_vec2(rng);
+            let b = random_vec2(rng);
+            let c = random_vec2(rng);
+
+            assert_eq!(Vec2::distance(a, b), Vec2::distance(b, a));
+            assert!(approx_eq(Vec2::distance(a, b), (b - a).len()));
+            assert!(approx_eq(
+                Vec2::distance(a, b).powi(2),
+                Vec2::distance_sqr(a, b)
+            ));
+            assert!(Vec2::distance(a, c) <= Vec2::distance(a, b) + Vec2::distance(b, c) + 1e-2);
+        });
+    }
+
+    #[test]
+    fn angle_round_trips_through_from_angle() {
+        check(|rng| {
+            let vec = random_vec2(rng);
+            let angle = vec.angle();
+
+            assert!((-std::f32::consts::PI..=std::f32::consts::PI).contains(&angle));
+            assert!(approx_eq_vec2(Vec2::from_angle(angle) * vec.len(), vec));
+        });
+    }
+
+    #[test]
+    fn rotating_by_angle_between_aligns_the_vectors() {
+        check(|rng| {
+            let a = random_unit_vec2(rng);
+            let b = random_unit_vec2(rng);
+            let angle = Vec2::angle_between(a, b);
+
+            assert!(approx_eq_vec2(a.rotate(angle), b));
+            assert!(approx_eq(Vec2::angle_between(b, a), -angle));
+        });
+    }
+
+    #[test]
+    fn perp_is_orthogonal_with_the_same_length() {
+        check(|rng| {
+            let vec = random_vec2(rng);
+            let perp = vec.perp();
+
+            assert_eq!(Vec2::dot(vec, perp), 0.0);
+            assert_eq!(perp.len(), vec.len());
+            assert!(approx_eq_vec2(perp.perp(), -vec));
+        });
+    }
+
+    #[test]
+    fn clamp_len_keeps_the_direction() {
+        check(|rng| {
+            let vec = random_vec2(rng);
+            let max_len = random_f32(rng, 0.0, 1500.0);
+            let clamped = vec.clamp_len(max_len);
+
+            assert!(clamped.len() <= max_len * (1.0 + 1e-5));
+            if vec.len() <= max_len {
+                assert_eq!(clamped, vec);
+            } else {
+                assert!(approx_eq(clamped.len(), max_len));
+                assert!(approx_eq_vec2(clamped.normalized(), vec.normalized()));
+            }
+        });
+    }
+
+    #[test]
+    fn scalar_multiplication_commutes() {
+        check(|rng| {
+            let vec = random_vec2(rng);
+            let other = random_vec2(rng);
+            let scalar = random_f32(rng, -10.0, 10.0);
+
+            assert_eq!(scalar * vec, vec * scalar);
+            assert_eq!(vec * other, Vec2::from_xy(vec.x * other.x, vec.y * other.y));
+            assert_eq!(vec * other, other * vec);
+        });
+    }
+}
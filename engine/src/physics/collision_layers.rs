@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::physics::{LayerMask, TILE_LAYER};
+
+// Name of the layer solid tiles live on, must be the first layer in the config
+pub const TILE_LAYER_NAME: &str = "world";
+
+// The layers shipped with the engine, used when the config file can't be loaded
+const DEFAULT_CONFIG_JSON: &str = include_str!("../../config/collision_layers.json");
+
+// Named collision layers and which of them collide with each other.
+// Loaded from config/collision_layers.json.
+pub struct CollisionLayers {
+    m_layers: HashMap<String, LayerMask>,
+    m_collision_masks: HashMap<LayerMask, LayerMask>,
+}
+
+impl CollisionLayers {
+    pub fn from_file<P>(path: P) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read '{}': {}", path.display(), err))?;
+        let config: CollisionLayersConfig = serde_json::from_str(&json)
+            .map_err(|err| format!("Failed to parse '{}': {}", path.display(), err))?;
+
+        Self::from_config(config).map_err(|err| format!("'{}': {}", path.display(), err))
+    }
+
+    pub fn from_default_config() -> Self {
+        let config: CollisionLayersConfig = serde_json::from_str(DEFAULT_CONFIG_JSON).unwrap();
+        Self::from_config(config).unwrap()
+    }
+
+    // Layer with the given name, as a single bit mask
+    pub fn get_layer(&self, name: &str) -> Result<LayerMask, String> {
+        self.m_layers
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("Unknown collision layer '{}'", name))
+    }
+
+    // Union of the named layers
+    pub fn get_mask(&self, names: &[&str]) -> Result<LayerMask, String> {
+        names
+            .iter()
+            .try_fold(0, |mask, name| Ok(mask | self.get_layer(name)?))
+    }
+
+    // Layers that collide with the given layer(s)
+    pub fn get_collision_mask(&self, layer: LayerMask) -> LayerMask {
+        self.m_collision_masks
+            .iter()
+            .filter(|(other, _)| layer & **other != 0)
+            .fold(0, |mask, (_, collision_mask)| mask | collision_mask)
+    }
+
+    fn from_config(config: CollisionLayersConfig) -> Result<Self, String> {
+        if config.layers.len() > LayerMask::BITS as usize {
+            return Err(format!(
+                "At most {} collision layers are supported",
+                LayerMask::BITS
+            ));
+        }
+
+        if config.layers.first().map(String::as_str) != Some(TILE_LAYER_NAME) {
+            return Err(format!(
+                "The first collision layer must be '{}'",
+                TILE_LAYER_NAME
+            ));
+        }
+
+        let mut layers = HashMap::new();
+        for (index, name) in config.layers.into_iter().enumerate() {
+            if layers.insert(name.clone(), 1 << index).is_some() {
+                return Err(format!("Duplicate collision layer '{}'", name));
+            }
+        }
+        debug_assert_eq!(layers[TILE_LAYER_NAME], TILE_LAYER);
+
+        let mut collision_layers = Self {
+            m_collision_masks: layers.values().map(|layer| (*layer, 0)).collect(),
+            m_layers: layers,
+        };
+
+        // Collisions go both ways, listing a pair once is enough
+        for (name, others) in &config.collisions {
+            let layer = collision_layers.get_layer(name)?;
+            for other_name in others {
+                let other = collision_layers.get_layer(other_name)?;
+                *collision_layers.m_collision_masks.get_mut(&layer).unwrap() |= other;
+                *collision_layers.m_collision_masks.get_mut(&other).unwrap() |= layer;
+            }
+        }
+
+        Ok(collision_layers)
+    }
+}
+
+#[derive(Deserialize)]
+struct CollisionLayersConfig {
+    layers: Vec<String>,
+    #[serde(default)]
+    collisions: HashMap<String, Vec<String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_is_valid() {
+        let collision_layers = CollisionLayers::from_default_config();
+        let world = collision_layers.get_layer(TILE_LAYER_NAME).unwrap();
+        let player = collision_layers.get_layer("player").unwrap();
+
+        assert_eq!(world, TILE_LAYER);
+        assert_ne!(collision_layers.get_collision_mask(player) & world, 0);
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        assert!(CollisionLayers::from_file("missing/collision_layers.json").is_err());
+    }
+}
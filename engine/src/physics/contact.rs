@@ -0,0 +1,29 @@
+use crate::entity::EntityId;
+use crate::math::Vec2;
+
+// Passed to Component::on_collision()
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Collision {
+    pub other: EntityId,
+    // Points away from the other collider
+    pub normal: Vec2,
+    // How far the colliders overlap along the normal, zero when they only touch
+    pub depth: f32,
+}
+
+// Produced by PhysicsWorld::update_contacts() for the entity with entity_id
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContactEvent {
+    TriggerEnter {
+        entity_id: EntityId,
+        other: EntityId,
+    },
+    TriggerExit {
+        entity_id: EntityId,
+        other: EntityId,
+    },
+    Collision {
+        entity_id: EntityId,
+        collision: Collision,
+    },
+}
@@ -0,0 +1,11 @@
+pub mod collision_layers;
+pub mod contact;
+pub mod physics_world;
+pub mod rigid_body;
+pub mod spatial_hash;
+
+// Re-exports
+pub use collision_layers::*;
+pub use contact::*;
+pub use physics_world::*;
+pub use spatial_hash::*;
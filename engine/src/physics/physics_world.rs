@@ -0,0 +1,1605 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use crate::core::path_utils::get_collision_layers_config_path;
+use crate::core::tilemap::{TilePos, Tilemap};
+use crate::core::tileset::{TileShape, Tileset};
+use crate::entity::EntityId;
+use crate::math::{Rect, Segment, Vec2};
+use crate::physics::rigid_body::{RigidBody, get_contact_impulse};
+use crate::physics::{Collision, CollisionLayers, ContactEvent, SpatialHash};
+
+pub type LayerMask = u32;
+pub const DEFAULT_LAYER: LayerMask = 1;
+pub const ALL_LAYERS: LayerMask = LayerMask::MAX;
+
+// Solid tiles always live on the default layer, the "world" layer of the config
+pub const TILE_LAYER: LayerMask = DEFAULT_LAYER;
+
+// Colliders closer than this are considered touching
+const CONTACT_SKIN: f32 = 0.01;
+
+// How far a rect may already have sunk into a one-way platform and still land on it
+const ONE_WAY_TOLERANCE: f32 = 0.1;
+// Points closer than this to a slope surface are on it, not below it
+const SLOPE_TOLERANCE: f32 = 0.01;
+
+// Broad phase cells span a few tiles, so most colliders cover one to four cells
+const BROAD_PHASE_TILES_PER_CELL: f32 = 2.0;
+
+// Pixels per second squared, pointing down
+pub const DEFAULT_GRAVITY: Vec2 = Vec2 { x: 0.0, y: 600.0 };
+// Bounces and slides of a body against the tiles per step
+const MAX_BODY_SLIDES: usize = 4;
+// Bodies slower than this for TIME_TO_SLEEP stop being simulated until something wakes them
+const SLEEP_VELOCITY: f32 = 5.0;
+const TIME_TO_SLEEP: f32 = 0.5;
+const DEFAULT_BROAD_PHASE_CELL_SIZE: f32 = 32.0;
+
+pub type ColliderHandle = i32;
+pub const INVALID_COLLIDER_HANDLE: ColliderHandle = -1;
+
+// Which colliders a query considers. A plain LayerMask converts into a filter.
+// Triggers are skipped unless asked for, one-way tiles are included unless asked not to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueryFilter {
+    pub layer_mask: LayerMask,
+    pub exclude_entity: Option<EntityId>,
+    pub include_triggers: bool,
+    pub include_one_way: bool,
+}
+
+impl QueryFilter {
+    pub fn new(layer_mask: LayerMask) -> Self {
+        Self {
+            layer_mask,
+            exclude_entity: None,
+            include_triggers: false,
+            include_one_way: true,
+        }
+    }
+
+    // E.g. while dropping through a platform
+    pub fn without_one_way(mut self) -> Self {
+        self.include_one_way = false;
+        self
+    }
+
+    pub fn with_triggers(mut self) -> Self {
+        self.include_triggers = true;
+        self
+    }
+
+    // Skips the colliders of this entity, e.g. the one doing the query
+    pub fn excluding(mut self, entity_id: EntityId) -> Self {
+        self.exclude_entity = Some(entity_id);
+        self
+    }
+
+    fn accepts_tiles(&self) -> bool {
+        self.layer_mask & TILE_LAYER != 0
+    }
+
+    fn accepts(&self, proxy: &ColliderProxy) -> bool {
+        self.layer_mask & proxy.layer != 0
+            && self.exclude_entity != Some(proxy.entity_id)
+            && (self.include_triggers || !proxy.is_trigger)
+    }
+}
+
+impl From<LayerMask> for QueryFilter {
+    fn from(layer_mask: LayerMask) -> Self {
+        QueryFilter::new(layer_mask)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HitTarget {
+    Tile(TilePos),
+    Entity(EntityId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicsHit {
+    // Raycasts: where the ray hits the surface.
+    // Shape casts: position (top-left) of the shape when it touches the surface.
+    // Overlaps: center of the overlapping area.
+    pub point: Vec2,
+    // Surface normal. Zero when the query starts inside the collider.
+    // For overlaps, the direction that pushes the query rect out.
+    pub normal: Vec2,
+    // Distance travelled along the ray or motion, or the penetration depth for overlaps
+    pub distance: f32,
+    pub target: HitTarget,
+}
+
+pub(crate) struct ColliderProxy {
+    pub entity_id: EntityId,
+    pub bounds: Rect,
+    pub layer: LayerMask,
+    // Layers this collider collides with
+    pub mask: LayerMask,
+    pub is_trigger: bool,
+}
+
+impl ColliderProxy {
+    fn interacts_with(&self, other: &ColliderProxy) -> bool {
+        self.entity_id != other.entity_id
+            && self.mask & other.layer != 0
+            && other.mask & self.layer != 0
+    }
+}
+
+type ContactKey = (ColliderHandle, ColliderHandle);
+
+struct Contact {
+    entity_a: EntityId,
+    entity_b: EntityId,
+    is_trigger: bool,
+}
+
+// Collision data of the level (solid tiles) and of the entity colliders, used for spatial queries.
+// Shared as Rc<PhysicsWorld>, so components can run queries from their ticks.
+pub struct PhysicsWorld {
+    m_collision_layers: CollisionLayers,
+    m_tile_size: Cell<f32>,
+    // Only tiles with a collision shape
+    m_tiles: RefCell<HashMap<TilePos, TileShape>>,
+    // Smallest and largest tile coordinates of m_tiles, rays outside them can stop
+    m_tile_bounds: Cell<Option<(TilePos, TilePos)>>,
+    m_colliders: RefCell<HashMap<ColliderHandle, ColliderProxy>>,
+    // Collider bounds grown by the contact skin, so touching colliders share a cell
+    m_broad_phase: RefCell<SpatialHash<ColliderHandle>>,
+    m_next_collider_handle: Cell<ColliderHandle>,
+    // Touching collider pairs as of the last update_contacts()
+    m_contacts: RefCell<HashMap<ContactKey, Contact>>,
+    m_gravity: Cell<Vec2>,
+    // Dynamic bodies, by the handle of their collider
+    m_bodies: RefCell<HashMap<ColliderHandle, RigidBody>>,
+}
+
+impl PhysicsWorld {
+    // Loads the collision layers from config/collision_layers.json
+    pub fn new() -> Result<Self, String> {
+        let collision_layers = CollisionLayers::from_file(get_collision_layers_config_path())?;
+        Ok(Self::with_collision_layers(collision_layers))
+    }
+
+    pub fn with_collision_layers(collision_layers: CollisionLayers) -> Self {
+        Self {
+            m_collision_layers: collision_layers,
+            m_tile_size: Cell::new(1.0),
+            m_tiles: RefCell::new(HashMap::new()),
+            m_tile_bounds: Cell::new(None),
+            m_colliders: RefCell::new(HashMap::new()),
+            m_broad_phase: RefCell::new(SpatialHash::new(DEFAULT_BROAD_PHASE_CELL_SIZE)),
+            m_next_collider_handle: Cell::new(0),
+            m_contacts: RefCell::new(HashMap::new()),
+            m_gravity: Cell::new(DEFAULT_GRAVITY),
+            m_bodies: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn get_collision_layers(&self) -> &CollisionLayers {
+        &self.m_collision_layers
+    }
+
+    // Shorthand for get_collision_layers().get_layer()
+    pub fn get_layer(&self, name: &str) -> Result<LayerMask, String> {
+        self.m_collision_layers.get_layer(name)
+    }
+
+    pub fn get_gravity(&self) -> Vec2 {
+        self.m_gravity.get()
+    }
+
+    pub fn set_gravity(&self, gravity: Vec2) {
+        self.m_gravity.set(gravity);
+        self.wake_up_bodies();
+    }
+
+    // Replaces the tile collision with the shapes the tileset defines for the map's tiles.
+    // The broad phase cells are resized to match the tile size.
+    pub fn set_tilemap(&self, tilemap: &Tilemap, tileset: &Tileset) {
+        let tile_size = tilemap.get_tile_size() as f32;
+        self.m_tile_size.set(tile_size);
+        self.m_broad_phase
+            .borrow_mut()
+            .set_cell_size(tile_size * BROAD_PHASE_TILES_PER_CELL);
+
+        let mut tiles = self.m_tiles.borrow_mut();
+        let mut tile_bounds: Option<(TilePos, TilePos)> = None;
+        tiles.clear();
+        for (tile_pos, tile) in tilemap.tile_iter() {
+            let shape = tileset.get_shape(&tile.tile_type, tile.variant);
+            if shape != TileShape::None {
+                tiles.insert(tile_pos, shape);
+                let (min, max) = tile_bounds.unwrap_or((tile_pos, tile_pos));
+                tile_bounds = Some((
+                    (min.0.min(tile_pos.0), min.1.min(tile_pos.1)),
+                    (max.0.max(tile_pos.0), max.1.max(tile_pos.1)),
+                ));
+            }
+        }
+        self.m_tile_bounds.set(tile_bounds);
+
+        self.wake_up_bodies();
+    }
+
+    pub fn clear_tilemap(&self) {
+        self.m_tiles.borrow_mut().clear();
+        self.m_tile_bounds.set(None);
+        self.wake_up_bodies();
+    }
+
+    pub fn get_tile_shape(&self, tile_pos: TilePos) -> TileShape {
+        self.m_tiles
+            .borrow()
+            .get(&tile_pos)
+            .copied()
+            .unwrap_or(TileShape::None)
+    }
+
+    pub(crate) fn add_collider(&self, proxy: ColliderProxy) -> ColliderHandle {
+        let handle = self.m_next_collider_handle.get();
+        self.m_next_collider_handle.set(handle + 1);
+
+        self.m_broad_phase
+            .borrow_mut()
+            .insert(handle, get_broad_phase_rect(&proxy.bounds));
+        self.m_colliders.borrow_mut().insert(handle, proxy);
+
+        handle
+    }
+
+    // Bodies moved by something other than the simulation wake up
+    pub(crate) fn update_collider(&self, handle: ColliderHandle, proxy: ColliderProxy) {
+        if let Some(current) = self.m_colliders.borrow_mut().get_mut(&handle) {
+            if current.bounds != proxy.bounds {
+                self.m_broad_phase
+                    .borrow_mut()
+                    .update(handle, get_broad_phase_rect(&proxy.bounds));
+            }
+
+            let is_moved = current.bounds.size != proxy.bounds.size
+                || !Vec2::approx_eq(current.bounds.pos, proxy.bounds.pos, CONTACT_SKIN);
+            if is_moved && let Some(body) = self.m_bodies.borrow_mut().get_mut(&handle) {
+                body.wake_up();
+            }
+
+            *current = proxy;
+        }
+    }
+
+    pub(crate) fn remove_collider(&self, handle: ColliderHandle) {
+        self.m_colliders.borrow_mut().remove(&handle);
+        self.m_broad_phase.borrow_mut().remove(handle);
+        self.m_bodies.borrow_mut().remove(&handle);
+    }
+
+    // Makes the collider with the handle a dynamic body
+    pub(crate) fn add_body(&self, handle: ColliderHandle, body: RigidBody) {
+        if self.m_colliders.borrow().contains_key(&handle) {
+            self.m_bodies.borrow_mut().insert(handle, body);
+        }
+    }
+
+    pub(crate) fn get_body(&self, handle: ColliderHandle) -> Option<RigidBody> {
+        self.m_bodies.borrow().get(&handle).copied()
+    }
+
+    pub(crate) fn set_body(&self, handle: ColliderHandle, body: RigidBody) {
+        if let Some(current) = self.m_bodies.borrow_mut().get_mut(&handle) {
+            *current = body;
+        }
+    }
+
+    pub(crate) fn remove_body(&self, handle: ColliderHandle) {
+        self.m_bodies.borrow_mut().remove(&handle);
+    }
+
+    pub fn wake_up_bodies(&self) {
+        for body in self.m_bodies.borrow_mut().values_mut() {
+            body.wake_up();
+        }
+    }
+
+    // Moves the awake bodies by their velocity, bouncing them off the tiles and each other.
+    // Bodies of entities that `is_simulated` rejects, e.g. ones of a paused scene, hold still
+    // and act as immovable colliders.
+    // Returns how far the entity of each moved body went, for updating its transform.
+    pub(crate) fn step_bodies<F>(&self, delta_time: f32, is_simulated: F) -> Vec<(EntityId, Vec2)>
+    where
+        F: Fn(EntityId) -> bool,
+    {
+        let mut handles: Vec<ColliderHandle> = {
+            let colliders = self.m_colliders.borrow();
+            self.m_bodies
+                .borrow()
+                .keys()
+                .copied()
+                .filter(|handle| {
+                    colliders
+                        .get(handle)
+                        .is_some_and(|proxy| is_simulated(proxy.entity_id))
+                })
+                .collect()
+        };
+        if handles.is_empty() {
+            return Vec::new();
+        }
+        handles.sort_unstable();
+
+        let mut start_positions = HashMap::new();
+        let gravity = self.m_gravity.get();
+
+        for &handle in &handles {
+            let Some(mut body) = self.get_body(handle).filter(|body| !body.is_sleeping) else {
+                continue;
+            };
+            let Some(bounds) = self.get_collider_bounds(handle) else {
+                continue;
+            };
+
+            start_positions.insert(handle, bounds.pos);
+            body.velocity += gravity * body.gravity_scale * delta_time;
+            let bounds = self.move_body(handle, bounds, &mut body, delta_time);
+            self.set_collider_bounds(handle, bounds);
+            self.set_body(handle, body);
+        }
+
+        self.resolve_body_contacts(&handles, &mut start_positions);
+
+        for &handle in &handles {
+            if let Some(body) = self.m_bodies.borrow_mut().get_mut(&handle)
+                && !body.is_sleeping
+            {
+                if body.velocity.len() < SLEEP_VELOCITY {
+                    body.sleep_time += delta_time;
+                } else {
+                    body.sleep_time = 0.0;
+                }
+
+                if body.sleep_time >= TIME_TO_SLEEP {
+                    body.is_sleeping = true;
+                    body.velocity = Vec2::zero();
+                }
+            }
+        }
+
+        let colliders = self.m_colliders.borrow();
+        let mut moved: Vec<(EntityId, Vec2)> = start_positions
+            .into_iter()
+            .filter_map(|(handle, start)| {
+                let proxy = colliders.get(&handle)?;
+                let motion = proxy.bounds.pos - start;
+                (motion != Vec2::zero()).then_some((proxy.entity_id, motion))
+            })
+            .collect();
+        moved.sort_by_key(|(entity_id, _)| *entity_id);
+
+        moved
+    }
+
+    // Compares the touching collider pairs with the ones from the previous update.
+    // New trigger pairs enter, vanished trigger pairs exit, new solid pairs collide.
+    // Both entities of a pair get an event.
+    pub fn update_contacts(&self) -> Vec<ContactEvent> {
+        let colliders = self.m_colliders.borrow();
+        let mut previous_contacts = self.m_contacts.borrow_mut();
+        let mut contacts = HashMap::new();
+        let mut events = Vec::new();
+
+        for (handle_a, handle_b) in self.m_broad_phase.borrow().query_pairs() {
+            let a = &colliders[&handle_a];
+            let b = &colliders[&handle_b];
+            if !a.interacts_with(b)
+                || !a
+                    .bounds
+                    .expanded(Vec2::one() * CONTACT_SKIN)
+                    .intersects(&b.bounds)
+            {
+                continue;
+            }
+
+            let key = (handle_a, handle_b);
+            let is_trigger = a.is_trigger || b.is_trigger;
+            contacts.insert(
+                key,
+                Contact {
+                    entity_a: a.entity_id,
+                    entity_b: b.entity_id,
+                    is_trigger,
+                },
+            );
+
+            if previous_contacts.contains_key(&key) {
+                continue;
+            }
+
+            if is_trigger {
+                events.push(ContactEvent::TriggerEnter {
+                    entity_id: a.entity_id,
+                    other: b.entity_id,
+                });
+                events.push(ContactEvent::TriggerEnter {
+                    entity_id: b.entity_id,
+                    other: a.entity_id,
+                });
+            } else {
+                let depth = a.bounds.overlap_depth(&b.bounds).unwrap_or(Vec2::zero());
+                let normal = if depth == Vec2::zero() {
+                    get_touch_normal(&a.bounds, &b.bounds)
+                } else {
+                    depth.normalized()
+                };
+
+                events.push(ContactEvent::Collision {
+                    entity_id: a.entity_id,
+                    collision: Collision {
+                        other: b.entity_id,
+                        normal,
+                        depth: depth.len(),
+                    },
+                });
+                events.push(ContactEvent::Collision {
+                    entity_id: b.entity_id,
+                    collision: Collision {
+                        other: a.entity_id,
+                        normal: -normal,
+                        depth: depth.len(),
+                    },
+                });
+            }
+        }
+
+        // Pairs that stopped touching, or lost a collider
+        for (key, contact) in previous_contacts.iter() {
+            if !contact.is_trigger || contacts.contains_key(key) {
+                continue;
+            }
+
+            events.push(ContactEvent::TriggerExit {
+                entity_id: contact.entity_a,
+                other: contact.entity_b,
+            });
+            events.push(ContactEvent::TriggerExit {
+                entity_id: contact.entity_b,
+                other: contact.entity_a,
+            });
+        }
+
+        *previous_contacts = contacts;
+
+        events
+    }
+
+    // Closest hit along the ray, if any within max_distance.
+    // None for an infinite or NaN max_distance.
+    pub fn raycast<F>(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+        filter: F,
+    ) -> Option<PhysicsHit>
+    where
+        F: Into<QueryFilter>,
+    {
+        let filter = filter.into();
+        let direction = direction.normalized();
+        if direction == Vec2::zero() || !max_distance.is_finite() {
+            return None;
+        }
+
+        let mut closest = if filter.accepts_tiles() {
+            self.raycast_tiles(origin, direction, max_distance, &filter)
+        } else {
+            None
+        };
+
+        let ray = Segment::new(origin, origin + direction * max_distance);
+        let colliders = self.m_colliders.borrow();
+        for handle in self.m_broad_phase.borrow().query(&ray.bounds()) {
+            let proxy = &colliders[&handle];
+            if !filter.accepts(proxy) {
+                continue;
+            }
+
+            if let Some(hit) = ray.intersect_rect(&proxy.bounds) {
+                closest = closer(
+                    closest,
+                    PhysicsHit {
+                        point: hit.point,
+                        normal: hit.normal,
+                        distance: hit.t * max_distance,
+                        target: HitTarget::Entity(proxy.entity_id),
+                    },
+                );
+            }
+        }
+
+        closest
+    }
+
+    // First hit of the rect moving by motion. Surfaces the rect only touches don't block
+    // motion along or away from them.
+    // Slopes are tested with the bottom center of the rect, so it can sink into the slope's
+    // tile up to the surface. A rect that starts below a slope surface hits it at distance 0
+    // with the surface normal, so it can be pushed out.
+    pub fn shape_cast<F>(&self, aabb: Rect, motion: Vec2, filter: F) -> Option<PhysicsHit>
+    where
+        F: Into<QueryFilter>,
+    {
+        let filter = filter.into();
+        let motion_len = motion.len();
+        let mut closest = None;
+
+        let swept_bounds = aabb.union(&aabb.translated(motion));
+        if filter.accepts_tiles() {
+            for (tile_pos, shape) in self.tiles_in(&swept_bounds) {
+                let tile_rect = self.get_tile_rect(tile_pos);
+                let hit = match shape {
+                    TileShape::None => None,
+                    TileShape::Solid => sweep_aabb(&aabb, motion, &tile_rect),
+                    TileShape::OneWay if filter.include_one_way => {
+                        sweep_aabb_one_way(&aabb, motion, &tile_rect)
+                    }
+                    TileShape::OneWay => None,
+                    TileShape::Slope { .. } => sweep_aabb_slope(&aabb, motion, &tile_rect, shape),
+                };
+
+                if let Some((t, normal)) = hit {
+                    closest = closer(
+                        closest,
+                        PhysicsHit {
+                            point: aabb.pos + motion * t,
+                            normal,
+                            distance: t * motion_len,
+                            target: HitTarget::Tile(tile_pos),
+                        },
+                    );
+                }
+            }
+        }
+
+        let colliders = self.m_colliders.borrow();
+        for handle in self.m_broad_phase.borrow().query(&swept_bounds) {
+            let proxy = &colliders[&handle];
+            if let Some((t, normal)) = filter
+                .accepts(proxy)
+                .then(|| sweep_aabb(&aabb, motion, &proxy.bounds))
+                .flatten()
+            {
+                closest = closer(
+                    closest,
+                    PhysicsHit {
+                        point: aabb.pos + motion * t,
+                        normal,
+                        distance: t * motion_len,
+                        target: HitTarget::Entity(proxy.entity_id),
+                    },
+                );
+            }
+        }
+
+        closest
+    }
+
+    // Everything overlapping the rect, in no particular order.
+    // One-way tiles never overlap, slopes only when the bottom center of the rect is below them.
+    pub fn overlap_aabb<F>(&self, rect: Rect, filter: F) -> Vec<PhysicsHit>
+    where
+        F: Into<QueryFilter>,
+    {
+        let filter = filter.into();
+        let mut hits = Vec::new();
+
+        let test = |target_rect: &Rect, target: HitTarget| {
+            Some(PhysicsHit {
+                point: rect.intersection(target_rect)?.center(),
+                normal: rect.overlap_depth(target_rect)?.normalized(),
+                distance: rect.overlap_depth(target_rect)?.len(),
+                target,
+            })
+        };
+
+        if filter.accepts_tiles() {
+            for (tile_pos, shape) in self.tiles_in(&rect) {
+                let tile_rect = self.get_tile_rect(tile_pos);
+                match shape {
+                    TileShape::Solid => hits.extend(test(&tile_rect, HitTarget::Tile(tile_pos))),
+                    TileShape::Slope { .. } => {
+                        let bottom_center = get_bottom_center(&rect);
+                        if let Some(depth) = get_slope_depth(&tile_rect, shape, bottom_center) {
+                            hits.push(PhysicsHit {
+                                point: bottom_center,
+                                normal: get_slope_normal(&tile_rect, shape),
+                                distance: depth,
+                                target: HitTarget::Tile(tile_pos),
+                            });
+                        }
+                    }
+                    TileShape::None | TileShape::OneWay => {}
+                }
+            }
+        }
+
+        let colliders = self.m_colliders.borrow();
+        for handle in self.m_broad_phase.borrow().query(&rect) {
+            let proxy = &colliders[&handle];
+            if filter.accepts(proxy) {
+                hits.extend(test(&proxy.bounds, HitTarget::Entity(proxy.entity_id)));
+            }
+        }
+
+        hits
+    }
+
+    pub fn get_tile_rect(&self, tile_pos: TilePos) -> Rect {
+        let tile_size = self.m_tile_size.get();
+        Rect::new(
+            tile_pos.0 as f32 * tile_size,
+            tile_pos.1 as f32 * tile_size,
+            tile_size,
+            tile_size,
+        )
+    }
+
+    fn world_to_tile(&self, position: Vec2) -> TilePos {
+        let tile_size = self.m_tile_size.get();
+        (
+            (position.x / tile_size).floor() as i32,
+            (position.y / tile_size).floor() as i32,
+        )
+    }
+
+    fn get_collider_bounds(&self, handle: ColliderHandle) -> Option<Rect> {
+        self.m_colliders
+            .borrow()
+            .get(&handle)
+            .map(|proxy| proxy.bounds)
+    }
+
+    fn set_collider_bounds(&self, handle: ColliderHandle, bounds: Rect) {
+        if let Some(proxy) = self.m_colliders.borrow_mut().get_mut(&handle) {
+            proxy.bounds = bounds;
+            self.m_broad_phase
+                .borrow_mut()
+                .update(handle, get_broad_phase_rect(&bounds));
+        }
+    }
+
+    // What a body can't move through: the tiles and the other colliders of the world layer
+    fn get_body_filter(&self, handle: ColliderHandle) -> QueryFilter {
+        let colliders = self.m_colliders.borrow();
+        let proxy = &colliders[&handle];
+        QueryFilter::new(proxy.mask & TILE_LAYER).excluding(proxy.entity_id)
+    }
+
+    fn move_body(
+        &self,
+        handle: ColliderHandle,
+        mut bounds: Rect,
+        body: &mut RigidBody,
+        delta_time: f32,
+    ) -> Rect {
+        let filter = self.get_body_filter(handle);
+        // How a body bounces off static surfaces doesn't depend on its mass
+        let unit_body = RigidBody {
+            inverse_mass: 1.0,
+            ..*body
+        };
+        let surface = RigidBody {
+            velocity: Vec2::zero(),
+            inverse_mass: 0.0,
+            ..*body
+        };
+
+        let mut remaining = body.velocity * delta_time;
+        for _ in 0..MAX_BODY_SLIDES {
+            if remaining.len_sqr() < CONTACT_SKIN * CONTACT_SKIN {
+                break;
+            }
+
+            let Some(hit) = self.shape_cast(bounds, remaining, filter) else {
+                bounds = bounds.translated(remaining);
+                break;
+            };
+
+            // Started inside a wall, let the contact pass push it out of whatever it hit
+            if hit.normal == Vec2::zero() {
+                break;
+            }
+
+            let remaining_len = remaining.len();
+            let direction = remaining / remaining_len;
+            bounds = bounds.translated(direction * (hit.distance - CONTACT_SKIN).max(0.0));
+
+            let hit_body = RigidBody {
+                velocity: body.velocity,
+                ..unit_body
+            };
+            if let Some(impulse) = get_contact_impulse(&hit_body, &surface, hit.normal) {
+                body.velocity += impulse;
+            }
+
+            remaining = direction * (remaining_len - hit.distance);
+            remaining -= hit.normal * Vec2::dot(remaining, hit.normal);
+        }
+
+        bounds
+    }
+
+    // Moves a body by offset as far as the tiles allow, returns how far it got
+    fn push_body(&self, handle: ColliderHandle, offset: Vec2) -> Vec2 {
+        let Some(bounds) = self.get_collider_bounds(handle) else {
+            return Vec2::zero();
+        };
+
+        let offset_len = offset.len();
+        if offset_len == 0.0 {
+            return Vec2::zero();
+        }
+
+        let pushed = match self.shape_cast(bounds, offset, self.get_body_filter(handle)) {
+            Some(hit) => offset / offset_len * (hit.distance - CONTACT_SKIN).max(0.0),
+            None => offset,
+        };
+        self.set_collider_bounds(handle, bounds.translated(pushed));
+
+        pushed
+    }
+
+    // Separates overlapping bodies and exchanges impulses between them.
+    // Colliders without a simulated body take part as immovable objects.
+    // Sleeping bodies only wake up when hit hard enough, otherwise they hold still too.
+    fn resolve_body_contacts(
+        &self,
+        simulated_handles: &[ColliderHandle],
+        start_positions: &mut HashMap<ColliderHandle, Vec2>,
+    ) {
+        let get_simulated_body = |handle: ColliderHandle| {
+            simulated_handles
+                .binary_search(&handle)
+                .ok()
+                .and_then(|_| self.get_body(handle))
+        };
+
+        let pairs = self.m_broad_phase.borrow().query_pairs();
+        for (handle_a, handle_b) in pairs {
+            let (bounds_a, bounds_b) = {
+                let colliders = self.m_colliders.borrow();
+                let (Some(a), Some(b)) = (colliders.get(&handle_a), colliders.get(&handle_b))
+                else {
+                    continue;
+                };
+
+                if !a.interacts_with(b) || a.is_trigger || b.is_trigger {
+                    continue;
+                }
+
+                (a.bounds, b.bounds)
+            };
+
+            let body_a = get_simulated_body(handle_a);
+            let body_b = get_simulated_body(handle_b);
+            let is_awake = |body: &Option<RigidBody>| body.is_some_and(|body| !body.is_sleeping);
+            if !is_awake(&body_a) && !is_awake(&body_b) {
+                continue;
+            }
+
+            // Pushes a out of b
+            let Some(separation) = bounds_a.overlap_depth(&bounds_b) else {
+                continue;
+            };
+            let normal = separation.normalized();
+
+            let as_contact_body = |body: Option<RigidBody>, other: Option<RigidBody>| {
+                let mut contact_body = body.unwrap_or(RigidBody {
+                    velocity: Vec2::zero(),
+                    inverse_mass: 0.0,
+                    gravity_scale: 0.0,
+                    restitution: 1.0,
+                    friction: 1.0,
+                    is_sleeping: false,
+                    sleep_time: 0.0,
+                });
+
+                let other_speed =
+                    other.map_or(0.0, |other| Vec2::dot(other.velocity, normal).abs());
+                if contact_body.is_sleeping {
+                    if other_speed < SLEEP_VELOCITY {
+                        contact_body.inverse_mass = 0.0;
+                    } else {
+                        contact_body.wake_up();
+                    }
+                }
+
+                contact_body
+            };
+
+            let mut a = as_contact_body(body_a, body_b);
+            let mut b = as_contact_body(body_b, body_a);
+            let total_inverse_mass = a.inverse_mass + b.inverse_mass;
+            if total_inverse_mass == 0.0 {
+                continue;
+            }
+
+            for handle in [handle_a, handle_b] {
+                if let Some(bounds) = self.get_collider_bounds(handle) {
+                    start_positions.entry(handle).or_insert(bounds.pos);
+                }
+            }
+
+            // What one body can't take because of the tiles goes to the other one
+            let share_a = separation * (a.inverse_mass / total_inverse_mass);
+            let pushed_a = if a.inverse_mass > 0.0 {
+                self.push_body(handle_a, share_a)
+            } else {
+                Vec2::zero()
+            };
+            if b.inverse_mass > 0.0 {
+                let pushed_b = self.push_body(handle_b, pushed_a - separation);
+                let rest = separation - pushed_a + pushed_b;
+                if a.inverse_mass > 0.0 && rest != Vec2::zero() {
+                    self.push_body(handle_a, rest);
+                }
+            }
+
+            if let Some(impulse) = get_contact_impulse(&a, &b, normal) {
+                a.velocity += impulse * a.inverse_mass;
+                b.velocity -= impulse * b.inverse_mass;
+            }
+
+            if let Some(body) = body_a {
+                self.set_body(
+                    handle_a,
+                    RigidBody {
+                        inverse_mass: body.inverse_mass,
+                        ..a
+                    },
+                );
+            }
+            if let Some(body) = body_b {
+                self.set_body(
+                    handle_b,
+                    RigidBody {
+                        inverse_mass: body.inverse_mass,
+                        ..b
+                    },
+                );
+            }
+        }
+    }
+
+    fn tiles_in(&self, rect: &Rect) -> Vec<(TilePos, TileShape)> {
+        let tiles = self.m_tiles.borrow();
+        if tiles.is_empty() {
+            return Vec::new();
+        }
+
+        let min = self.world_to_tile(rect.min());
+        let max = self.world_to_tile(rect.max());
+
+        let mut tiles_in_rect = Vec::new();
+        for y in min.1..=max.1 {
+            for x in min.0..=max.0 {
+                if let Some(shape) = tiles.get(&(x, y)) {
+                    tiles_in_rect.push(((x, y), *shape));
+                }
+            }
+        }
+
+        tiles_in_rect
+    }
+
+    // Grid traversal (DDA), visits only the tiles the ray passes through
+    fn raycast_tiles(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+        filter: &QueryFilter,
+    ) -> Option<PhysicsHit> {
+        let tiles = self.m_tiles.borrow();
+        let (min_tile_pos, max_tile_pos) = self.m_tile_bounds.get()?;
+
+        let tile_size = self.m_tile_size.get();
+        let mut tile_pos = self.world_to_tile(origin);
+
+        let step_x = direction.x.signum() as i32;
+        let step_y = direction.y.signum() as i32;
+
+        let first_boundary = |tile: i32, step: i32, origin: f32, direction: f32| {
+            if direction == 0.0 {
+                return f32::INFINITY;
+            }
+
+            let boundary = if step > 0 { tile + 1 } else { tile };
+            (boundary as f32 * tile_size - origin) / direction
+        };
+
+        let mut t_max_x = first_boundary(tile_pos.0, step_x, origin.x, direction.x);
+        let mut t_max_y = first_boundary(tile_pos.1, step_y, origin.y, direction.y);
+        let t_delta_x = tile_size / direction.x.abs();
+        let t_delta_y = tile_size / direction.y.abs();
+
+        // Where the ray entered the current tile, and through which side.
+        // The ray starts inside the first tile, so there is no side.
+        let mut t_enter = 0.0;
+        let mut entry_normal = Vec2::zero();
+
+        // Past the last tile on an axis and not heading back
+        let is_leaving = |tile: i32, step: i32, min: i32, max: i32| {
+            (tile < min && step <= 0) || (tile > max && step >= 0)
+        };
+
+        loop {
+            if is_leaving(tile_pos.0, step_x, min_tile_pos.0, max_tile_pos.0)
+                || is_leaving(tile_pos.1, step_y, min_tile_pos.1, max_tile_pos.1)
+            {
+                return None;
+            }
+
+            let t_exit = t_max_x.min(t_max_y).min(max_distance);
+
+            if let Some(shape) = tiles.get(&tile_pos) {
+                let ray = TileRay {
+                    origin,
+                    direction,
+                    t_enter,
+                    t_exit,
+                    entry_normal,
+                };
+
+                if let Some((t, normal)) = ray.hit(
+                    &self.get_tile_rect(tile_pos),
+                    *shape,
+                    filter.include_one_way,
+                ) {
+                    return Some(PhysicsHit {
+                        point: origin + direction * t,
+                        normal,
+                        distance: t,
+                        target: HitTarget::Tile(tile_pos),
+                    });
+                }
+            }
+
+            if t_exit >= max_distance {
+                return None;
+            }
+
+            if t_max_x < t_max_y {
+                tile_pos.0 += step_x;
+                t_enter = t_max_x;
+                t_max_x += t_delta_x;
+                entry_normal = Vec2::from_xy(-step_x as f32, 0.0);
+            } else {
+                tile_pos.1 += step_y;
+                t_enter = t_max_y;
+                t_max_y += t_delta_y;
+                entry_normal = Vec2::from_xy(0.0, -step_y as f32);
+            }
+        }
+    }
+}
+
+// Part of a ray inside a single tile
+struct TileRay {
+    origin: Vec2,
+    direction: Vec2,
+    t_enter: f32,
+    t_exit: f32,
+    entry_normal: Vec2,
+}
+
+impl TileRay {
+    fn hit(
+        &self,
+        tile_rect: &Rect,
+        shape: TileShape,
+        include_one_way: bool,
+    ) -> Option<(f32, Vec2)> {
+        match shape {
+            TileShape::None => None,
+            TileShape::Solid => Some((self.t_enter, self.entry_normal)),
+            // Only blocks rays coming through the top
+            TileShape::OneWay => (include_one_way && self.entry_normal == Vec2::up())
+                .then_some((self.t_enter, self.entry_normal)),
+            TileShape::Slope { .. } => {
+                let entry = self.origin + self.direction * self.t_enter;
+                if get_slope_depth(tile_rect, shape, entry).is_some() {
+                    // Entered through one of the solid sides
+                    return Some((self.t_enter, self.entry_normal));
+                }
+
+                let normal = get_slope_normal(tile_rect, shape);
+                if Vec2::dot(self.direction, normal) >= 0.0 {
+                    return None;
+                }
+
+                let exit = self.origin + self.direction * self.t_exit;
+                let point = Segment::new(entry, exit)
+                    .intersect_segment(&get_slope_surface(tile_rect, shape))?;
+
+                Some((self.t_enter + Vec2::distance(entry, point), normal))
+            }
+        }
+    }
+}
+
+fn get_bottom_center(rect: &Rect) -> Vec2 {
+    Vec2::from_xy(rect.center().x, rect.bottom())
+}
+
+// Surface line of a slope tile, from its left to its right edge
+fn get_slope_surface(tile_rect: &Rect, shape: TileShape) -> Segment {
+    let height = |local_x: f32| {
+        tile_rect.bottom() - shape.get_surface_height(local_x).unwrap_or(0.0) * tile_rect.size.y
+    };
+
+    Segment::new(
+        Vec2::from_xy(tile_rect.left(), height(0.0)),
+        Vec2::from_xy(tile_rect.right(), height(1.0)),
+    )
+}
+
+// Points up, away from the solid part
+fn get_slope_normal(tile_rect: &Rect, shape: TileShape) -> Vec2 {
+    let delta = get_slope_surface(tile_rect, shape).delta();
+    Vec2::from_xy(delta.y, -delta.x).normalized()
+}
+
+// How far the point is below the slope surface, None when it isn't inside the solid part
+fn get_slope_depth(tile_rect: &Rect, shape: TileShape, point: Vec2) -> Option<f32> {
+    if point.x < tile_rect.left() || point.x > tile_rect.right() || point.y > tile_rect.bottom() {
+        return None;
+    }
+
+    let local_x = (point.x - tile_rect.left()) / tile_rect.size.x;
+    let surface_y = tile_rect.bottom() - shape.get_surface_height(local_x)? * tile_rect.size.y;
+    let depth = point.y - surface_y;
+
+    (depth > SLOPE_TOLERANCE).then_some(depth)
+}
+
+fn get_broad_phase_rect(bounds: &Rect) -> Rect {
+    bounds.expanded(Vec2::one() * CONTACT_SKIN)
+}
+
+// Normal pointing from b to a, for rects that touch without overlapping
+fn get_touch_normal(a: &Rect, b: &Rect) -> Vec2 {
+    let gaps = [
+        (b.left() - a.right(), Vec2::left()),
+        (a.left() - b.right(), Vec2::right()),
+        (b.top() - a.bottom(), Vec2::up()),
+        (a.top() - b.bottom(), Vec2::down()),
+    ];
+
+    gaps.into_iter()
+        .max_by(|(gap_a, _), (gap_b, _)| gap_a.total_cmp(gap_b))
+        .map_or(Vec2::zero(), |(_, normal)| normal)
+}
+
+fn closer(current: Option<PhysicsHit>, hit: PhysicsHit) -> Option<PhysicsHit> {
+    match current {
+        Some(current) if current.distance <= hit.distance => Some(current),
+        _ => Some(hit),
+    }
+}
+
+// Lands on the top of the tile when moving down from above it
+fn sweep_aabb_one_way(moving: &Rect, motion: Vec2, tile_rect: &Rect) -> Option<(f32, Vec2)> {
+    if motion.y <= 0.0 || moving.bottom() > tile_rect.top() + ONE_WAY_TOLERANCE {
+        return None;
+    }
+
+    let t = ((tile_rect.top() - moving.bottom()) / motion.y).max(0.0);
+    if t > 1.0 {
+        return None;
+    }
+
+    let left = moving.left() + motion.x * t;
+    if left >= tile_rect.right() || left + moving.size.x <= tile_rect.left() {
+        return None;
+    }
+
+    Some((t, Vec2::up()))
+}
+
+// Moves the bottom center of the rect against the slope surface
+fn sweep_aabb_slope(
+    moving: &Rect,
+    motion: Vec2,
+    tile_rect: &Rect,
+    shape: TileShape,
+) -> Option<(f32, Vec2)> {
+    let normal = get_slope_normal(tile_rect, shape);
+    let bottom_center = get_bottom_center(moving);
+
+    if get_slope_depth(tile_rect, shape, bottom_center).is_some() {
+        return Some((0.0, normal));
+    }
+
+    if Vec2::dot(motion, normal) >= 0.0 {
+        return None;
+    }
+
+    let path = Segment::new(bottom_center, bottom_center + motion);
+    let point = path.intersect_segment(&get_slope_surface(tile_rect, shape))?;
+
+    Some((Vec2::distance(bottom_center, point) / motion.len(), normal))
+}
+
+// Time of impact in [0, 1] and the normal of the hit surface.
+// Rects that already overlap hit at 0 with a zero normal, touching rects don't count as overlapping.
+fn sweep_aabb(moving: &Rect, motion: Vec2, target: &Rect) -> Option<(f32, Vec2)> {
+    if moving.intersects(target) {
+        return Some((0.0, Vec2::zero()));
+    }
+
+    let axis = |motion: f32, moving_min: f32, moving_max: f32, target_min: f32, target_max: f32| {
+        if motion > 0.0 {
+            Some((
+                (target_min - moving_max) / motion,
+                (target_max - moving_min) / motion,
+                -1.0,
+            ))
+        } else if motion < 0.0 {
+            Some((
+                (target_max - moving_min) / motion,
+                (target_min - moving_max) / motion,
+                1.0,
+            ))
+        } else if moving_max <= target_min || moving_min >= target_max {
+            None
+        } else {
+            Some((f32::NEG_INFINITY, f32::INFINITY, 0.0))
+        }
+    };
+
+    let (entry_x, exit_x, normal_x) = axis(
+        motion.x,
+        moving.left(),
+        moving.right(),
+        target.left(),
+        target.right(),
+    )?;
+    let (entry_y, exit_y, normal_y) = axis(
+        motion.y,
+        moving.top(),
+        moving.bottom(),
+        target.top(),
+        target.bottom(),
+    )?;
+
+    let entry = entry_x.max(entry_y);
+    let exit = exit_x.min(exit_y);
+    if entry >= exit || !(0.0..=1.0).contains(&entry) {
+        return None;
+    }
+
+    let normal = if entry_x > entry_y {
+        Vec2::from_xy(normal_x, 0.0)
+    } else {
+        Vec2::from_xy(0.0, normal_y)
+    };
+
+    Some((entry, normal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::tilemap::Tile;
+    use crate::core::tileset::TileTypeDefinition;
+
+    const TILE_SIZE: i32 = 16;
+    const DELTA_TIME: f32 = 1.0 / 60.0;
+
+    fn create_tileset() -> Tileset {
+        let mut tileset = Tileset::default();
+        for (tile_type, shape) in [
+            ("solid", TileShape::Solid),
+            ("platform", TileShape::OneWay),
+            (
+                "slope",
+                TileShape::Slope {
+                    left: 0.0,
+                    right: 1.0,
+                },
+            ),
+        ] {
+            tileset.tile_types.insert(
+                tile_type.to_string(),
+                TileTypeDefinition {
+                    shape,
+                    ..Default::default()
+                },
+            );
+        }
+
+        tileset
+    }
+
+    // A floor of solid tiles at row 4 from column 0 to 9, plus the given tiles
+    fn create_world(tiles: &[(TilePos, &str)]) -> PhysicsWorld {
+        let mut tilemap = Tilemap::new(TILE_SIZE);
+        let floor = (0..10).map(|x| ((x, 4), "solid"));
+        for (tile_pos, tile_type) in floor.chain(tiles.iter().copied()) {
+            tilemap.set_tile(
+                tile_pos,
+                Tile {
+                    tile_type: tile_type.to_string(),
+                    variant: 0,
+                    pos: Vec2::zero(),
+                },
+            );
+        }
+
+        let physics_world =
+            PhysicsWorld::with_collision_layers(CollisionLayers::from_default_config());
+        physics_world.set_tilemap(&tilemap, &create_tileset());
+        physics_world
+    }
+
+    fn add_box(physics_world: &PhysicsWorld, entity_id: EntityId, bounds: Rect) -> ColliderHandle {
+        physics_world.add_collider(ColliderProxy {
+            entity_id,
+            bounds,
+            layer: DEFAULT_LAYER,
+            mask: ALL_LAYERS,
+            is_trigger: false,
+        })
+    }
+
+    // A collider on the layer of dynamic props, which pass through each other's shape casts
+    // and only meet in the contact pass
+    fn add_prop(physics_world: &PhysicsWorld, entity_id: EntityId, bounds: Rect) -> ColliderHandle {
+        let collision_layers = physics_world.get_collision_layers();
+        let layer = collision_layers.get_layer("prop").unwrap();
+        physics_world.add_collider(ColliderProxy {
+            entity_id,
+            bounds,
+            layer,
+            mask: collision_layers.get_collision_mask(layer),
+            is_trigger: false,
+        })
+    }
+
+    fn create_body(
+        velocity: Vec2,
+        inverse_mass: f32,
+        restitution: f32,
+        friction: f32,
+    ) -> RigidBody {
+        RigidBody {
+            velocity,
+            inverse_mass,
+            gravity_scale: 1.0,
+            restitution,
+            friction,
+            is_sleeping: false,
+            sleep_time: 0.0,
+        }
+    }
+
+    fn step_for(physics_world: &PhysicsWorld, seconds: f32) {
+        for _ in 0..(seconds / DELTA_TIME).round() as usize {
+            physics_world.step_bodies(DELTA_TIME, |_| true);
+        }
+    }
+
+    #[test]
+    fn ray_hits_solid_tiles_through_the_entry_side() {
+        let physics_world = create_world(&[((6, 3), "solid")]);
+
+        let hit = physics_world
+            .raycast(Vec2::from_xy(40.0, 8.0), Vec2::down(), 100.0, ALL_LAYERS)
+            .unwrap();
+        assert_eq!(hit.target, HitTarget::Tile((2, 4)));
+        assert_eq!(hit.normal, Vec2::up());
+        assert!((hit.distance - 56.0).abs() < 1e-3);
+        assert!(Vec2::approx_eq(hit.point, Vec2::from_xy(40.0, 64.0), 1e-3));
+
+        let hit = physics_world
+            .raycast(Vec2::from_xy(8.0, 56.0), Vec2::right(), 200.0, ALL_LAYERS)
+            .unwrap();
+        assert_eq!(hit.target, HitTarget::Tile((6, 3)));
+        assert_eq!(hit.normal, Vec2::left());
+
+        // Too short to reach the floor
+        assert!(
+            physics_world
+                .raycast(Vec2::from_xy(40.0, 8.0), Vec2::down(), 50.0, ALL_LAYERS)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn ray_stops_outside_the_tiles() {
+        let physics_world = create_world(&[]);
+        let origin = Vec2::from_xy(40.0, 8.0);
+
+        assert!(
+            physics_world
+                .raycast(origin, Vec2::up(), f32::MAX, ALL_LAYERS)
+                .is_none()
+        );
+        assert!(
+            physics_world
+                .raycast(origin, Vec2::from_xy(-1.0, -0.01), 1e9, ALL_LAYERS)
+                .is_none()
+        );
+        for max_distance in [f32::INFINITY, f32::NAN] {
+            assert!(
+                physics_world
+                    .raycast(origin, Vec2::down(), max_distance, ALL_LAYERS)
+                    .is_none()
+            );
+        }
+
+        // Starting outside and heading into the tiles still hits them
+        let hit = physics_world
+            .raycast(
+                Vec2::from_xy(-1e4, 72.0),
+                Vec2::right(),
+                f32::MAX,
+                ALL_LAYERS,
+            )
+            .unwrap();
+        assert_eq!(hit.target, HitTarget::Tile((0, 4)));
+        assert_eq!(hit.normal, Vec2::left());
+    }
+
+    #[test]
+    fn one_way_platforms_only_block_from_above() {
+        let physics_world = create_world(&[((2, 2), "platform")]);
+        let above = Vec2::from_xy(40.0, 8.0);
+        let below = Vec2::from_xy(40.0, 60.0);
+
+        let hit = physics_world
+            .raycast(above, Vec2::down(), 100.0, ALL_LAYERS)
+            .unwrap();
+        assert_eq!(hit.target, HitTarget::Tile((2, 2)));
+        assert_eq!(hit.normal, Vec2::up());
+
+        let filter = QueryFilter::new(ALL_LAYERS).without_one_way();
+        let hit = physics_world
+            .raycast(above, Vec2::down(), 100.0, filter)
+            .unwrap();
+        assert_eq!(hit.target, HitTarget::Tile((2, 4)));
+        assert!(
+            physics_world
+                .raycast(below, Vec2::up(), 100.0, ALL_LAYERS)
+                .is_none()
+        );
+
+        // A falling box lands on top, a jumping one passes through
+        let falling = Rect::new(36.0, 10.0, 8.0, 8.0);
+        let hit = physics_world
+            .shape_cast(falling, Vec2::from_xy(0.0, 30.0), ALL_LAYERS)
+            .unwrap();
+        assert_eq!(hit.target, HitTarget::Tile((2, 2)));
+        assert_eq!(hit.normal, Vec2::up());
+        assert!((hit.point.y - 24.0).abs() < 1e-3);
+
+        let jumping = Rect::new(36.0, 50.0, 8.0, 8.0);
+        assert!(
+            physics_world
+                .shape_cast(jumping, Vec2::from_xy(0.0, -40.0), ALL_LAYERS)
+                .is_none()
+        );
+        assert!(
+            physics_world
+                .overlap_aabb(Rect::new(36.0, 34.0, 8.0, 8.0), ALL_LAYERS)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn boxes_land_on_slopes_by_their_bottom_center() {
+        let physics_world = create_world(&[((2, 3), "slope")]);
+
+        // The surface rises from (32, 64) to (48, 48), so it is at y = 56 under x = 40
+        let falling = Rect::new(36.0, 20.0, 8.0, 8.0);
+        let hit = physics_world
+            .shape_cast(falling, Vec2::from_xy(0.0, 40.0), ALL_LAYERS)
+            .unwrap();
+        assert_eq!(hit.target, HitTarget::Tile((2, 3)));
+        assert!((hit.point.y - 48.0).abs() < 1e-3);
+        assert!(Vec2::approx_eq(
+            hit.normal,
+            Vec2::from_xy(-1.0, -1.0).normalized(),
+            1e-3
+        ));
+
+        // Sunk into the slope
+        let hits = physics_world.overlap_aabb(Rect::new(36.0, 52.0, 8.0, 8.0), ALL_LAYERS);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].target, HitTarget::Tile((2, 3)));
+        assert!((hits[0].distance - 4.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn overlaps_report_tiles_and_colliders() {
+        let physics_world = create_world(&[]);
+        add_box(&physics_world, 7, Rect::new(96.0, 52.0, 8.0, 8.0));
+
+        // Touching the tile at column 4 doesn't count as overlapping it
+        let rect = Rect::new(80.0, 58.0, 20.0, 8.0);
+        let hits = physics_world.overlap_aabb(rect, ALL_LAYERS);
+        let mut targets: Vec<HitTarget> = hits.iter().map(|hit| hit.target).collect();
+        targets.sort_by_key(|target| format!("{:?}", target));
+        assert_eq!(
+            targets,
+            vec![
+                HitTarget::Entity(7),
+                HitTarget::Tile((5, 4)),
+                HitTarget::Tile((6, 4))
+            ]
+        );
+
+        let filter = QueryFilter::new(ALL_LAYERS).excluding(7);
+        assert_eq!(physics_world.overlap_aabb(rect, filter).len(), 2);
+    }
+
+    #[test]
+    fn bodies_come_to_rest_on_the_floor_and_sleep() {
+        let physics_world = create_world(&[]);
+        let handle = add_box(&physics_world, 1, Rect::new(36.0, 10.0, 8.0, 8.0));
+        physics_world.add_body(
+            handle,
+            RigidBody {
+                velocity: Vec2::from_xy(20.0, 0.0),
+                inverse_mass: 1.0,
+                gravity_scale: 1.0,
+                restitution: 0.3,
+                friction: 0.8,
+                is_sleeping: false,
+                sleep_time: 0.0,
+            },
+        );
+
+        for _ in 0..(4.0 / DELTA_TIME) as usize {
+            physics_world.step_bodies(DELTA_TIME, |_| true);
+        }
+
+        let body = physics_world.get_body(handle).unwrap();
+        assert!(body.is_sleeping);
+        assert_eq!(body.velocity, Vec2::zero());
+
+        let bounds = physics_world.get_collider_bounds(handle).unwrap();
+        assert!(
+            (bounds.bottom() - 64.0).abs() <= CONTACT_SKIN * 2.0,
+            "{:?}",
+            bounds
+        );
+
+        // Sleeping bodies don't move until woken up
+        assert!(physics_world.step_bodies(DELTA_TIME, |_| true).is_empty());
+        physics_world.set_gravity(Vec2::from_xy(0.0, -600.0));
+        assert!(!physics_world.step_bodies(DELTA_TIME, |_| true).is_empty());
+    }
+
+    #[test]
+    fn restitution_decides_how_bodies_bounce() {
+        for (restitution, expected_velocity_y) in [(1.0, -200.0), (0.0, 0.0)] {
+            let physics_world = create_world(&[]);
+            physics_world.set_gravity(Vec2::zero());
+            let handle = add_box(&physics_world, 1, Rect::new(36.0, 10.0, 8.0, 8.0));
+            physics_world.add_body(
+                handle,
+                create_body(Vec2::from_xy(0.0, 200.0), 1.0, restitution, 0.0),
+            );
+
+            // Reaches the floor after about a quarter of a second
+            step_for(&physics_world, 0.5);
+
+            let body = physics_world.get_body(handle).unwrap();
+            assert!(
+                (body.velocity.y - expected_velocity_y).abs() < 1e-3,
+                "restitution {}: {:?}",
+                restitution,
+                body
+            );
+            assert_eq!(body.velocity.x, 0.0);
+
+            let bounds = physics_world.get_collider_bounds(handle).unwrap();
+            if restitution == 0.0 {
+                assert!((bounds.bottom() - 64.0).abs() <= CONTACT_SKIN * 2.0);
+            } else {
+                assert!(bounds.bottom() < 64.0 - 10.0, "{:?}", bounds);
+            }
+        }
+    }
+
+    #[test]
+    fn friction_slows_sliding_bodies() {
+        let mut velocities_x = Vec::new();
+        for friction in [0.0, 0.8] {
+            let physics_world = create_world(&[]);
+            let handle = add_box(
+                &physics_world,
+                1,
+                Rect::new(8.0, 64.0 - 8.0 - CONTACT_SKIN, 8.0, 8.0),
+            );
+            physics_world.add_body(
+                handle,
+                create_body(Vec2::from_xy(100.0, 0.0), 1.0, 0.0, friction),
+            );
+
+            step_for(&physics_world, 0.25);
+            velocities_x.push(physics_world.get_body(handle).unwrap().velocity.x);
+        }
+
+        assert!((velocities_x[0] - 100.0).abs() < 1e-3, "{:?}", velocities_x);
+        assert!(velocities_x[1] < 50.0, "{:?}", velocities_x);
+        // Friction stops the sliding but never reverses it
+        assert!(velocities_x[1] >= 0.0, "{:?}", velocities_x);
+    }
+
+    #[test]
+    fn colliding_bodies_exchange_momentum_by_their_masses() {
+        let physics_world = create_world(&[]);
+        physics_world.set_gravity(Vec2::zero());
+        let handle_a = add_prop(&physics_world, 1, Rect::new(0.0, 10.0, 8.0, 8.0));
+        let handle_b = add_prop(&physics_world, 2, Rect::new(12.0, 10.0, 8.0, 8.0));
+        // b is twice as heavy as a
+        physics_world.add_body(
+            handle_a,
+            create_body(Vec2::from_xy(100.0, 0.0), 1.0, 1.0, 0.0),
+        );
+        physics_world.add_body(handle_b, create_body(Vec2::zero(), 0.5, 1.0, 0.0));
+
+        step_for(&physics_world, 0.1);
+
+        // An elastic collision of masses 1 and 2 leaves -1/3 and 2/3 of the speed
+        let velocity_a = physics_world.get_body(handle_a).unwrap().velocity;
+        let velocity_b = physics_world.get_body(handle_b).unwrap().velocity;
+        assert!(
+            (velocity_a.x + 100.0 / 3.0).abs() < 1e-2,
+            "{:?}",
+            velocity_a
+        );
+        assert!(
+            (velocity_b.x - 200.0 / 3.0).abs() < 1e-2,
+            "{:?}",
+            velocity_b
+        );
+        assert!((velocity_a.x + 2.0 * velocity_b.x - 100.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn bodies_of_paused_entities_hold_still() {
+        let physics_world = create_world(&[]);
+        physics_world.set_gravity(Vec2::zero());
+        let handle_a = add_prop(&physics_world, 1, Rect::new(0.0, 10.0, 8.0, 8.0));
+        let handle_b = add_prop(&physics_world, 2, Rect::new(12.0, 10.0, 8.0, 8.0));
+        physics_world.add_body(
+            handle_a,
+            create_body(Vec2::from_xy(100.0, 0.0), 1.0, 1.0, 0.0),
+        );
+        physics_world.add_body(
+            handle_b,
+            create_body(Vec2::from_xy(0.0, 50.0), 1.0, 1.0, 0.0),
+        );
+
+        for _ in 0..(0.1 / DELTA_TIME).round() as usize {
+            let moved = physics_world.step_bodies(DELTA_TIME, |entity_id| entity_id != 2);
+            assert!(moved.iter().all(|(entity_id, _)| *entity_id != 2));
+        }
+
+        // b neither falls nor gets pushed, a bounces off it like off a wall
+        let bounds_b = physics_world.get_collider_bounds(handle_b).unwrap();
+        assert_eq!(bounds_b, Rect::new(12.0, 10.0, 8.0, 8.0));
+        assert_eq!(
+            physics_world.get_body(handle_b).unwrap().velocity,
+            Vec2::from_xy(0.0, 50.0)
+        );
+        let velocity_a = physics_world.get_body(handle_a).unwrap().velocity;
+        assert!((velocity_a.x + 100.0).abs() < 1e-2, "{:?}", velocity_a);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::math::Vec2;
+
+// Impacts slower than this don't bounce, so resting bodies don't jitter
+const RESTITUTION_VELOCITY_THRESHOLD: f32 = 30.0;
+
+// Simulation state of a dynamic body, stored in the PhysicsWorld next to its collider.
+// RigidBodyComponent owns the settings and mirrors the velocity for saving.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RigidBody {
+    pub velocity: Vec2,
+    // Zero for bodies that can't be moved by impulses
+    pub inverse_mass: f32,
+    pub gravity_scale: f32,
+    pub restitution: f32,
+    pub friction: f32,
+    pub is_sleeping: bool,
+    // How long the body has been slow enough to sleep
+    pub sleep_time: f32,
+}
+
+impl RigidBody {
+    pub fn wake_up(&mut self) {
+        self.is_sleeping = false;
+        self.sleep_time = 0.0;
+    }
+}
+
+// Bounce and friction impulses of two bodies touching along normal, which points from b to a.
+// Bodies that aren't simulated (e.g. kinematic colliders) have zero inverse mass and velocity.
+// Returns the impulse to add to a, b gets the opposite one.
+pub(crate) fn get_contact_impulse(a: &RigidBody, b: &RigidBody, normal: Vec2) -> Option<Vec2> {
+    let total_inverse_mass = a.inverse_mass + b.inverse_mass;
+    if total_inverse_mass == 0.0 {
+        return None;
+    }
+
+    let relative_velocity = a.velocity - b.velocity;
+    let normal_velocity = Vec2::dot(relative_velocity, normal);
+    if normal_velocity >= 0.0 {
+        return None;
+    }
+
+    let restitution = if -normal_velocity < RESTITUTION_VELOCITY_THRESHOLD {
+        0.0
+    } else {
+        a.restitution.min(b.restitution)
+    };
+    let normal_impulse = -(1.0 + restitution) * normal_velocity / total_inverse_mass;
+
+    // Coulomb friction, can stop the sliding but never reverse it
+    let tangent_velocity = relative_velocity - normal * normal_velocity;
+    let tangent_speed = tangent_velocity.len();
+    let friction_impulse = if tangent_speed > 0.0 {
+        let friction = (a.friction * b.friction).sqrt();
+        let max_impulse = tangent_speed / total_inverse_mass;
+        tangent_velocity / tangent_speed * -(friction * normal_impulse).min(max_impulse)
+    } else {
+        Vec2::zero()
+    };
+
+    Some(normal * normal_impulse + friction_impulse)
+}
@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::math::{Rect, Vec2};
+
+type CellPos = (i32, i32);
+
+// Inclusive range of cells covered by a rect
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CellRange {
+    min: CellPos,
+    max: CellPos,
+}
+
+impl CellRange {
+    fn cells(&self) -> impl Iterator<Item = CellPos> {
+        let (min, max) = (self.min, self.max);
+        (min.1..=max.1).flat_map(move |y| (min.0..=max.0).map(move |x| (x, y)))
+    }
+
+    fn get_cell_count(&self) -> u64 {
+        let width = (self.max.0 as i64 - self.min.0 as i64 + 1).max(0) as u64;
+        let height = (self.max.1 as i64 - self.min.1 as i64 + 1).max(0) as u64;
+        width.saturating_mul(height)
+    }
+
+    fn contains(&self, cell: CellPos) -> bool {
+        (self.min.0..=self.max.0).contains(&cell.0) && (self.min.1..=self.max.1).contains(&cell.1)
+    }
+}
+
+// Uniform grid broad phase. Items are stored in every cell their rect covers,
+// so a query only looks at the items near it.
+pub struct SpatialHash<T>
+where
+    T: Copy + Eq + Hash + Ord,
+{
+    m_cell_size: f32,
+    m_cells: HashMap<CellPos, Vec<T>>,
+    m_items: HashMap<T, (Rect, CellRange)>,
+}
+
+impl<T> SpatialHash<T>
+where
+    T: Copy + Eq + Hash + Ord,
+{
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0, "Spatial hash cell size must be positive");
+
+        Self {
+            m_cell_size: cell_size,
+            m_cells: HashMap::new(),
+            m_items: HashMap::new(),
+        }
+    }
+
+    pub fn get_cell_size(&self) -> f32 {
+        self.m_cell_size
+    }
+
+    // Re-inserts all items into cells of the new size
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        let items: Vec<(T, Rect)> = self
+            .m_items
+            .iter()
+            .map(|(item, (rect, _))| (*item, *rect))
+            .collect();
+
+        *self = SpatialHash::new(cell_size);
+        for (item, rect) in items {
+            self.insert(item, rect);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.m_items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.m_items.is_empty()
+    }
+
+    pub fn contains(&self, item: T) -> bool {
+        self.m_items.contains_key(&item)
+    }
+
+    pub fn get_rect(&self, item: T) -> Option<Rect> {
+        self.m_items.get(&item).map(|(rect, _)| *rect)
+    }
+
+    // Replaces the item if it is already present
+    pub fn insert(&mut self, item: T, rect: Rect) {
+        self.remove(item);
+
+        let range = self.get_cell_range(&rect);
+        for cell in range.cells() {
+            self.m_cells.entry(cell).or_default().push(item);
+        }
+        self.m_items.insert(item, (rect, range));
+    }
+
+    // Only touches the cells when the item moved into different ones
+    pub fn update(&mut self, item: T, rect: Rect) {
+        let range = self.get_cell_range(&rect);
+        match self.m_items.get_mut(&item) {
+            Some((current_rect, current_range)) if *current_range == range => {
+                *current_rect = rect;
+            }
+            _ => self.insert(item, rect),
+        }
+    }
+
+    pub fn remove(&mut self, item: T) {
+        let Some((_, range)) = self.m_items.remove(&item) else {
+            return;
+        };
+
+        for cell in range.cells() {
+            if let Some(items) = self.m_cells.get_mut(&cell) {
+                items.retain(|other| *other != item);
+                if items.is_empty() {
+                    self.m_cells.remove(&cell);
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.m_cells.clear();
+        self.m_items.clear();
+    }
+
+    // Items whose cells overlap the rect, sorted and without duplicates.
+    // Candidates only, their rects still have to be tested.
+    pub fn query(&self, rect: &Rect) -> Vec<T> {
+        let range = self.get_cell_range(rect);
+        let mut items = Vec::new();
+        // Rects covering more cells than are occupied, e.g. very long rays, visit the occupied ones
+        if range.get_cell_count() > self.m_cells.len() as u64 {
+            for (cell, cell_items) in &self.m_cells {
+                if range.contains(*cell) {
+                    items.extend_from_slice(cell_items);
+                }
+            }
+        } else {
+            for cell in range.cells() {
+                if let Some(cell_items) = self.m_cells.get(&cell) {
+                    items.extend_from_slice(cell_items);
+                }
+            }
+        }
+
+        items.sort_unstable();
+        items.dedup();
+        items
+    }
+
+    // Every pair of items sharing a cell, once, as (smaller, larger)
+    pub fn query_pairs(&self) -> Vec<(T, T)> {
+        let mut pairs = Vec::new();
+        for items in self.m_cells.values() {
+            for (index, a) in items.iter().enumerate() {
+                for b in &items[index + 1..] {
+                    pairs.push(if a < b { (*a, *b) } else { (*b, *a) });
+                }
+            }
+        }
+
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    fn get_cell_range(&self, rect: &Rect) -> CellRange {
+        CellRange {
+            min: self.get_cell(rect.min()),
+            max: self.get_cell(rect.max()),
+        }
+    }
+
+    fn get_cell(&self, position: Vec2) -> CellPos {
+        (
+            (position.x / self.m_cell_size).floor() as i32,
+            (position.y / self.m_cell_size).floor() as i32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CELL_SIZE: f32 = 10.0;
+
+    fn get_cells(spatial_hash: &SpatialHash<i32>, item: i32) -> Vec<CellPos> {
+        let mut cells: Vec<CellPos> = spatial_hash
+            .m_cells
+            .iter()
+            .filter(|(_, items)| items.contains(&item))
+            .map(|(cell, _)| *cell)
+            .collect();
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn update_moves_items_between_cells() {
+        let mut spatial_hash = SpatialHash::new(CELL_SIZE);
+        spatial_hash.insert(1, Rect::new(1.0, 1.0, 4.0, 4.0));
+
+        // Within the same cell only the rect changes
+        spatial_hash.update(1, Rect::new(5.0, 5.0, 4.0, 4.0));
+        assert_eq!(
+            spatial_hash.get_rect(1),
+            Some(Rect::new(5.0, 5.0, 4.0, 4.0))
+        );
+        assert_eq!(get_cells(&spatial_hash, 1), vec![(0, 0)]);
+
+        // Across cells the old ones are left, and dropped once empty
+        spatial_hash.update(1, Rect::new(25.0, 5.0, 10.0, 4.0));
+        assert_eq!(get_cells(&spatial_hash, 1), vec![(2, 0), (3, 0)]);
+        assert_eq!(spatial_hash.m_cells.len(), 2);
+        assert!(
+            spatial_hash
+                .query(&Rect::new(0.0, 0.0, 9.0, 9.0))
+                .is_empty()
+        );
+        assert_eq!(spatial_hash.query(&Rect::new(30.0, 0.0, 1.0, 1.0)), vec![1]);
+    }
+
+    #[test]
+    fn remove_drops_empty_cells() {
+        let mut spatial_hash = SpatialHash::new(CELL_SIZE);
+        spatial_hash.insert(1, Rect::new(5.0, 5.0, 10.0, 1.0));
+        spatial_hash.insert(2, Rect::new(12.0, 5.0, 1.0, 1.0));
+
+        spatial_hash.remove(1);
+        assert!(!spatial_hash.contains(1));
+        assert_eq!(spatial_hash.m_cells.len(), 1);
+        assert_eq!(get_cells(&spatial_hash, 2), vec![(1, 0)]);
+
+        spatial_hash.remove(2);
+        assert!(spatial_hash.is_empty());
+        assert!(spatial_hash.m_cells.is_empty());
+
+        // Removing a missing item is a no-op
+        spatial_hash.remove(3);
+    }
+
+    #[test]
+    fn set_cell_size_reinserts_every_item() {
+        let mut spatial_hash = SpatialHash::new(CELL_SIZE);
+        let rects = [
+            Rect::new(0.0, 0.0, 5.0, 5.0),
+            Rect::new(15.0, 15.0, 30.0, 5.0),
+            Rect::new(-40.0, 8.0, 2.0, 2.0),
+        ];
+        for (item, rect) in rects.iter().enumerate() {
+            spatial_hash.insert(item as i32, *rect);
+        }
+
+        spatial_hash.set_cell_size(CELL_SIZE * 4.0);
+        assert_eq!(spatial_hash.get_cell_size(), CELL_SIZE * 4.0);
+        assert_eq!(spatial_hash.len(), rects.len());
+        for (item, rect) in rects.iter().enumerate() {
+            let item = item as i32;
+            assert_eq!(spatial_hash.get_rect(item), Some(*rect));
+            assert_eq!(
+                get_cells(&spatial_hash, item),
+                spatial_hash
+                    .get_cell_range(rect)
+                    .cells()
+                    .collect::<Vec<_>>()
+            );
+            assert!(spatial_hash.query(rect).contains(&item));
+        }
+        assert_eq!(get_cells(&spatial_hash, 1), vec![(0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn large_and_small_queries_agree() {
+        let mut spatial_hash = SpatialHash::new(CELL_SIZE);
+        let mut rng = fastrand::Rng::with_seed(0x5eed);
+        for item in 0..50 {
+            let rect = Rect::new(
+                rng.f32() * 400.0 - 200.0,
+                rng.f32() * 400.0 - 200.0,
+                rng.f32() * 30.0,
+                rng.f32() * 30.0,
+            );
+            spatial_hash.insert(item, rect);
+        }
+
+        for _ in 0..200 {
+            let rect = Rect::new(
+                rng.f32() * 600.0 - 300.0,
+                rng.f32() * 600.0 - 300.0,
+                rng.f32() * 500.0,
+                rng.f32() * 500.0,
+            );
+            let range = spatial_hash.get_cell_range(&rect);
+
+            // What query() gives either way, by visiting the range cell by cell
+            let mut expected: Vec<i32> = range
+                .cells()
+                .filter_map(|cell| spatial_hash.m_cells.get(&cell))
+                .flatten()
+                .copied()
+                .collect();
+            expected.sort_unstable();
+            expected.dedup();
+
+            assert_eq!(spatial_hash.query(&rect), expected, "{:?}", rect);
+        }
+
+        // Both branches are taken
+        let small = spatial_hash.get_cell_range(&Rect::new(0.0, 0.0, 1.0, 1.0));
+        let large = spatial_hash.get_cell_range(&Rect::new(-300.0, -300.0, 600.0, 600.0));
+        let occupied_count = spatial_hash.m_cells.len() as u64;
+        assert!(small.get_cell_count() <= occupied_count);
+        assert!(large.get_cell_count() > occupied_count);
+        assert_eq!(
+            spatial_hash.query(&Rect::new(-300.0, -300.0, 600.0, 600.0)),
+            (0..50).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn query_pairs_lists_each_pair_once() {
+        let mut spatial_hash = SpatialHash::new(CELL_SIZE);
+        // 1 and 2 share four cells, 3 only touches 2
+        spatial_hash.insert(1, Rect::new(0.0, 0.0, 15.0, 15.0));
+        spatial_hash.insert(2, Rect::new(5.0, 5.0, 25.0, 10.0));
+        spatial_hash.insert(3, Rect::new(25.0, 5.0, 2.0, 2.0));
+        spatial_hash.insert(4, Rect::new(100.0, 100.0, 2.0, 2.0));
+
+        assert_eq!(spatial_hash.query_pairs(), vec![(1, 2), (2, 3)]);
+    }
+}
@@ -0,0 +1,21 @@
+// Loads the game's shipped assets for tests, in this crate and the editor
+use std::path::PathBuf;
+
+use crate::core::tilemap::Tilemap;
+use crate::core::tileset::Tileset;
+
+pub fn get_assets_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../game/assets")
+}
+
+pub fn get_map_path(level_index: usize) -> PathBuf {
+    get_assets_path().join(format!("maps/{}.json", level_index))
+}
+
+pub fn load_tileset() -> Tileset {
+    Tileset::from_file(get_assets_path().join("tilesets/tileset.json")).unwrap()
+}
+
+pub fn load_map(level_index: usize) -> Tilemap {
+    Tilemap::from_file(get_map_path(level_index)).unwrap()
+}
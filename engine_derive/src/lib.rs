@@ -1,8 +1,16 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{Data, DeriveInput, Fields, Type, TypePath, TypePtr, parse_macro_input};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::visit_mut::VisitMut;
+use syn::{
+    Data, DeriveInput, Expr, ExprStruct, Field, Fields, FieldsNamed, Ident, Item, ItemImpl,
+    ItemStruct, Meta, Path, Token, Type, TypePath, TypePtr, parse_macro_input, parse_quote,
+};
 
-#[proc_macro_derive(ComponentBase)]
+const ENTITY_FIELD_NAME: &str = "m_entity";
+
+#[proc_macro_derive(ComponentBase, attributes(save, reflect))]
 pub fn derive_component(input: TokenStream) -> TokenStream {
     // Parse input tokens into a syntax tree
     let input = parse_macro_input!(input as DeriveInput);
@@ -31,13 +39,9 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
     // Find a field of type *mut Entity
     let mut entity_field_ident = None;
     for field in fields {
-        if let Type::Ptr(TypePtr { elem, .. }) = &field.ty {
-            if let Type::Path(TypePath { path, .. }) = &**elem {
-                if path.segments.last().unwrap().ident == "Entity" {
-                    entity_field_ident = field.ident.clone();
-                    break;
-                }
-            }
+        if is_entity_field(field) {
+            entity_field_ident = field.ident.clone();
+            break;
         }
     }
 
@@ -53,24 +57,488 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
         }
     };
 
+    let saved_fields = match collect_saved_fields(fields.iter()) {
+        Ok(saved_fields) => saved_fields,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let reflected_fields = collect_reflected_fields(fields.iter());
+
+    expand_component_base(
+        struct_name,
+        &entity_field,
+        &saved_fields,
+        &reflected_fields,
+        &ComponentOptions::default(),
+    )
+    .into()
+}
+
+// #[component(priority = RENDER, requires(TransformComponent), serialize)]
+//
+// On a struct:
+// - Injects the `m_entity: *mut Entity` field.
+// - Implements ComponentBase, including the declared priority and required components.
+// - Exposes the fields marked with #[reflect] by name, e.g. for the inspector overlay.
+// - With `serialize`, saves the fields marked with #[save] and registers the component
+//   in the global component registry, so it can be restored by type name and added
+//   automatically when another component requires it.
+//   The component must provide `fn new() -> Self`.
+// - With `serialize, manual_register`, saves the fields marked with #[save] but leaves the
+//   registration to the app, for components whose constructor needs runtime dependencies.
+//
+// On an impl block of the component:
+// - Initializes `m_entity` in every `Self { .. }` struct literal, so constructors don't have to.
+#[proc_macro_attribute]
+pub fn component(args: TokenStream, input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as Item);
+
+    let result = match item {
+        Item::Struct(item_struct) => ComponentOptions::parse(args.into())
+            .and_then(|options| expand_component_struct(item_struct, options)),
+        Item::Impl(item_impl) => {
+            if args.is_empty() {
+                Ok(expand_component_impl(item_impl))
+            } else {
+                Err(syn::Error::new_spanned(
+                    proc_macro2::TokenStream::from(args),
+                    "#[component] on an impl block takes no arguments",
+                ))
+            }
+        }
+        other => Err(syn::Error::new_spanned(
+            other,
+            "#[component] can only be used on structs and their impl blocks",
+        )),
+    };
+
+    match result {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[derive(Default)]
+struct ComponentOptions {
+    priority: Option<Expr>,
+    requires: Vec<Path>,
+    serialize: bool,
+    manual_register: bool,
+}
+
+impl ComponentOptions {
+    fn parse(args: proc_macro2::TokenStream) -> syn::Result<Self> {
+        let mut options = ComponentOptions::default();
+        let metas = Punctuated::<Meta, Token![,]>::parse_terminated.parse2(args)?;
+
+        for meta in metas {
+            match &meta {
+                Meta::NameValue(nv) if nv.path.is_ident("priority") => {
+                    options.priority = Some(nv.value.clone());
+                }
+                Meta::List(list) if list.path.is_ident("requires") => {
+                    let paths =
+                        list.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?;
+                    options.requires.extend(paths);
+                }
+                Meta::Path(path) if path.is_ident("serialize") => {
+                    options.serialize = true;
+                }
+                Meta::Path(path) if path.is_ident("manual_register") => {
+                    options.manual_register = true;
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        "Expected `priority = ..`, `requires(..)`, `serialize` or `manual_register`",
+                    ));
+                }
+            }
+        }
+
+        if options.manual_register && !options.serialize {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "`manual_register` requires `serialize`",
+            ));
+        }
+
+        Ok(options)
+    }
+
+    // `priority = RENDER` refers to the `component_priority` constants,
+    // any other expression is used as is
+    fn priority_tokens(&self) -> Option<proc_macro2::TokenStream> {
+        let priority = self.priority.as_ref()?;
+        if let Expr::Path(expr_path) = priority
+            && let Some(ident) = expr_path.path.get_ident()
+        {
+            return Some(quote! { ::engine::components::component_priority::#ident });
+        }
+
+        Some(quote! { #priority })
+    }
+}
+
+fn expand_component_struct(
+    mut item_struct: ItemStruct,
+    options: ComponentOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_name = item_struct.ident.clone();
+
+    let Fields::Named(FieldsNamed { named: fields, .. }) = &mut item_struct.fields else {
+        return Err(syn::Error::new_spanned(
+            &item_struct,
+            "#[component] can only be used on structs with named fields",
+        ));
+    };
+
+    let entity_field: Ident = Ident::new(ENTITY_FIELD_NAME, proc_macro2::Span::call_site());
+    if !fields.iter().any(is_entity_field) {
+        let field: Field = Field::parse_named.parse2(quote! {
+            #entity_field: *mut ::engine::entity::Entity
+        })?;
+        fields.insert(0, field);
+    }
+
+    let saved_fields = collect_saved_fields(fields.iter())?;
+    if !options.serialize
+        && let Some((field, _)) = saved_fields.first()
+    {
+        return Err(syn::Error::new_spanned(
+            field,
+            "#[save] fields require #[component(serialize)]",
+        ));
+    }
+
+    let reflected_fields = collect_reflected_fields(fields.iter());
+
+    // Strip the helper attributes, they are not valid outside of the derive
+    for field in fields.iter_mut() {
+        field
+            .attrs
+            .retain(|attr| !attr.path().is_ident("save") && !attr.path().is_ident("reflect"));
+    }
+
+    let component_base = expand_component_base(
+        &struct_name,
+        &entity_field,
+        &saved_fields,
+        &reflected_fields,
+        &options,
+    );
+
+    let registration = if options.serialize && !options.manual_register {
+        quote! {
+            ::engine::inventory::submit! {
+                ::engine::core::world_state::ComponentRegistration::new(
+                    stringify!(#struct_name),
+                    ::std::any::TypeId::of::<#struct_name>,
+                    || ::engine::components::Component::into_box(#struct_name::new()),
+                )
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        #item_struct
+
+        #component_base
+
+        #registration
+    })
+}
+
+fn expand_component_impl(mut item_impl: ItemImpl) -> proc_macro2::TokenStream {
+    let struct_name = match &*item_impl.self_ty {
+        Type::Path(type_path) => type_path.path.segments.last().map(|s| s.ident.clone()),
+        _ => None,
+    };
+
+    let mut visitor = EntityFieldInitializer { struct_name };
+    visitor.visit_item_impl_mut(&mut item_impl);
+
+    quote! { #item_impl }
+}
+
+struct EntityFieldInitializer {
+    struct_name: Option<Ident>,
+}
+
+impl VisitMut for EntityFieldInitializer {
+    fn visit_expr_struct_mut(&mut self, expr: &mut ExprStruct) {
+        syn::visit_mut::visit_expr_struct_mut(self, expr);
+
+        let Some(last) = expr.path.segments.last() else {
+            return;
+        };
+
+        let is_self = last.ident == "Self" || self.struct_name.as_ref() == Some(&last.ident);
+        let has_entity_field = expr.fields.iter().any(|field| match &field.member {
+            syn::Member::Named(ident) => ident == ENTITY_FIELD_NAME,
+            syn::Member::Unnamed(_) => false,
+        });
+
+        if is_self && !has_entity_field && expr.rest.is_none() {
+            let entity_field: Ident = Ident::new(ENTITY_FIELD_NAME, proc_macro2::Span::call_site());
+            expr.fields
+                .insert(0, parse_quote! { #entity_field: ::std::ptr::null_mut() });
+        }
+    }
+}
+
+fn is_entity_field(field: &Field) -> bool {
+    if let Type::Ptr(TypePtr { elem, .. }) = &field.ty
+        && let Type::Path(TypePath { path, .. }) = &**elem
+    {
+        return path.segments.last().unwrap().ident == "Entity";
+    }
+
+    false
+}
+
+fn collect_saved_fields<'a>(
+    fields: impl Iterator<Item = &'a Field>,
+) -> syn::Result<Vec<(Ident, SaveKind)>> {
+    let mut saved_fields = Vec::new();
+    for field in fields {
+        if let Some(kind) = parse_save_attribute(field)? {
+            saved_fields.push((field.ident.clone().unwrap(), kind));
+        }
+    }
+
+    Ok(saved_fields)
+}
+
+fn collect_reflected_fields<'a>(fields: impl Iterator<Item = &'a Field>) -> Vec<Ident> {
+    fields
+        .filter(|field| {
+            field
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("reflect"))
+        })
+        .map(|field| field.ident.clone().unwrap())
+        .collect()
+}
+
+fn expand_component_base(
+    struct_name: &Ident,
+    entity_field: &Ident,
+    saved_fields: &[(Ident, SaveKind)],
+    reflected_fields: &[Ident],
+    options: &ComponentOptions,
+) -> proc_macro2::TokenStream {
+    let reflection = generate_reflection(struct_name, reflected_fields);
+    let save_state = generate_save_state(saved_fields);
+    let load_state = generate_load_state(saved_fields);
+
+    let declared_priority = options.priority_tokens().map(|priority| {
+        quote! {
+            fn get_declared_priority(&self) -> i32 {
+                #priority
+            }
+        }
+    });
+
+    let requirements = if options.requires.is_empty() {
+        quote! {}
+    } else {
+        let requires = &options.requires;
+        quote! {
+            fn get_requirements(&self) -> Vec<::engine::components::ComponentRequirement> {
+                vec![#(::engine::components::ComponentRequirement::of::<#requires>(stringify!(#requires))),*]
+            }
+        }
+    };
+
     // Generate the Component impl
-    let expanded = quote! {
-        impl ComponentBase for #struct_name {
-            unsafe fn set_entity(&mut self, entity: *mut Entity) {
+    quote! {
+        impl ::engine::components::ComponentBase for #struct_name {
+            unsafe fn set_entity(&mut self, entity: *mut ::engine::entity::Entity) {
                 self.#entity_field = entity;
             }
 
-            fn get_entity(&self) -> &Entity {
+            fn has_entity(&self) -> bool {
+                !self.#entity_field.is_null()
+            }
+
+            fn get_entity(&self) -> &::engine::entity::Entity {
                 assert!(!self.#entity_field.is_null());
                 unsafe { &*self.#entity_field }
             }
 
-            fn get_entity_mut(&self) -> &mut Entity {
+            fn get_entity_mut(&self) -> &mut ::engine::entity::Entity {
                 assert!(!self.#entity_field.is_null());
                 unsafe { &mut *self.#entity_field }
             }
+
+            fn get_type_name(&self) -> &'static str {
+                stringify!(#struct_name)
+            }
+
+            #declared_priority
+
+            #requirements
+
+            #reflection
+
+            #save_state
+
+            #load_state
         }
-    };
+    }
+}
+
+// Reflected fields are exposed with the same keys as saved fields
+fn generate_reflection(
+    struct_name: &Ident,
+    reflected_fields: &[Ident],
+) -> proc_macro2::TokenStream {
+    if reflected_fields.is_empty() {
+        return quote! {};
+    }
+
+    let keys: Vec<String> = reflected_fields.iter().map(state_key).collect();
+
+    quote! {
+        fn get_fields(&self) -> Vec<(&'static str, ::engine::core::reflect::FieldValue)> {
+            vec![#((#keys, ::engine::core::reflect::Reflect::to_field_value(&self.#reflected_fields))),*]
+        }
+
+        fn set_field(
+            &mut self,
+            name: &str,
+            value: ::engine::core::reflect::FieldValue,
+        ) -> Result<(), String> {
+            match name {
+                #(#keys => {
+                    self.#reflected_fields = ::engine::core::reflect::Reflect::from_field_value(value)?;
+                    Ok(())
+                })*
+                _ => Err(format!(
+                    "'{}' has no reflected field '{}'",
+                    stringify!(#struct_name),
+                    name
+                )),
+            }
+        }
+    }
+}
+
+enum SaveKind {
+    Value,    // #[save]
+    Texture,  // #[save(texture)], a TextureId saved as an asset path
+    Textures, // #[save(textures)], a Vec<TextureId> saved as asset paths
+}
+
+fn parse_save_attribute(field: &Field) -> syn::Result<Option<SaveKind>> {
+    for attr in &field.attrs {
+        if !attr.path().is_ident("save") {
+            continue;
+        }
+
+        if matches!(attr.meta, Meta::Path(_)) {
+            return Ok(Some(SaveKind::Value));
+        }
+
+        let kind: Ident = attr.parse_args()?;
+        if kind == "texture" {
+            return Ok(Some(SaveKind::Texture));
+        }
+        if kind == "textures" {
+            return Ok(Some(SaveKind::Textures));
+        }
+
+        return Err(syn::Error::new_spanned(
+            kind,
+            "Expected #[save], #[save(texture)] or #[save(textures)]",
+        ));
+    }
+
+    Ok(None)
+}
+
+// Saved fields are keyed by their name without the `m_` prefix
+fn state_key(field: &Ident) -> String {
+    let name = field.to_string();
+    name.strip_prefix("m_").unwrap_or(&name).to_string()
+}
 
-    expanded.into()
+fn generate_save_state(saved_fields: &[(Ident, SaveKind)]) -> proc_macro2::TokenStream {
+    if saved_fields.is_empty() {
+        return quote! {
+            fn save_state(
+                &self,
+                _ctx: &::engine::core::world_state::SaveContext,
+            ) -> Result<Option<::engine::serde_json::Value>, String> {
+                Ok(None)
+            }
+        };
+    }
+
+    let inserts = saved_fields.iter().map(|(field, kind)| {
+        let key = state_key(field);
+        let value = match kind {
+            SaveKind::Value => quote! { ctx.save_value(&self.#field)? },
+            SaveKind::Texture => quote! { ctx.save_texture(self.#field)? },
+            SaveKind::Textures => quote! { ctx.save_textures(&self.#field)? },
+        };
+
+        quote! { state.insert(#key.to_string(), #value); }
+    });
+
+    quote! {
+        fn save_state(
+            &self,
+            ctx: &::engine::core::world_state::SaveContext,
+        ) -> Result<Option<::engine::serde_json::Value>, String> {
+            let mut state = ::engine::serde_json::Map::new();
+            #(#inserts)*
+            Ok(Some(::engine::serde_json::Value::Object(state)))
+        }
+    }
+}
+
+fn generate_load_state(saved_fields: &[(Ident, SaveKind)]) -> proc_macro2::TokenStream {
+    if saved_fields.is_empty() {
+        return quote! {
+            fn load_state(
+                &mut self,
+                _state: &::engine::serde_json::Value,
+                _ctx: &mut ::engine::core::world_state::LoadContext,
+            ) -> Result<(), String> {
+                Ok(())
+            }
+        };
+    }
+
+    // Missing keys keep their current value, so older saves still load
+    let loads = saved_fields.iter().map(|(field, kind)| {
+        let key = state_key(field);
+        let value = match kind {
+            SaveKind::Value => quote! { ctx.load_value(value)? },
+            SaveKind::Texture => quote! { ctx.load_texture(value)? },
+            SaveKind::Textures => quote! { ctx.load_textures(value)? },
+        };
+
+        quote! {
+            if let Some(value) = state.get(#key) {
+                self.#field = #value;
+            }
+        }
+    });
+
+    quote! {
+        fn load_state(
+            &mut self,
+            state: &::engine::serde_json::Value,
+            ctx: &mut ::engine::core::world_state::LoadContext,
+        ) -> Result<(), String> {
+            #(#loads)*
+            Ok(())
+        }
+    }
 }
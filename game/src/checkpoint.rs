@@ -0,0 +1,68 @@
+use std::rc::Rc;
+
+use engine::components::{
+    ColliderComponent, Component, ComponentBase, TransformComponent, component,
+};
+use engine::core::app::App;
+use engine::core::event_bus::EventBus;
+use engine::entity::{Entity, EntityId};
+use engine::math::Vec2;
+
+const CHECKPOINT_SIZE: Vec2 = Vec2 { x: 16.0, y: 16.0 };
+
+// Queued when an entity walks into a checkpoint
+#[derive(Debug, Clone, Copy)]
+pub struct CheckpointEvent {
+    pub entity_id: EntityId,
+    pub position: Vec2,
+}
+
+pub fn create_checkpoint(app: &mut App, position: Vec2) -> Box<Entity> {
+    let mut transform_comp = TransformComponent::new();
+    transform_comp.set_position(position);
+
+    let physics_world = app.get_physics_world();
+    let layer = physics_world.get_layer("trigger").unwrap();
+    let collider_comp = ColliderComponent::new(physics_world, CHECKPOINT_SIZE)
+        .with_layer(layer)
+        .as_trigger();
+    let checkpoint_comp = CheckpointComponent::new(app.get_event_bus());
+
+    let mut entity = Entity::new();
+    entity.add_tag("checkpoint");
+    entity.add_component(transform_comp).unwrap();
+    entity.add_component(collider_comp).unwrap();
+    entity.add_component(checkpoint_comp).unwrap();
+
+    entity
+}
+
+// Invisible trigger that moves the player's respawn point to itself
+#[component(requires(ColliderComponent), serialize, manual_register)]
+pub struct CheckpointComponent {
+    m_event_bus: Rc<EventBus>,
+}
+
+impl Component for CheckpointComponent {
+    fn on_trigger_enter(&mut self, other: EntityId) {
+        let position = self
+            .get_entity()
+            .get_component::<TransformComponent>()
+            .unwrap()
+            .get_position();
+
+        self.m_event_bus.queue(CheckpointEvent {
+            entity_id: other,
+            position,
+        });
+    }
+}
+
+#[component]
+impl CheckpointComponent {
+    pub fn new(event_bus: Rc<EventBus>) -> Self {
+        Self {
+            m_event_bus: event_bus,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use engine::components::{ParticleCurve, ParticleEmitterComponent, TransformComponent};
+use engine::core::app::App;
+use engine::core::assets::TextureId;
+use engine::entity::Entity;
+use engine::math::{Easing, Vec2};
+
+use crate::frames::load_scene_frames;
+
+pub const EFFECTS_NAME: &str = "effects";
+// Where leaves fall from, relative to the top-left of a tree
+const TREE_CANOPY_OFFSET: Vec2 = Vec2 { x: 4.0, y: 4.0 };
+const TREE_CANOPY_SIZE: Vec2 = Vec2 { x: 23.0, y: 13.0 };
+
+// Particle textures shared by the effects of a level, loaded once
+pub struct EffectTextures {
+    pub leaf_frames: Vec<TextureId>,
+    pub particle_frames: Vec<TextureId>,
+}
+
+impl EffectTextures {
+    pub fn load(app: &mut App) -> Result<Self, String> {
+        Ok(Self {
+            leaf_frames: load_scene_frames(app, &["images", "particles", "leaf"])?,
+            particle_frames: load_scene_frames(app, &["images", "particles", "particle"])?,
+        })
+    }
+}
+
+// Leaves drifting down from a tree's canopy
+pub fn create_leaf_emitter(textures: &EffectTextures, tree_position: Vec2) -> Box<Entity> {
+    let mut transform_comp = TransformComponent::new();
+    transform_comp.set_position(tree_position);
+
+    let emitter_comp = ParticleEmitterComponent::new()
+        .with_frames(textures.leaf_frames.clone())
+        .with_rate(0.4)
+        .with_lifetime(4.0, 6.0)
+        .with_speed(12.0, 20.0)
+        .with_angle(100.0, 115.0)
+        .with_spawn_area(TREE_CANOPY_OFFSET, TREE_CANOPY_SIZE)
+        .with_alpha_curve(ParticleCurve::new(1.0, 0.0, Easing::QuadIn))
+        .with_max_particles(32);
+
+    let mut entity = Entity::new();
+    entity.add_tag("leaves");
+    entity.add_component(transform_comp).unwrap();
+    entity.add_component(emitter_comp).unwrap();
+
+    entity
+}
+
+// World space emitter for one-off bursts anywhere in the level, e.g. hit sparks.
+// Found by its name.
+pub fn create_effects(textures: &EffectTextures) -> Box<Entity> {
+    let emitter_comp = ParticleEmitterComponent::new()
+        .with_frames(textures.particle_frames.clone())
+        .with_rate(0.0)
+        .with_lifetime(0.3, 0.6)
+        .with_speed(40.0, 120.0)
+        .with_angle(0.0, 360.0)
+        .with_drag(4.0)
+        .with_spawn_area(Vec2::zero(), Vec2::from_xy(4.0, 4.0))
+        .with_scale_curve(ParticleCurve::new(1.0, 0.0, Easing::QuadIn));
+
+    let mut entity = Entity::new();
+    entity.set_name(EFFECTS_NAME);
+    entity.add_component(TransformComponent::new()).unwrap();
+    entity.add_component(emitter_comp).unwrap();
+
+    entity
+}
+
+// Left behind while dashing, turned on and off by the player
+pub fn create_dash_trail(textures: &EffectTextures) -> ParticleEmitterComponent {
+    ParticleEmitterComponent::new()
+        .with_frames(textures.particle_frames.clone())
+        .with_rate(60.0)
+        .with_emitting(false)
+        .with_lifetime(0.2, 0.4)
+        .with_speed(5.0, 20.0)
+        .with_angle(0.0, 360.0)
+        .with_drag(3.0)
+        .with_spawn_area(Vec2::from_xy(5.0, 6.0), Vec2::from_xy(4.0, 8.0))
+        .with_scale_curve(ParticleCurve::new(0.8, 0.0, Easing::Linear))
+}
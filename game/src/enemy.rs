@@ -0,0 +1,287 @@
+use std::rc::Rc;
+
+use engine::components::{
+    AnimationComponent, CharacterControllerComponent, ColliderComponent, Component, ComponentBase,
+    HealthComponent, ImageComponent, TransformComponent, component,
+};
+use engine::core::app::App;
+use engine::core::assets::TextureId;
+use engine::entity::Entity;
+use engine::math::{Vec2, random_range};
+use engine::physics::{LayerMask, PhysicsWorld, QueryFilter, TILE_LAYER};
+
+use crate::frames::load_scene_frames;
+use crate::projectile::{ProjectilePool, ProjectilePrefab};
+use crate::weapon::WeaponComponent;
+
+const SPRITE_WIDTH: f32 = 14.0;
+// How far past the feet the ground must go on for the enemy to keep walking
+const LEDGE_PROBE_DEPTH: f32 = 4.0;
+
+// Textures shared by all enemies of a level, loaded once
+pub struct EnemyTextures {
+    pub idle_frames: Vec<TextureId>,
+    pub run_frames: Vec<TextureId>,
+    pub gun: TextureId,
+    pub projectile: TextureId,
+}
+
+impl EnemyTextures {
+    pub fn load(app: &mut App) -> Result<Self, String> {
+        let gun_path = app.get_assets().get_asset_path(["images", "gun.png"])?;
+        let projectile_path = app
+            .get_assets()
+            .get_asset_path(["images", "projectile.png"])?;
+
+        Ok(Self {
+            idle_frames: load_scene_frames(app, &["images", "entities", "enemy", "idle"])?,
+            run_frames: load_scene_frames(app, &["images", "entities", "enemy", "run"])?,
+            gun: app.load_scene_texture(gun_path)?,
+            projectile: app.load_scene_texture(projectile_path)?,
+        })
+    }
+}
+
+pub fn create_enemy(
+    app: &mut App,
+    textures: &EnemyTextures,
+    projectile_pool: Rc<ProjectilePool>,
+    position: Vec2,
+) -> Box<Entity> {
+    let mut transform_comp = TransformComponent::new();
+    transform_comp.set_position(position);
+
+    let image_comp = ImageComponent::new();
+    let animation_comp = AnimationComponent::new()
+        .with_clip("idle", textures.idle_frames.clone(), 0.1, true)
+        .with_clip("run", textures.run_frames.clone(), 0.08, true);
+
+    let physics_world = app.get_physics_world();
+    let layer = physics_world.get_layer("enemy").unwrap();
+    let collider_comp = ColliderComponent::new(physics_world.clone(), Vec2::from_xy(8.0, 15.0))
+        .with_offset(Vec2::from_xy(3.0, 3.0))
+        .with_layer(layer);
+    let controller_comp = CharacterControllerComponent::new(physics_world.clone());
+
+    let projectile_layer = physics_world.get_layer("enemy_projectile").unwrap();
+    let projectile = ProjectilePrefab {
+        texture_id: textures.projectile,
+        speed: 150.0,
+        lifetime: 2.0,
+        damage: 1,
+        knockback: 120.0,
+        hit_mask: physics_world
+            .get_collision_layers()
+            .get_collision_mask(projectile_layer),
+    };
+    let weapon_comp = WeaponComponent::new(projectile_pool, app.get_audio())
+        .with_sprite(textures.gun, Vec2::from_xy(5.0, 3.0))
+        .with_offset(Vec2::from_xy(10.0, 8.0), SPRITE_WIDTH / 2.0)
+        .with_muzzle_offset(Vec2::from_xy(5.0, 0.0))
+        .with_fire_rate(1.0 / 1.5)
+        .with_spread(4.0)
+        .with_projectile(projectile);
+    let health_comp = HealthComponent::new(app.get_event_bus()).with_max_health(1);
+    let enemy_comp = EnemyComponent::new(physics_world);
+
+    let mut entity = Entity::new();
+    entity.add_tag("enemy");
+    entity.add_component(transform_comp).unwrap();
+    entity.add_component(image_comp).unwrap();
+    entity.add_component(animation_comp).unwrap();
+    entity.add_component(collider_comp).unwrap();
+    entity.add_component(controller_comp).unwrap();
+    entity.add_component(weapon_comp).unwrap();
+    entity.add_component(health_comp).unwrap();
+    entity.add_component(enemy_comp).unwrap();
+
+    entity
+}
+
+// Patrols: walks for a while, turns at walls and ledges, idles for a while.
+// Shoots at the player when it sees it in front of it.
+#[component(
+    requires(
+        AnimationComponent,
+        CharacterControllerComponent,
+        WeaponComponent,
+        HealthComponent
+    ),
+    serialize,
+    manual_register
+)]
+pub struct EnemyComponent {
+    m_physics_world: Rc<PhysicsWorld>,
+    m_player_layer: LayerMask,
+    #[save]
+    #[reflect]
+    m_speed: f32,
+    #[save]
+    #[reflect]
+    m_gravity: f32,
+    #[save]
+    #[reflect]
+    m_sight_distance: f32,
+    #[save]
+    m_is_walking: bool,
+    // Until the enemy switches between walking and idling
+    #[save]
+    m_state_time: f32,
+    #[save]
+    m_is_facing_left: bool,
+    #[save]
+    m_fall_speed: f32,
+}
+
+impl Component for EnemyComponent {
+    fn physics_tick(&mut self, fixed_delta_time: f32) {
+        // Waits to be removed by the level
+        if self
+            .get_entity()
+            .get_component::<HealthComponent>()
+            .is_some_and(|health| health.is_dead())
+        {
+            return;
+        }
+
+        self.update_state(fixed_delta_time);
+
+        let direction = self.get_facing_direction();
+        let is_walking = self.m_is_walking;
+        let speed = if is_walking { self.m_speed } else { 0.0 };
+
+        self.m_fall_speed += self.m_gravity * fixed_delta_time;
+        let motion = Vec2::from_xy(direction * speed, self.m_fall_speed) * fixed_delta_time;
+
+        let controller = self.get_controller();
+        controller.move_by(motion);
+        let is_grounded = controller.is_grounded();
+        let is_on_wall = controller.is_on_wall();
+        if is_grounded {
+            self.m_fall_speed = 0.0;
+        }
+
+        if is_walking && is_grounded && (is_on_wall || self.is_at_ledge()) {
+            self.m_is_facing_left = !self.m_is_facing_left;
+        }
+
+        let is_facing_left = self.m_is_facing_left;
+        let weapon = self.get_weapon();
+        weapon.set_facing_left(is_facing_left);
+        if weapon.can_fire() && self.can_see_player() {
+            self.get_weapon().fire();
+        }
+    }
+
+    fn tick(&mut self, _delta_time: f32) {
+        let is_walking = self.m_is_walking;
+        let is_facing_left = self.m_is_facing_left;
+        let entity = self.get_entity_mut();
+
+        if let Some(animation) = entity.get_component_mut::<AnimationComponent>() {
+            animation.play(if is_walking { "run" } else { "idle" });
+        }
+        if let Some(image) = entity.get_component_mut::<ImageComponent>() {
+            image.set_flip_x(is_facing_left);
+        }
+    }
+}
+
+#[component]
+impl EnemyComponent {
+    pub fn new(physics_world: Rc<PhysicsWorld>) -> Self {
+        let player_layer = physics_world.get_layer("player").unwrap_or(0);
+
+        Self {
+            m_physics_world: physics_world,
+            m_player_layer: player_layer,
+            m_speed: 40.0,
+            m_gravity: 600.0,
+            m_sight_distance: 160.0,
+            m_is_walking: false,
+            m_state_time: 1.0,
+            m_is_facing_left: fastrand::bool(),
+            m_fall_speed: 0.0,
+        }
+    }
+
+    fn update_state(&mut self, delta_time: f32) {
+        self.m_state_time -= delta_time;
+        if self.m_state_time > 0.0 {
+            return;
+        }
+
+        self.m_is_walking = !self.m_is_walking;
+        if self.m_is_walking {
+            // Sometimes turn around before walking off again
+            if fastrand::bool() {
+                self.m_is_facing_left = !self.m_is_facing_left;
+            }
+            self.m_state_time = random_range(1.5, 4.0);
+        } else {
+            self.m_state_time = random_range(0.5, 2.5);
+        }
+    }
+
+    fn get_controller(&self) -> &mut CharacterControllerComponent {
+        self.get_entity_mut()
+            .get_component_mut::<CharacterControllerComponent>()
+            .unwrap()
+    }
+
+    fn get_weapon(&self) -> &mut WeaponComponent {
+        self.get_entity_mut()
+            .get_component_mut::<WeaponComponent>()
+            .unwrap()
+    }
+
+    fn get_facing_direction(&self) -> f32 {
+        if self.m_is_facing_left { -1.0 } else { 1.0 }
+    }
+
+    // No ground just in front of the feet
+    fn is_at_ledge(&self) -> bool {
+        let Some(bounds) = self
+            .get_entity()
+            .get_component::<ColliderComponent>()
+            .map(|collider| collider.get_bounds())
+        else {
+            return false;
+        };
+
+        let direction = self.get_facing_direction();
+        let probe = Vec2::from_xy(
+            bounds.center().x + direction * (bounds.half_size().x + 1.0),
+            bounds.bottom() - 1.0,
+        );
+
+        self.m_physics_world
+            .raycast(probe, Vec2::down(), LEDGE_PROBE_DEPTH + 1.0, TILE_LAYER)
+            .is_none()
+    }
+
+    // The player is in front, within sight distance and not behind a wall
+    fn can_see_player(&self) -> bool {
+        let Some(bounds) = self
+            .get_entity()
+            .get_component::<ColliderComponent>()
+            .map(|collider| collider.get_bounds())
+        else {
+            return false;
+        };
+
+        let eye = bounds.center();
+        let direction = Vec2::right() * self.get_facing_direction();
+        let filter = QueryFilter::new(self.m_player_layer);
+        let Some(player_hit) =
+            self.m_physics_world
+                .raycast(eye, direction, self.m_sight_distance, filter)
+        else {
+            return false;
+        };
+
+        self.m_physics_world
+            .raycast(eye, direction, player_hit.distance, TILE_LAYER)
+            .is_none()
+    }
+}
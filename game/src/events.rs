@@ -0,0 +1,68 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use engine::core::event_bus::EventBus;
+
+// Gameplay events the level scene queues on the event bus
+
+// Queued when the player's health runs out, before the respawn
+#[derive(Debug, Clone, Copy)]
+pub struct PlayerDied;
+
+// Queued when an enemy takes damage
+#[derive(Debug, Clone, Copy)]
+pub struct EnemyHit {
+    pub damage: i32,
+}
+
+// Queued once when the level is won, before the next one loads
+#[derive(Debug, Clone, Copy)]
+pub struct LevelCompleted {
+    pub level_index: usize,
+}
+
+// Tallies the gameplay events of this run, printed when the game closes
+#[derive(Default)]
+pub struct RunStats {
+    m_death_count: Cell<u32>,
+    m_damage_dealt: Cell<i32>,
+    m_completed_level_count: Cell<usize>,
+}
+
+impl RunStats {
+    // Stays subscribed for the lifetime of the event bus
+    pub fn subscribe(event_bus: &EventBus) -> Rc<Self> {
+        let run_stats = Rc::new(Self::default());
+
+        let stats = run_stats.clone();
+        event_bus.subscribe(move |_: &PlayerDied| {
+            stats.m_death_count.set(stats.m_death_count.get() + 1);
+        });
+        let stats = run_stats.clone();
+        event_bus.subscribe(move |event: &EnemyHit| {
+            stats
+                .m_damage_dealt
+                .set(stats.m_damage_dealt.get() + event.damage);
+        });
+        let stats = run_stats.clone();
+        event_bus.subscribe(move |event: &LevelCompleted| {
+            stats.m_completed_level_count.set(
+                stats
+                    .m_completed_level_count
+                    .get()
+                    .max(event.level_index + 1),
+            );
+        });
+
+        run_stats
+    }
+
+    pub fn get_summary(&self) -> String {
+        format!(
+            "Levels completed: {}, deaths: {}, damage dealt: {}",
+            self.m_completed_level_count.get(),
+            self.m_death_count.get(),
+            self.m_damage_dealt.get()
+        )
+    }
+}
@@ -0,0 +1,21 @@
+use engine::core::app::App;
+use engine::core::assets::TextureId;
+
+// Loads the numbered frames in an image folder, e.g. images/entities/enemy/run/0.png, 1.png..
+// as scene textures, in file name order
+pub fn load_scene_frames(app: &mut App, parts: &[&str]) -> Result<Vec<TextureId>, String> {
+    let dir_path = app.get_assets().get_asset_path(parts)?;
+    let entries = std::fs::read_dir(&dir_path)
+        .map_err(|err| format!("Failed to read '{}': {}", dir_path.display(), err))?;
+
+    let mut frame_paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "png"))
+        .collect();
+    frame_paths.sort();
+
+    frame_paths
+        .into_iter()
+        .map(|path| app.load_scene_texture(path))
+        .collect()
+}
@@ -0,0 +1,56 @@
+use std::rc::Rc;
+
+use engine::components::{ColliderComponent, Component, TransformComponent, component};
+use engine::core::app::App;
+use engine::core::event_bus::EventBus;
+use engine::entity::{Entity, EntityId};
+use engine::math::Vec2;
+
+const LEVEL_EXIT_SIZE: Vec2 = Vec2 { x: 16.0, y: 16.0 };
+
+// Queued when an entity walks into a level exit
+#[derive(Debug, Clone, Copy)]
+pub struct LevelExitEvent {
+    pub entity_id: EntityId,
+}
+
+pub fn create_level_exit(app: &mut App, position: Vec2) -> Box<Entity> {
+    let mut transform_comp = TransformComponent::new();
+    transform_comp.set_position(position);
+
+    let physics_world = app.get_physics_world();
+    let layer = physics_world.get_layer("trigger").unwrap();
+    let collider_comp = ColliderComponent::new(physics_world, LEVEL_EXIT_SIZE)
+        .with_layer(layer)
+        .as_trigger();
+    let level_exit_comp = LevelExitComponent::new(app.get_event_bus());
+
+    let mut entity = Entity::new();
+    entity.add_tag("level_exit");
+    entity.add_component(transform_comp).unwrap();
+    entity.add_component(collider_comp).unwrap();
+    entity.add_component(level_exit_comp).unwrap();
+
+    entity
+}
+
+// Invisible trigger that completes the level when the player reaches it
+#[component(requires(ColliderComponent), serialize, manual_register)]
+pub struct LevelExitComponent {
+    m_event_bus: Rc<EventBus>,
+}
+
+impl Component for LevelExitComponent {
+    fn on_trigger_enter(&mut self, other: EntityId) {
+        self.m_event_bus.queue(LevelExitEvent { entity_id: other });
+    }
+}
+
+#[component]
+impl LevelExitComponent {
+    pub fn new(event_bus: Rc<EventBus>) -> Self {
+        Self {
+            m_event_bus: event_bus,
+        }
+    }
+}
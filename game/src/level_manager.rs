@@ -0,0 +1,135 @@
+use std::cell::Cell;
+use std::path::PathBuf;
+
+use engine::core::assets::Assets;
+use engine::core::path_utils::get_save_dir_path;
+use engine::serde_json::{self, json};
+
+const PROGRESS_FILE_NAME: &str = "progress.json";
+
+// What the player brings into the next level
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayerState {
+    pub health: i32,
+}
+
+// Which parts of the player state are kept between levels
+#[derive(Debug, Clone, Copy)]
+pub struct PlayerCarryOver {
+    pub keep_health: bool,
+}
+
+// Plays the maps in order, maps/0.json, 1.json, .. and starts over after the last one.
+// The current level is saved to disk, so the game continues where it was left.
+// Shared as `Rc<LevelManager>` between the level scenes.
+pub struct LevelManager {
+    m_level_count: usize,
+    m_level_index: Cell<usize>,
+    m_carry_over: PlayerCarryOver,
+    m_player_state: Cell<Option<PlayerState>>,
+}
+
+impl LevelManager {
+    pub fn new(level_count: usize, carry_over: PlayerCarryOver) -> Self {
+        Self {
+            m_level_count: level_count.max(1),
+            m_level_index: Cell::new(0),
+            m_carry_over: carry_over,
+            m_player_state: Cell::new(None),
+        }
+    }
+
+    // Counts the consecutively numbered maps in assets/maps
+    pub fn count_levels(assets: &Assets) -> usize {
+        let mut level_count = 0;
+        while assets
+            .get_asset_path(["maps", &get_map_file_name(level_count)])
+            .is_ok()
+        {
+            level_count += 1;
+        }
+
+        level_count
+    }
+
+    pub fn get_level_index(&self) -> usize {
+        self.m_level_index.get()
+    }
+
+    pub fn get_map_file_name(&self) -> String {
+        get_map_file_name(self.get_level_index())
+    }
+
+    // The state kept from the previous level, None when starting fresh
+    pub fn take_player_state(&self) -> Option<PlayerState> {
+        self.m_player_state.take()
+    }
+
+    // Moves on to the next level, keeping the chosen parts of the player state
+    pub fn complete_level(&self, player_state: PlayerState) {
+        let next_level_index = (self.get_level_index() + 1) % self.m_level_count;
+        self.m_level_index.set(next_level_index);
+
+        if self.m_carry_over.keep_health {
+            self.m_player_state.set(Some(player_state));
+        } else {
+            self.m_player_state.set(None);
+        }
+
+        if let Err(err) = self.save_progress() {
+            eprintln!("Failed to save progress: {}", err);
+        }
+    }
+
+    // Plays the current level again from its start, without any kept state
+    pub fn restart_level(&self) {
+        self.m_player_state.set(None);
+    }
+
+    pub fn load_progress(&self) -> Result<(), String> {
+        let path = get_progress_path();
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let json = std::fs::read_to_string(&path)
+            .map_err(|err| format!("Failed to read '{}': {}", path.display(), err))?;
+        let value: serde_json::Value = serde_json::from_str(&json)
+            .map_err(|err| format!("Failed to parse '{}': {}", path.display(), err))?;
+        let level_index = value["level_index"]
+            .as_u64()
+            .ok_or_else(|| format!("Missing 'level_index' in '{}'", path.display()))?;
+
+        // A saved level that no longer exists starts the game over
+        let level_index = level_index as usize;
+        if level_index < self.m_level_count {
+            self.m_level_index.set(level_index);
+        }
+
+        Ok(())
+    }
+
+    pub fn save_progress(&self) -> Result<(), String> {
+        let path = get_progress_path();
+        let json = serde_json::to_string_pretty(&json!({
+            "level_index": self.get_level_index(),
+        }))
+        .map_err(|err| format!("Failed to serialize progress: {}", err))?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create '{}': {}", parent.display(), err))?;
+        }
+
+        std::fs::write(&path, json)
+            .map_err(|err| format!("Failed to write '{}': {}", path.display(), err))
+    }
+}
+
+fn get_map_file_name(level_index: usize) -> String {
+    format!("{}.json", level_index)
+}
+
+fn get_progress_path() -> PathBuf {
+    get_save_dir_path().join(PROGRESS_FILE_NAME)
+}
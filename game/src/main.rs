@@ -1,12 +1,33 @@
+use std::rc::Rc;
+
 use engine::core::app::App;
+use engine::core::scene::SceneTransition;
+use engine::core::time_dilation::TimeDilationMeter;
+
+use crate::events::RunStats;
+use crate::level_manager::{LevelManager, PlayerCarryOver};
+use crate::projectile::ProjectilePool;
 
+mod checkpoint;
+mod effects;
+mod enemy;
+mod events;
+mod frames;
+mod level_exit;
+mod level_manager;
 mod player;
+mod projectile;
+mod scenes;
+mod weapon;
 
 const TARGET_FPS: u32 = 30;
 const VSYNC_ENABLED: bool = true;
 const WINDOW_TITLE: &str = "Rusty Platformer";
 const WINDOW_WIDTH: u32 = 640;
 const WINDOW_HEIGHT: u32 = 480;
+// Seconds of slow motion on a full meter, and seconds regained per second
+const SLOW_MOTION_CAPACITY: f32 = 3.0;
+const SLOW_MOTION_RECHARGE_RATE: f32 = 0.5;
 
 fn main() {
     let mut app = App::new(
@@ -17,8 +38,78 @@ fn main() {
         WINDOW_HEIGHT,
     );
 
-    let player_entity = player::create_player(&mut app);
-    app.get_entity_spawner().spawn_entity(player_entity);
+    app.get_time_dilation()
+        .set_meter(Some(TimeDilationMeter::new(
+            SLOW_MOTION_CAPACITY,
+            SLOW_MOTION_RECHARGE_RATE,
+        )));
+
+    let projectile_pool = Rc::new(ProjectilePool::new());
+    register_components(&mut app, projectile_pool.clone());
+
+    let level_count = LevelManager::count_levels(app.get_assets());
+    let level_manager = Rc::new(LevelManager::new(
+        level_count,
+        PlayerCarryOver { keep_health: true },
+    ));
+    if let Err(err) = level_manager.load_progress() {
+        eprintln!("Failed to load progress: {}", err);
+    }
+
+    let run_stats = RunStats::subscribe(&app.get_event_bus());
+
+    app.push_scene(
+        Box::new(scenes::LevelScene::new(projectile_pool, level_manager)),
+        SceneTransition::Fade(0.5),
+    );
 
     app.run();
+
+    println!("{}", run_stats.get_summary());
+}
+
+// Game components that need engine services, so saved worlds can recreate them
+fn register_components(app: &mut App, projectile_pool: Rc<ProjectilePool>) {
+    let physics_world = app.get_physics_world();
+    let audio = app.get_audio();
+    let time_dilation = app.get_time_dilation();
+    let scheduler = app.get_scheduler();
+    let event_bus = app.get_event_bus();
+    let component_registry = app.get_component_registry();
+
+    let event_bus_copy = event_bus.clone();
+    let audio_copy = audio.clone();
+    component_registry.register("PlayerComponent", move || {
+        player::PlayerComponent::new(
+            event_bus_copy.clone(),
+            audio_copy.clone(),
+            time_dilation.clone(),
+            scheduler.clone(),
+        )
+    });
+    let event_bus_copy = event_bus.clone();
+    component_registry.register("CheckpointComponent", move || {
+        checkpoint::CheckpointComponent::new(event_bus_copy.clone())
+    });
+    let event_bus_copy = event_bus.clone();
+    component_registry.register("LevelExitComponent", move || {
+        level_exit::LevelExitComponent::new(event_bus_copy.clone())
+    });
+    let physics_world_copy = physics_world.clone();
+    component_registry.register("EnemyComponent", move || {
+        enemy::EnemyComponent::new(physics_world_copy.clone())
+    });
+    let projectile_pool_copy = projectile_pool.clone();
+    let audio_copy = audio.clone();
+    component_registry.register("WeaponComponent", move || {
+        weapon::WeaponComponent::new(projectile_pool_copy.clone(), audio_copy.clone())
+    });
+    component_registry.register("ProjectileComponent", move || {
+        projectile::ProjectileComponent::new(
+            physics_world.clone(),
+            audio.clone(),
+            event_bus.clone(),
+            projectile_pool.clone(),
+        )
+    });
 }
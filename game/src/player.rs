@@ -1,52 +1,177 @@
 use engine::components::{
-    BindingId, Component, ComponentBase, INVALID_BINDING_ID, ImageComponent, InputComponent,
-    TransformComponent, component_priority,
+    BindingId, CharacterControllerComponent, ColliderComponent, Component, ComponentBase,
+    DamageEvent, HealthComponent, INVALID_BINDING_ID, ImageComponent, InputComponent,
+    ParticleEmitterComponent, TransformComponent, component,
 };
+use std::rc::Rc;
+
 use engine::core::app::App;
+use engine::core::audio::Audio;
+use engine::core::event_bus::EventBus;
 use engine::core::input::InputEventType;
+use engine::core::scheduler::{
+    INVALID_SCHEDULE_HANDLE, ScheduleHandle, ScheduleOptions, Scheduler,
+};
+use engine::core::time_dilation::TimeDilation;
 use engine::entity::Entity;
 use engine::math::Vec2;
+use engine::physics::Collision;
+
+use crate::effects::{self, EffectTextures};
+
+pub const DASH_SOUND: &str = "sfx/dash.wav";
 
-pub fn create_player(app: &mut App) -> Box<Entity> {
-    let transform_comp = TransformComponent::new();
-    let player_comp = PlayerComponent::new();
+// Below this the vertical axis drops through one-way platforms
+const DROP_THROUGH_AXIS: f32 = -0.5;
+const DASH_DAMAGE: i32 = 1;
+const DASH_KNOCKBACK: Vec2 = Vec2 { x: 120.0, y: -80.0 };
+// How fast knockback wears off, in px/s per second
+const KNOCKBACK_DRAG: f32 = 600.0;
+// Speed of the invulnerability blinking, in blinks per second
+const BLINK_RATE: f32 = 10.0;
+const SLOW_MOTION_TIME_SCALE: f32 = 0.3;
+// The player keeps half of the normal speed lost in slow motion
+const SLOW_MOTION_EXEMPTION: f32 = 0.5;
+
+pub fn create_player(
+    app: &mut App,
+    effect_textures: &EffectTextures,
+    position: Vec2,
+) -> Box<Entity> {
+    let mut transform_comp = TransformComponent::new();
+    transform_comp.set_position(position);
+    let player_comp = PlayerComponent::new(
+        app.get_event_bus(),
+        app.get_audio(),
+        app.get_time_dilation(),
+        app.get_scheduler(),
+    );
+    let health_comp = HealthComponent::new(app.get_event_bus())
+        .with_max_health(3)
+        .with_hit_invulnerability(1.0);
     let input_comp = InputComponent::new(app.get_input());
+    let physics_world = app.get_physics_world();
+    let layer = physics_world.get_layer("player").unwrap();
+    let collider_comp = ColliderComponent::new(physics_world.clone(), Vec2::from_xy(8.0, 15.0))
+        .with_offset(Vec2::from_xy(3.0, 3.0))
+        .with_layer(layer);
+    let controller_comp = CharacterControllerComponent::new(physics_world);
+    let dash_trail_comp = effects::create_dash_trail(effect_textures);
 
     let texture_path = app
         .get_assets()
         .get_asset_path(["images", "entities", "player", "idle", "00.png"])
         .unwrap();
-    let texture_id = app.get_assets().load_texture(texture_path).unwrap();
-    let mut image_comp = ImageComponent::new(texture_id);
-    image_comp.set_scale(Vec2::one() * 2.0);
+    let texture_id = app.load_scene_texture(texture_path).unwrap();
+    let image_comp = ImageComponent::from_texture(texture_id);
 
     let mut entity = Entity::new();
-    entity.add_component(transform_comp);
-    entity.add_component(player_comp);
-    entity.add_component(input_comp);
-    entity.add_component(image_comp);
+    entity.set_name("player");
+    entity.add_tag("player");
+    entity.add_component(transform_comp).unwrap();
+    entity.add_component(input_comp).unwrap();
+    entity.add_component(collider_comp).unwrap();
+    entity.add_component(controller_comp).unwrap();
+    entity.add_component(health_comp).unwrap();
+    entity.add_component(player_comp).unwrap();
+    entity.add_component(image_comp).unwrap();
+    entity.add_component(dash_trail_comp).unwrap();
 
     entity
 }
 
-#[derive(ComponentBase)]
+// Runs, jumps and dashes. Dashing makes the player invulnerable and hurts what it runs into.
+#[component(
+    requires(
+        TransformComponent,
+        InputComponent,
+        CharacterControllerComponent,
+        HealthComponent
+    ),
+    serialize,
+    manual_register
+)]
 pub struct PlayerComponent {
-    m_entity: *mut Entity,
+    m_event_bus: Rc<EventBus>,
+    m_audio: Rc<Audio>,
+    m_time_dilation: Rc<TimeDilation>,
+    m_scheduler: Rc<Scheduler>,
+    #[save]
+    #[reflect]
     m_speed: f32,
+    #[save]
+    #[reflect]
+    m_jump_speed: f32,
+    #[save]
+    #[reflect]
+    m_gravity: f32,
+    #[save]
+    #[reflect]
+    m_max_fall_speed: f32,
+    #[save]
+    #[reflect]
+    m_dash_speed: f32,
+    #[save]
+    #[reflect]
+    m_dash_duration: f32,
+    #[save]
+    #[reflect]
+    m_dash_cooldown: f32,
+    #[save]
+    m_velocity: Vec2,
+    // Decays on its own, so it isn't overwritten by the movement input
+    #[save]
+    m_knockback: Vec2,
+    #[save]
+    m_is_facing_left: bool,
+    #[save]
+    m_dash_time_left: f32,
+    m_blink_time: f32,
+    // Scheduled when a dash starts, dashing again waits until it has run
+    m_dash_cooldown_handle: ScheduleHandle,
     m_movement_input: Vec2,
+    m_jump_requested: bool,
+    m_dash_requested: bool,
     m_x_axis_binding_id: BindingId,
     m_y_axis_binding_id: BindingId,
+    m_jump_action_binding_id: BindingId,
+    m_dash_action_binding_id: BindingId,
     m_slow_motion_action_binding_id: BindingId,
 }
 
+#[component]
 impl PlayerComponent {
-    pub fn new() -> Self {
+    pub fn new(
+        event_bus: Rc<EventBus>,
+        audio: Rc<Audio>,
+        time_dilation: Rc<TimeDilation>,
+        scheduler: Rc<Scheduler>,
+    ) -> Self {
         Self {
-            m_entity: std::ptr::null_mut(),
-            m_speed: 300.0,
+            m_event_bus: event_bus,
+            m_audio: audio,
+            m_time_dilation: time_dilation,
+            m_scheduler: scheduler,
+            m_speed: 100.0,
+            m_jump_speed: 230.0,
+            m_gravity: 600.0,
+            m_max_fall_speed: 300.0,
+            m_dash_speed: 300.0,
+            m_dash_duration: 0.15,
+            m_dash_cooldown: 0.5,
+            m_velocity: Vec2::zero(),
+            m_knockback: Vec2::zero(),
+            m_is_facing_left: false,
+            m_dash_time_left: 0.0,
+            m_blink_time: 0.0,
+            m_dash_cooldown_handle: INVALID_SCHEDULE_HANDLE,
             m_movement_input: Vec2::zero(),
+            m_jump_requested: false,
+            m_dash_requested: false,
             m_x_axis_binding_id: INVALID_BINDING_ID,
             m_y_axis_binding_id: INVALID_BINDING_ID,
+            m_jump_action_binding_id: INVALID_BINDING_ID,
+            m_dash_action_binding_id: INVALID_BINDING_ID,
             m_slow_motion_action_binding_id: INVALID_BINDING_ID,
         }
     }
@@ -59,17 +184,62 @@ impl PlayerComponent {
         self.m_movement_input.y = y_axis;
     }
 
+    fn get_controller(&self) -> &mut CharacterControllerComponent {
+        self.get_entity_mut()
+            .get_component_mut::<CharacterControllerComponent>()
+            .unwrap()
+    }
+
+    fn get_health(&self) -> &mut HealthComponent {
+        self.get_entity_mut()
+            .get_component_mut::<HealthComponent>()
+            .unwrap()
+    }
+
+    pub fn is_dashing(&self) -> bool {
+        self.m_dash_time_left > 0.0
+    }
+
+    fn is_dash_ready(&self) -> bool {
+        !self.m_scheduler.is_scheduled(self.m_dash_cooldown_handle)
+    }
+
+    fn request_jump(&mut self) {
+        self.m_jump_requested = true;
+    }
+
+    fn request_dash(&mut self) {
+        self.m_dash_requested = true;
+    }
+
+    fn start_dash(&mut self) {
+        self.m_dash_time_left = self.m_dash_duration;
+        self.m_dash_cooldown_handle = self.m_scheduler.after_with(
+            self.m_dash_cooldown,
+            ScheduleOptions::for_entity(self.get_entity().get_id()),
+            || {},
+        );
+        self.m_velocity.y = 0.0;
+        self.m_audio.play_sound(DASH_SOUND);
+
+        let dash_duration = self.m_dash_duration;
+        self.get_health().set_invulnerable(dash_duration);
+    }
+
+    fn get_facing_direction(&self) -> f32 {
+        if self.m_is_facing_left { -1.0 } else { 1.0 }
+    }
+
     fn toggle_slow_motion(&self) {
-        println!("TODO Implement toggle_slow_motion");
+        self.m_time_dilation.toggle(SLOW_MOTION_TIME_SCALE);
     }
 }
 
 impl Component for PlayerComponent {
-    fn get_priority(&self) -> i32 {
-        component_priority::DEFAULT
-    }
-
     fn enter_play(&mut self) {
+        self.m_time_dilation
+            .set_exemption(self.get_entity().get_id(), SLOW_MOTION_EXEMPTION);
+
         let this: *mut PlayerComponent = self;
         unsafe {
             let input_comp = (*this)
@@ -85,6 +255,16 @@ impl Component for PlayerComponent {
                 (*this).set_movement_input_y(axis);
             });
 
+            (*this).m_jump_action_binding_id =
+                input_comp.bind_action("jump", InputEventType::Pressed, move || {
+                    (*this).request_jump();
+                });
+
+            (*this).m_dash_action_binding_id =
+                input_comp.bind_action("dash", InputEventType::Pressed, move || {
+                    (*this).request_dash();
+                });
+
             (*this).m_slow_motion_action_binding_id =
                 input_comp.bind_action("slow_motion", InputEventType::Pressed, move || {
                     (*this).toggle_slow_motion();
@@ -100,28 +280,122 @@ impl Component for PlayerComponent {
 
         input_comp.unbind_axis("horizontal", self.m_x_axis_binding_id);
         input_comp.unbind_axis("vertical", self.m_y_axis_binding_id);
+        input_comp.unbind_action("jump", self.m_jump_action_binding_id);
+        input_comp.unbind_action("dash", self.m_dash_action_binding_id);
         input_comp.unbind_action("slow_motion", self.m_slow_motion_action_binding_id);
 
+        self.m_time_dilation
+            .remove_exemption(self.get_entity().get_id());
+
         self.m_movement_input = Vec2::zero();
+        self.m_jump_requested = false;
+        self.m_dash_requested = false;
     }
 
-    fn tick(&mut self, _delta_time: f32) {
-        let transform_comp = self
-            .get_entity_mut()
-            .get_component_mut::<TransformComponent>()
-            .unwrap();
+    fn tick(&mut self, delta_time: f32) {
+        self.m_blink_time += delta_time;
 
-        let movement_input;
-        if self.m_movement_input.len_sqr() > 1.0 {
-            movement_input = self.m_movement_input.normalized();
+        let health = self.get_health();
+        let alpha = if health.is_dead() {
+            0.0
+        } else if health.is_invulnerable() && !self.is_dashing() {
+            // Blinks while invulnerable after a hit
+            if (self.m_blink_time * BLINK_RATE) as i32 % 2 == 0 {
+                0.3
+            } else {
+                1.0
+            }
         } else {
-            movement_input = self.m_movement_input;
+            1.0
+        };
+
+        let is_facing_left = self.m_is_facing_left;
+        let is_dashing = self.is_dashing();
+        let entity = self.get_entity_mut();
+        if let Some(image) = entity.get_component_mut::<ImageComponent>() {
+            image.set_alpha(alpha);
+            image.set_flip_x(is_facing_left);
+        }
+        if let Some(dash_trail) = entity.get_component_mut::<ParticleEmitterComponent>() {
+            dash_trail.set_emitting(is_dashing);
+        }
+    }
+
+    fn on_collision(&mut self, collision: &Collision) {
+        if !self.is_dashing() {
+            return;
         }
 
-        let pos_delta_x = Vec2::right() * movement_input.x;
-        let pos_delta_y = Vec2::up() * movement_input.y;
-        let pos_delta = (pos_delta_x + pos_delta_y) * self.m_speed * _delta_time;
-        let new_pos = transform_comp.get_position() + pos_delta;
-        transform_comp.set_position(new_pos);
+        let knockback = Vec2::from_xy(
+            DASH_KNOCKBACK.x * self.get_facing_direction(),
+            DASH_KNOCKBACK.y,
+        );
+        self.m_event_bus.queue(DamageEvent {
+            target_id: collision.other,
+            source_id: self.get_entity().get_id(),
+            amount: DASH_DAMAGE,
+            knockback,
+        });
+    }
+
+    fn physics_tick(&mut self, fixed_delta_time: f32) {
+        self.m_dash_time_left = (self.m_dash_time_left - fixed_delta_time).max(0.0);
+
+        // Dead players wait for the level to respawn them
+        let health = self.get_health();
+        if health.is_dead() {
+            self.m_velocity = Vec2::zero();
+            self.m_knockback = Vec2::zero();
+            self.m_dash_time_left = 0.0;
+            self.m_jump_requested = false;
+            self.m_dash_requested = false;
+            return;
+        }
+
+        let knockback = health.take_knockback();
+        self.m_knockback.x += knockback.x;
+        self.m_velocity.y += knockback.y;
+        let drag = KNOCKBACK_DRAG * fixed_delta_time;
+        self.m_knockback.x -= self.m_knockback.x.clamp(-drag, drag);
+
+        if self.m_movement_input.x != 0.0 && !self.is_dashing() {
+            self.m_is_facing_left = self.m_movement_input.x < 0.0;
+        }
+
+        if self.m_dash_requested && self.is_dash_ready() {
+            self.start_dash();
+        }
+        self.m_dash_requested = false;
+
+        if self.is_dashing() {
+            let motion = Vec2::from_xy(self.get_facing_direction() * self.m_dash_speed, 0.0);
+            self.get_controller().move_by(motion * fixed_delta_time);
+            self.m_jump_requested = false;
+            return;
+        }
+
+        let controller = self.get_controller();
+        if controller.is_grounded() {
+            if self.m_movement_input.y < DROP_THROUGH_AXIS && controller.is_on_one_way() {
+                controller.drop_through();
+            } else if self.m_jump_requested {
+                self.m_velocity.y = -self.m_jump_speed;
+            }
+        }
+        self.m_jump_requested = false;
+
+        self.m_velocity.x =
+            self.m_movement_input.x.clamp(-1.0, 1.0) * self.m_speed + self.m_knockback.x;
+        self.m_velocity.y =
+            (self.m_velocity.y + self.m_gravity * fixed_delta_time).min(self.m_max_fall_speed);
+
+        let controller = self.get_controller();
+        controller.move_by(self.m_velocity * fixed_delta_time);
+
+        let is_landing = controller.is_grounded() && self.m_velocity.y > 0.0;
+        let is_bumping_head = controller.is_on_ceiling() && self.m_velocity.y < 0.0;
+        if is_landing || is_bumping_head {
+            self.m_velocity.y = 0.0;
+        }
     }
 }
@@ -0,0 +1,267 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use engine::components::{Component, ComponentBase, DamageEvent, TransformComponent, component};
+use engine::core::app::App;
+use engine::core::assets::{INVALID_TEXTURE_ID, TextureId};
+use engine::core::audio::Audio;
+use engine::core::event_bus::EventBus;
+use engine::core::render::{RenderData, RenderQueue};
+use engine::entity::{Entity, EntityId, INVALID_ENTITY_ID};
+use engine::math::Vec2;
+use engine::physics::{HitTarget, LayerMask, PhysicsWorld, QueryFilter};
+
+pub const HIT_SOUND: &str = "sfx/hit.wav";
+
+// What a weapon fires
+#[derive(Debug, Clone, Copy)]
+pub struct ProjectilePrefab {
+    pub texture_id: TextureId,
+    pub speed: f32,
+    pub lifetime: f32,
+    pub damage: i32,
+    // Speed the target is pushed away with
+    pub knockback: f32,
+    // Layers the projectile stops at, e.g. the world and the player for enemy shots
+    pub hit_mask: LayerMask,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Shot {
+    pub prefab: ProjectilePrefab,
+    pub position: Vec2,
+    pub direction: Vec2,
+    // Never hit by its own shots
+    pub owner_id: EntityId,
+}
+
+// Projectiles are spawned once per level and reused, fire() hands a shot to the next free one.
+// Shared as `Rc<ProjectilePool>` between the weapons and the projectiles.
+pub struct ProjectilePool {
+    m_shots: RefCell<VecDeque<Shot>>,
+    m_free_count: Cell<usize>,
+}
+
+impl ProjectilePool {
+    pub fn new() -> Self {
+        Self {
+            m_shots: RefCell::new(VecDeque::new()),
+            m_free_count: Cell::new(0),
+        }
+    }
+
+    // Spawns the projectiles as scene entities, they go away with the scene
+    pub fn spawn_projectiles(self: &Rc<Self>, app: &mut App, count: usize) {
+        for _ in 0..count {
+            let projectile_entity = create_projectile(app, self.clone());
+            app.spawn_scene_entity(projectile_entity);
+        }
+    }
+
+    // Returns false when all projectiles are in flight and the shot is dropped
+    pub fn fire(&self, shot: Shot) -> bool {
+        let mut shots = self.m_shots.borrow_mut();
+        if shots.len() >= self.m_free_count.get() {
+            return false;
+        }
+
+        shots.push_back(shot);
+        true
+    }
+
+    // Drops the shots no projectile has picked up yet
+    pub fn clear(&self) {
+        self.m_shots.borrow_mut().clear();
+    }
+
+    fn take_shot(&self) -> Option<Shot> {
+        let shot = self.m_shots.borrow_mut().pop_front()?;
+        self.claim();
+        Some(shot)
+    }
+
+    fn claim(&self) {
+        self.m_free_count
+            .set(self.m_free_count.get().saturating_sub(1));
+    }
+
+    fn release(&self) {
+        self.m_free_count.set(self.m_free_count.get() + 1);
+    }
+}
+
+pub fn create_projectile(app: &mut App, pool: Rc<ProjectilePool>) -> Box<Entity> {
+    let transform_comp = TransformComponent::new();
+    let projectile_comp = ProjectileComponent::new(
+        app.get_physics_world(),
+        app.get_audio(),
+        app.get_event_bus(),
+        pool,
+    );
+
+    let mut entity = Entity::new();
+    entity.add_tag("projectile");
+    entity.add_component(transform_comp).unwrap();
+    entity.add_component(projectile_comp).unwrap();
+
+    entity
+}
+
+// Flies in a straight line until it hits something in the hit mask or runs out of time,
+// then goes back to the pool. Entities it hits get a DamageEvent from the owner.
+// Moves by raycasting, so fast shots don't skip through thin walls.
+// Draws itself, so inactive projectiles don't show up.
+#[component(requires(TransformComponent), serialize, manual_register)]
+pub struct ProjectileComponent {
+    m_physics_world: Rc<PhysicsWorld>,
+    m_audio: Rc<Audio>,
+    m_event_bus: Rc<EventBus>,
+    m_pool: Rc<ProjectilePool>,
+    #[save]
+    m_is_active: bool,
+    #[save(texture)]
+    m_texture_id: TextureId,
+    #[save]
+    m_velocity: Vec2,
+    #[save]
+    #[reflect]
+    m_lifetime: f32,
+    #[save]
+    #[reflect]
+    m_damage: i32,
+    #[save]
+    #[reflect]
+    m_knockback: f32,
+    #[save]
+    m_hit_mask: LayerMask,
+    #[save]
+    m_owner_id: EntityId,
+}
+
+impl Component for ProjectileComponent {
+    fn enter_play(&mut self) {
+        if !self.m_is_active {
+            self.m_pool.release();
+        }
+    }
+
+    fn exit_play(&mut self) {
+        if !self.m_is_active {
+            self.m_pool.claim();
+        }
+    }
+
+    fn physics_tick(&mut self, fixed_delta_time: f32) {
+        if !self.m_is_active {
+            match self.m_pool.take_shot() {
+                Some(shot) => self.launch(shot),
+                None => return,
+            }
+        }
+
+        self.m_lifetime -= fixed_delta_time;
+        if self.m_lifetime <= 0.0 {
+            self.deactivate();
+            return;
+        }
+
+        let transform = self
+            .get_entity_mut()
+            .get_component_mut::<TransformComponent>()
+            .unwrap();
+
+        let position = transform.get_position();
+        let motion = self.m_velocity * fixed_delta_time;
+        let filter = QueryFilter::new(self.m_hit_mask).excluding(self.m_owner_id);
+        let hit = self
+            .m_physics_world
+            .raycast(position, motion.normalized(), motion.len(), filter);
+
+        transform.set_position(hit.map_or(position + motion, |hit| hit.point));
+
+        if let Some(hit) = hit {
+            if let HitTarget::Entity(target_id) = hit.target {
+                self.m_event_bus.queue(DamageEvent {
+                    target_id,
+                    source_id: self.m_owner_id,
+                    amount: self.m_damage,
+                    knockback: self.m_velocity.normalized() * self.m_knockback,
+                });
+            }
+
+            self.m_audio.play_sound(HIT_SOUND);
+            self.deactivate();
+        }
+    }
+
+    fn render_tick(&mut self, _delta_time: f32, render_queue: &mut RenderQueue) {
+        if !self.m_is_active {
+            return;
+        }
+
+        let transform = self
+            .get_entity()
+            .get_component::<TransformComponent>()
+            .unwrap();
+
+        render_queue.enqueue(
+            RenderData::new(
+                self.m_texture_id,
+                transform.get_position(),
+                transform.get_prev_position(),
+                transform.get_scale(),
+            )
+            .with_flip_x(self.m_velocity.x < 0.0),
+        );
+    }
+}
+
+#[component]
+impl ProjectileComponent {
+    pub fn new(
+        physics_world: Rc<PhysicsWorld>,
+        audio: Rc<Audio>,
+        event_bus: Rc<EventBus>,
+        pool: Rc<ProjectilePool>,
+    ) -> Self {
+        Self {
+            m_physics_world: physics_world,
+            m_audio: audio,
+            m_event_bus: event_bus,
+            m_pool: pool,
+            m_is_active: false,
+            m_texture_id: INVALID_TEXTURE_ID,
+            m_velocity: Vec2::zero(),
+            m_lifetime: 0.0,
+            m_damage: 0,
+            m_knockback: 0.0,
+            m_hit_mask: 0,
+            m_owner_id: INVALID_ENTITY_ID,
+        }
+    }
+
+    fn launch(&mut self, shot: Shot) {
+        self.m_is_active = true;
+        self.m_texture_id = shot.prefab.texture_id;
+        self.m_velocity = shot.direction.normalized() * shot.prefab.speed;
+        self.m_lifetime = shot.prefab.lifetime;
+        self.m_damage = shot.prefab.damage;
+        self.m_knockback = shot.prefab.knockback;
+        self.m_hit_mask = shot.prefab.hit_mask;
+        self.m_owner_id = shot.owner_id;
+
+        self.get_entity_mut()
+            .get_component_mut::<TransformComponent>()
+            .unwrap()
+            .teleport(shot.position);
+    }
+
+    fn deactivate(&mut self) {
+        self.m_is_active = false;
+        self.m_velocity = Vec2::zero();
+        self.m_lifetime = 0.0;
+        self.m_owner_id = INVALID_ENTITY_ID;
+        self.m_pool.release();
+    }
+}
@@ -0,0 +1,612 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use engine::components::{
+    ColliderComponent, DeathEvent, HealthComponent, HurtEvent, ParticleEmitterComponent,
+    TransformComponent,
+};
+use engine::core::app::App;
+use engine::core::autotile::autotile_tilemap;
+use engine::core::background::{BackgroundLayer, CloudField};
+use engine::core::camera_effects::{
+    CameraPunchEvent, CameraShakeEvent, HitStopEvent, ScreenFlashEvent,
+};
+use engine::core::event_bus::EventSubscriptionId;
+use engine::core::input::{INVALID_INPUT_EVENT_HANDLER_ID, InputEventHandlerId, InputEventType};
+use engine::core::path_utils::get_save_dir_path;
+use engine::core::scene::{Scene, SceneTransition};
+use engine::core::tilemap::Tilemap;
+use engine::core::tileset::Tileset;
+use engine::entity::{Entity, EntityId};
+use engine::math::Vec2;
+use engine::sdl2::pixels::Color;
+use engine::serde_json::{self, json};
+
+use crate::checkpoint::{self, CheckpointEvent};
+use crate::effects::{self, EffectTextures};
+use crate::enemy::{self, EnemyTextures};
+use crate::events::{EnemyHit, LevelCompleted, PlayerDied};
+use crate::level_exit::{self, LevelExitEvent};
+use crate::level_manager::{LevelManager, PlayerState};
+use crate::player;
+use crate::projectile::{self, ProjectilePool};
+use crate::scenes::PauseScene;
+use crate::weapon;
+
+const TILESET_PATH: [&str; 2] = ["tilesets", "tileset.json"];
+const SPAWNER_TILE_TYPE: &str = "spawners";
+const LARGE_DECOR_TILE_TYPE: &str = "large_decor";
+const TREE_DECOR: i32 = 2;
+const PLAYER_SPAWNER: i32 = 0;
+const ENEMY_SPAWNER: i32 = 1;
+const CHECKPOINT_SPAWNER: i32 = 2;
+const EXIT_SPAWNER: i32 = 3;
+const PLAYER_NAME: &str = "player";
+const DEATH_SOUND: &str = "sfx/hit.wav";
+// Seconds between the player's death and respawn
+const RESPAWN_DELAY: f32 = 1.5;
+const RESPAWN_INVULNERABILITY: f32 = 1.0;
+// Seconds for each of the fade-out and fade-in between levels
+const LEVEL_FADE_DURATION: f32 = 0.5;
+const BACKGROUND_IMAGE_PATH: [&str; 2] = ["images", "background.png"];
+const CLOUD_IMAGE_PATHS: [[&str; 3]; 2] = [
+    ["images", "clouds", "cloud_1.png"],
+    ["images", "clouds", "cloud_2.png"],
+];
+// How quickly the camera catches up with the player, per second
+const CAMERA_FOLLOW_SPEED: f32 = 4.0;
+// Camera effects of hits and deaths, trauma in [0, 1] and durations in seconds
+const HIT_TRAUMA: f32 = 0.3;
+const HIT_STOP_DURATION: f32 = 0.05;
+const PLAYER_HIT_FLASH_COLOR: Color = Color::RGBA(255, 40, 40, 96);
+const PLAYER_HIT_FLASH_DURATION: f32 = 0.2;
+const DEATH_TRAUMA: f32 = 0.6;
+const DEATH_HIT_STOP_DURATION: f32 = 0.12;
+const DEATH_ZOOM_PUNCH: f32 = 0.08;
+const HIT_SPARK_COUNT: usize = 8;
+const DEATH_BURST_COUNT: usize = 30;
+// Most projectiles that can be in flight at once
+const PROJECTILE_POOL_SIZE: usize = 32;
+
+pub struct LevelScene {
+    m_projectile_pool: Rc<ProjectilePool>,
+    m_level_manager: Rc<LevelManager>,
+    m_pressed_actions: Rc<RefCell<Vec<String>>>,
+    m_hurt_events: Rc<RefCell<Vec<HurtEvent>>>,
+    m_death_events: Rc<RefCell<Vec<DeathEvent>>>,
+    m_checkpoint_events: Rc<RefCell<Vec<CheckpointEvent>>>,
+    m_level_exit_events: Rc<RefCell<Vec<LevelExitEvent>>>,
+    m_event_subscription_ids: Vec<EventSubscriptionId>,
+    // The map's player spawn until a checkpoint is reached
+    m_respawn_position: Vec2,
+    // Without a checkpoint, dying restarts the level
+    m_has_reached_checkpoint: bool,
+    m_respawn_time_left: Option<f32>,
+    m_enemy_count: usize,
+    m_is_level_complete: bool,
+    m_input_event_handler_id: InputEventHandlerId,
+}
+
+impl LevelScene {
+    pub fn new(projectile_pool: Rc<ProjectilePool>, level_manager: Rc<LevelManager>) -> Self {
+        Self {
+            m_projectile_pool: projectile_pool,
+            m_level_manager: level_manager,
+            m_pressed_actions: Rc::new(RefCell::new(Vec::new())),
+            m_hurt_events: Rc::new(RefCell::new(Vec::new())),
+            m_death_events: Rc::new(RefCell::new(Vec::new())),
+            m_checkpoint_events: Rc::new(RefCell::new(Vec::new())),
+            m_level_exit_events: Rc::new(RefCell::new(Vec::new())),
+            m_event_subscription_ids: Vec::new(),
+            m_respawn_position: Vec2::zero(),
+            m_has_reached_checkpoint: false,
+            m_respawn_time_left: None,
+            m_enemy_count: 0,
+            m_is_level_complete: false,
+            m_input_event_handler_id: INVALID_INPUT_EVENT_HANDLER_ID,
+        }
+    }
+
+    // Sets up the map's tile collision and spawns the entities of its spawners
+    fn load_level(&mut self, app: &mut App) -> Result<(), String> {
+        let assets = app.get_assets();
+        let tileset = Tileset::from_file(assets.get_asset_path(TILESET_PATH)?)?;
+        let map_file_name = self.m_level_manager.get_map_file_name();
+        let mut tilemap = Tilemap::from_file(assets.get_asset_path(["maps", &map_file_name])?)?;
+        // Hand edited maps may have grass and stone variants that don't fit their neighbors
+        autotile_tilemap(&tileset, &mut tilemap);
+
+        app.get_physics_world().set_tilemap(&tilemap, &tileset);
+
+        self.m_projectile_pool
+            .spawn_projectiles(app, PROJECTILE_POOL_SIZE);
+
+        let enemy_textures = EnemyTextures::load(app)?;
+        let effect_textures = EffectTextures::load(app)?;
+        app.get_audio().load_sound(weapon::SHOOT_SOUND)?;
+        app.get_audio().load_sound(projectile::HIT_SOUND)?;
+        app.get_audio().load_sound(player::DASH_SOUND)?;
+        app.get_audio().load_sound(DEATH_SOUND)?;
+        let mut player_position = Vec2::zero();
+        for tile in tilemap.get_offgrid_tiles() {
+            if tile.tile_type == LARGE_DECOR_TILE_TYPE && tile.variant == TREE_DECOR {
+                let leaf_entity = effects::create_leaf_emitter(&effect_textures, tile.pos);
+                app.spawn_scene_entity(leaf_entity);
+            }
+            if tile.tile_type != SPAWNER_TILE_TYPE {
+                continue;
+            }
+
+            match tile.variant {
+                PLAYER_SPAWNER => player_position = tile.pos,
+                ENEMY_SPAWNER => {
+                    let enemy_entity = enemy::create_enemy(
+                        app,
+                        &enemy_textures,
+                        self.m_projectile_pool.clone(),
+                        tile.pos,
+                    );
+                    app.spawn_scene_entity(enemy_entity);
+                    self.m_enemy_count += 1;
+                }
+                CHECKPOINT_SPAWNER => {
+                    let checkpoint_entity = checkpoint::create_checkpoint(app, tile.pos);
+                    app.spawn_scene_entity(checkpoint_entity);
+                }
+                EXIT_SPAWNER => {
+                    let level_exit_entity = level_exit::create_level_exit(app, tile.pos);
+                    app.spawn_scene_entity(level_exit_entity);
+                }
+                _ => {}
+            }
+        }
+
+        self.m_respawn_position = player_position;
+        app.set_camera_position(player_position - app.get_view_size() / 2.0);
+        let mut player_entity = player::create_player(app, &effect_textures, player_position);
+        if let Some(player_state) = self.m_level_manager.take_player_state() {
+            apply_player_state(&mut player_entity, player_state);
+        }
+        app.spawn_scene_entity(player_entity);
+
+        let effects_entity = effects::create_effects(&effect_textures);
+        app.spawn_scene_entity(effects_entity);
+
+        Ok(())
+    }
+
+    // A static sky with clouds drifting in front of it
+    fn load_background(&mut self, app: &mut App) -> Result<(), String> {
+        let background_path = app.get_assets().get_asset_path(BACKGROUND_IMAGE_PATH)?;
+        let background_texture_id = app.load_scene_texture(background_path)?;
+
+        let mut cloud_texture_ids = Vec::new();
+        for cloud_image_path in CLOUD_IMAGE_PATHS {
+            let cloud_path = app.get_assets().get_asset_path(cloud_image_path)?;
+            cloud_texture_ids.push(app.load_scene_texture(cloud_path)?);
+        }
+
+        let mut cloud_field = CloudField::new(cloud_texture_ids);
+        cloud_field.generate(app.get_view_size());
+
+        let background = app.get_background();
+        background.add_layer(BackgroundLayer::stretched(background_texture_id));
+        background.set_cloud_field(cloud_field);
+
+        Ok(())
+    }
+
+    // Keeps the player in the middle of the screen, with a bit of lag
+    fn update_camera(&mut self, app: &mut App, delta_time: f32) {
+        let Some(player_center) = app
+            .get_entity_spawner()
+            .find_by_name(PLAYER_NAME)
+            .and_then(|player| player.get_component::<ColliderComponent>())
+            .map(|collider| collider.get_bounds().center())
+        else {
+            return;
+        };
+
+        let camera_position = app.get_camera_position();
+        let target = player_center - app.get_view_size() / 2.0;
+        let t = (CAMERA_FOLLOW_SPEED * delta_time).min(1.0);
+        app.set_camera_position(Vec2::lerp(camera_position, target, t));
+    }
+
+    fn subscribe_to_events(&mut self, app: &mut App) {
+        let event_bus = app.get_event_bus();
+
+        let hurt_events = self.m_hurt_events.clone();
+        self.m_event_subscription_ids
+            .push(event_bus.subscribe(move |event: &HurtEvent| {
+                hurt_events.borrow_mut().push(*event);
+            }));
+
+        let death_events = self.m_death_events.clone();
+        self.m_event_subscription_ids
+            .push(event_bus.subscribe(move |event: &DeathEvent| {
+                death_events.borrow_mut().push(*event);
+            }));
+
+        let checkpoint_events = self.m_checkpoint_events.clone();
+        self.m_event_subscription_ids
+            .push(event_bus.subscribe(move |event: &CheckpointEvent| {
+                checkpoint_events.borrow_mut().push(*event);
+            }));
+
+        let level_exit_events = self.m_level_exit_events.clone();
+        self.m_event_subscription_ids
+            .push(event_bus.subscribe(move |event: &LevelExitEvent| {
+                level_exit_events.borrow_mut().push(*event);
+            }));
+    }
+
+    fn unsubscribe_from_events(&mut self, app: &mut App) {
+        let event_bus = app.get_event_bus();
+        for subscription_id in self.m_event_subscription_ids.drain(..) {
+            event_bus.unsubscribe(subscription_id);
+        }
+
+        self.m_hurt_events.borrow_mut().clear();
+        self.m_death_events.borrow_mut().clear();
+        self.m_checkpoint_events.borrow_mut().clear();
+        self.m_level_exit_events.borrow_mut().clear();
+    }
+
+    fn handle_hurts(&mut self, app: &mut App) {
+        let hurt_events = std::mem::take(&mut *self.m_hurt_events.borrow_mut());
+        for event in hurt_events {
+            if let Some(center) = get_entity_center(app, event.entity_id) {
+                burst_effects(app, center, HIT_SPARK_COUNT);
+            }
+
+            let (is_player, is_enemy) = app
+                .get_entity_spawner()
+                .get_entity(event.entity_id)
+                .map_or((false, false), |entity| {
+                    (entity.has_tag("player"), entity.has_tag("enemy"))
+                });
+
+            let event_bus = app.get_event_bus();
+            if is_enemy {
+                event_bus.queue(EnemyHit {
+                    damage: event.amount,
+                });
+            }
+            event_bus.queue(CameraShakeEvent { trauma: HIT_TRAUMA });
+            event_bus.queue(HitStopEvent {
+                duration: HIT_STOP_DURATION,
+            });
+            if is_player {
+                event_bus.queue(ScreenFlashEvent {
+                    color: PLAYER_HIT_FLASH_COLOR,
+                    duration: PLAYER_HIT_FLASH_DURATION,
+                });
+            }
+        }
+    }
+
+    // The player respawns after a moment, enemies are gone for good
+    fn handle_deaths(&mut self, app: &mut App) {
+        let death_events = std::mem::take(&mut *self.m_death_events.borrow_mut());
+        for event in death_events {
+            let center = get_entity_center(app, event.entity_id).unwrap_or(event.position);
+            burst_effects(app, center, DEATH_BURST_COUNT);
+
+            let event_bus = app.get_event_bus();
+            event_bus.queue(CameraShakeEvent {
+                trauma: DEATH_TRAUMA,
+            });
+            event_bus.queue(HitStopEvent {
+                duration: DEATH_HIT_STOP_DURATION,
+            });
+            event_bus.queue(CameraPunchEvent {
+                offset: Vec2::zero(),
+                zoom: DEATH_ZOOM_PUNCH,
+            });
+
+            let entity_spawner = app.get_entity_spawner();
+            let Some(entity) = entity_spawner.get_entity(event.entity_id) else {
+                continue;
+            };
+
+            if entity.has_tag("player") {
+                self.m_respawn_time_left = Some(RESPAWN_DELAY);
+                app.get_event_bus().queue(PlayerDied);
+            } else if entity.has_tag("enemy") {
+                entity_spawner.destroy_entity(event.entity_id);
+            }
+
+            app.get_audio().play_sound(DEATH_SOUND);
+        }
+    }
+
+    fn handle_checkpoints(&mut self, app: &mut App) {
+        let checkpoint_events = std::mem::take(&mut *self.m_checkpoint_events.borrow_mut());
+        for event in checkpoint_events {
+            let is_player = app
+                .get_entity_spawner()
+                .get_entity(event.entity_id)
+                .is_some_and(|entity| entity.has_tag("player"));
+
+            if is_player {
+                self.m_respawn_position = event.position;
+                self.m_has_reached_checkpoint = true;
+            }
+        }
+    }
+
+    fn update_respawn(&mut self, app: &mut App, delta_time: f32) {
+        let Some(respawn_time_left) = self.m_respawn_time_left else {
+            return;
+        };
+
+        if respawn_time_left > delta_time {
+            self.m_respawn_time_left = Some(respawn_time_left - delta_time);
+            return;
+        }
+        self.m_respawn_time_left = None;
+
+        if !self.m_has_reached_checkpoint {
+            self.restart_level(app);
+            return;
+        }
+
+        let Some(player) = app.get_entity_spawner().find_by_name_mut(PLAYER_NAME) else {
+            return;
+        };
+
+        if let Some(transform) = player.get_component_mut::<TransformComponent>() {
+            transform.teleport(self.m_respawn_position);
+        }
+        if let Some(health) = player.get_component_mut::<HealthComponent>() {
+            health.revive();
+            health.set_invulnerable(RESPAWN_INVULNERABILITY);
+        }
+    }
+
+    // The level is won by defeating every enemy or by reaching an exit
+    fn check_level_complete(&mut self, app: &mut App) {
+        let level_exit_events = std::mem::take(&mut *self.m_level_exit_events.borrow_mut());
+        if self.m_is_level_complete || self.m_respawn_time_left.is_some() {
+            return;
+        }
+
+        let entity_spawner = app.get_entity_spawner();
+        let has_reached_exit = level_exit_events.iter().any(|event| {
+            entity_spawner
+                .get_entity(event.entity_id)
+                .is_some_and(|entity| entity.has_tag("player"))
+        });
+        let has_defeated_enemies =
+            self.m_enemy_count > 0 && entity_spawner.iter_tagged("enemy").next().is_none();
+
+        if !has_reached_exit && !has_defeated_enemies {
+            return;
+        }
+
+        let Some(player_state) = entity_spawner
+            .find_by_name(PLAYER_NAME)
+            .and_then(get_player_state)
+        else {
+            return;
+        };
+
+        self.m_is_level_complete = true;
+        app.get_event_bus().queue(LevelCompleted {
+            level_index: self.m_level_manager.get_level_index(),
+        });
+        self.m_level_manager.complete_level(player_state);
+        self.load_next_level(app);
+    }
+
+    fn restart_level(&mut self, app: &mut App) {
+        self.m_is_level_complete = true;
+        self.m_level_manager.restart_level();
+        self.load_next_level(app);
+    }
+
+    // Replaces this scene with the level manager's current level
+    fn load_next_level(&mut self, app: &mut App) {
+        let next_level =
+            LevelScene::new(self.m_projectile_pool.clone(), self.m_level_manager.clone());
+        app.replace_scene(
+            Box::new(next_level),
+            SceneTransition::Fade(LEVEL_FADE_DURATION),
+        );
+    }
+
+    // One quick save per level. The entities go into a world state, the scene's own
+    // state into a second file next to it.
+    fn get_quick_save_paths(&self) -> (PathBuf, PathBuf) {
+        let level_index = self.m_level_manager.get_level_index();
+        let save_dir_path = get_save_dir_path();
+        (
+            save_dir_path.join(format!("quick_save_{}.json", level_index)),
+            save_dir_path.join(format!("quick_save_{}_level.json", level_index)),
+        )
+    }
+
+    fn quick_save(&self, app: &App) -> Result<(), String> {
+        let (world_state_path, level_state_path) = self.get_quick_save_paths();
+        app.save_world_state(&world_state_path)?;
+
+        let json = serde_json::to_string_pretty(&json!({
+            "level_index": self.m_level_manager.get_level_index(),
+            "respawn_position": self.m_respawn_position,
+            "has_reached_checkpoint": self.m_has_reached_checkpoint,
+            "enemy_count": self.m_enemy_count,
+        }))
+        .map_err(|err| format!("Failed to serialize level state: {}", err))?;
+
+        std::fs::write(&level_state_path, json)
+            .map_err(|err| format!("Failed to write '{}': {}", level_state_path.display(), err))
+    }
+
+    fn quick_load(&mut self, app: &mut App) -> Result<(), String> {
+        // Too late once the level is being left
+        if self.m_is_level_complete {
+            return Ok(());
+        }
+
+        let (world_state_path, level_state_path) = self.get_quick_save_paths();
+        let json = std::fs::read_to_string(&level_state_path)
+            .map_err(|err| format!("Failed to read '{}': {}", level_state_path.display(), err))?;
+        let value: serde_json::Value = serde_json::from_str(&json)
+            .map_err(|err| format!("Failed to parse '{}': {}", level_state_path.display(), err))?;
+
+        // Entities of another map would stand in this map's tiles
+        let level_index = self.m_level_manager.get_level_index();
+        if value["level_index"].as_u64() != Some(level_index as u64) {
+            return Err(format!(
+                "'{}' is not a save of level {}",
+                level_state_path.display(),
+                level_index
+            ));
+        }
+
+        let respawn_position: Vec2 = serde_json::from_value(value["respawn_position"].clone())
+            .map_err(|err| format!("Invalid 'respawn_position': {}", err))?;
+        let has_reached_checkpoint = value["has_reached_checkpoint"]
+            .as_bool()
+            .ok_or("Missing 'has_reached_checkpoint'")?;
+        let enemy_count = value["enemy_count"]
+            .as_u64()
+            .ok_or("Missing 'enemy_count'")?;
+
+        app.load_world_state(&world_state_path)?;
+
+        self.m_respawn_position = respawn_position;
+        self.m_has_reached_checkpoint = has_reached_checkpoint;
+        self.m_enemy_count = enemy_count as usize;
+        // The restored player is alive, or waits for a respawn that is no longer pending
+        self.m_respawn_time_left = None;
+
+        Ok(())
+    }
+
+    fn add_input_event_handler(&mut self, app: &mut App) {
+        let pressed_actions = self.m_pressed_actions.clone();
+        self.m_input_event_handler_id =
+            app.get_input()
+                .borrow_mut()
+                .add_input_event_handler(move |event| {
+                    if event.ev_type == InputEventType::Pressed {
+                        pressed_actions.borrow_mut().push(event.ev_name.to_string());
+                    }
+                });
+    }
+
+    fn remove_input_event_handler(&mut self, app: &mut App) {
+        app.get_input()
+            .borrow_mut()
+            .remove_input_event_handler(self.m_input_event_handler_id);
+
+        self.m_input_event_handler_id = INVALID_INPUT_EVENT_HANDLER_ID;
+        self.m_pressed_actions.borrow_mut().clear();
+    }
+}
+
+impl Scene for LevelScene {
+    fn on_enter(&mut self, app: &mut App) {
+        if let Err(err) = self.load_background(app) {
+            eprintln!("Failed to load background: {}", err);
+        }
+
+        if let Err(err) = self.load_level(app) {
+            eprintln!("Failed to load level: {}", err);
+        }
+
+        self.subscribe_to_events(app);
+        self.add_input_event_handler(app);
+    }
+
+    fn on_exit(&mut self, app: &mut App) {
+        self.remove_input_event_handler(app);
+        self.unsubscribe_from_events(app);
+        self.m_respawn_time_left = None;
+        self.m_has_reached_checkpoint = false;
+        self.m_enemy_count = 0;
+        app.get_physics_world().clear_tilemap();
+        self.m_projectile_pool.clear();
+        app.get_audio().unload_sounds();
+        app.get_background().clear();
+        app.get_time_dilation().stop();
+        app.get_camera_effects().reset();
+        app.set_camera_position(Vec2::zero());
+    }
+
+    // The pause scene handles "cancel" while it is on top
+    fn on_pause(&mut self, app: &mut App) {
+        self.remove_input_event_handler(app);
+    }
+
+    fn on_resume(&mut self, app: &mut App) {
+        self.add_input_event_handler(app);
+    }
+
+    fn update(&mut self, app: &mut App, delta_time: f32) {
+        self.handle_checkpoints(app);
+        self.handle_hurts(app);
+        self.handle_deaths(app);
+        self.update_respawn(app, delta_time);
+        self.update_camera(app, delta_time);
+        self.check_level_complete(app);
+
+        let pressed_actions = std::mem::take(&mut *self.m_pressed_actions.borrow_mut());
+        for action in pressed_actions {
+            match action.as_str() {
+                "cancel" => {
+                    app.push_scene(Box::new(PauseScene::new()), SceneTransition::Cut);
+                }
+                "quick_save" => {
+                    if let Err(err) = self.quick_save(app) {
+                        eprintln!("Quick save failed: {}", err);
+                    }
+                }
+                "quick_load" => {
+                    if let Err(err) = self.quick_load(app) {
+                        eprintln!("Quick load failed: {}", err);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn get_player_state(player: &Entity) -> Option<PlayerState> {
+    let health = player.get_component::<HealthComponent>()?;
+    if health.is_dead() {
+        return None;
+    }
+
+    Some(PlayerState {
+        health: health.get_health(),
+    })
+}
+
+fn apply_player_state(player: &mut Entity, player_state: PlayerState) {
+    if let Some(health) = player.get_component_mut::<HealthComponent>() {
+        health.set_health(player_state.health);
+    }
+}
+
+// Middle of the entity's collider, if it has one
+fn get_entity_center(app: &mut App, entity_id: EntityId) -> Option<Vec2> {
+    app.get_entity_spawner()
+        .get_entity(entity_id)?
+        .get_component::<ColliderComponent>()
+        .map(|collider| collider.get_bounds().center())
+}
+
+fn burst_effects(app: &mut App, position: Vec2, count: usize) {
+    if let Some(emitter) = app
+        .get_entity_spawner()
+        .find_by_name_mut(effects::EFFECTS_NAME)
+        .and_then(|entity| entity.get_component_mut::<ParticleEmitterComponent>())
+    {
+        emitter.burst_at(position, count);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod level_scene;
+pub mod pause_scene;
+
+// Re-exports
+pub use level_scene::*;
+pub use pause_scene::*;
@@ -0,0 +1,46 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use engine::core::app::App;
+use engine::core::input::{INVALID_INPUT_EVENT_HANDLER_ID, InputEventHandlerId, InputEventType};
+use engine::core::scene::{Scene, SceneTransition};
+
+pub struct PauseScene {
+    m_resume_requested: Rc<Cell<bool>>,
+    m_input_event_handler_id: InputEventHandlerId,
+}
+
+impl PauseScene {
+    pub fn new() -> Self {
+        Self {
+            m_resume_requested: Rc::new(Cell::new(false)),
+            m_input_event_handler_id: INVALID_INPUT_EVENT_HANDLER_ID,
+        }
+    }
+}
+
+impl Scene for PauseScene {
+    fn on_enter(&mut self, app: &mut App) {
+        let resume_requested = self.m_resume_requested.clone();
+        self.m_input_event_handler_id =
+            app.get_input()
+                .borrow_mut()
+                .add_input_event_handler(move |event| {
+                    if event.ev_name == "cancel" && event.ev_type == InputEventType::Pressed {
+                        resume_requested.set(true);
+                    }
+                });
+    }
+
+    fn on_exit(&mut self, app: &mut App) {
+        app.get_input()
+            .borrow_mut()
+            .remove_input_event_handler(self.m_input_event_handler_id);
+    }
+
+    fn update(&mut self, app: &mut App, _delta_time: f32) {
+        if self.m_resume_requested.replace(false) {
+            app.pop_scene(SceneTransition::Cut);
+        }
+    }
+}
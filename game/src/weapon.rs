@@ -0,0 +1,239 @@
+use std::rc::Rc;
+
+use engine::components::{
+    Component, ComponentBase, TransformComponent, component, component_priority,
+};
+use engine::core::assets::{INVALID_TEXTURE_ID, TextureId};
+use engine::core::audio::Audio;
+use engine::core::render::{RenderData, RenderQueue};
+use engine::math::Vec2;
+use engine::physics::LayerMask;
+
+use crate::projectile::{ProjectilePool, ProjectilePrefab, Shot};
+
+pub const SHOOT_SOUND: &str = "sfx/shoot.wav";
+
+// A gun held by its owner entity. Fires projectiles from the pool and draws itself over
+// the owner's sprite.
+//
+// Offsets are relative to the owner's position when facing right. Facing left mirrors them
+// around the owner's mirror axis, so the gun ends up in the other hand.
+#[component(
+    priority = component_priority::RENDER + 1,
+    requires(TransformComponent),
+    serialize,
+    manual_register
+)]
+pub struct WeaponComponent {
+    m_pool: Rc<ProjectilePool>,
+    m_audio: Rc<Audio>,
+    #[save(texture)]
+    m_texture_id: TextureId,
+    #[save]
+    m_size: Vec2,
+    // Top-left of the gun sprite
+    #[save]
+    m_offset: Vec2,
+    // Where shots leave the gun, relative to the gun sprite
+    #[save]
+    m_muzzle_offset: Vec2,
+    #[save]
+    m_mirror_x: f32,
+    // Shots per second
+    #[save]
+    #[reflect]
+    m_fire_rate: f32,
+    // Shots deviate up to half of this many degrees to either side
+    #[save]
+    #[reflect]
+    m_spread: f32,
+    #[save(texture)]
+    m_projectile_texture_id: TextureId,
+    #[save]
+    #[reflect]
+    m_projectile_speed: f32,
+    #[save]
+    #[reflect]
+    m_projectile_lifetime: f32,
+    #[save]
+    #[reflect]
+    m_projectile_damage: i32,
+    #[save]
+    #[reflect]
+    m_projectile_knockback: f32,
+    #[save]
+    m_projectile_hit_mask: LayerMask,
+    #[save]
+    m_is_facing_left: bool,
+    #[save]
+    m_cooldown: f32,
+}
+
+impl Component for WeaponComponent {
+    fn physics_tick(&mut self, fixed_delta_time: f32) {
+        self.m_cooldown = (self.m_cooldown - fixed_delta_time).max(0.0);
+    }
+
+    fn render_tick(&mut self, _delta_time: f32, render_queue: &mut RenderQueue) {
+        if self.m_texture_id == INVALID_TEXTURE_ID {
+            return;
+        }
+
+        let transform = self
+            .get_entity()
+            .get_component::<TransformComponent>()
+            .unwrap();
+
+        let offset = self.get_gun_offset();
+        render_queue.enqueue(
+            RenderData::new(
+                self.m_texture_id,
+                transform.get_position() + offset,
+                transform.get_prev_position() + offset,
+                transform.get_scale(),
+            )
+            .with_flip_x(self.m_is_facing_left),
+        );
+    }
+}
+
+#[component]
+impl WeaponComponent {
+    pub fn new(pool: Rc<ProjectilePool>, audio: Rc<Audio>) -> Self {
+        Self {
+            m_pool: pool,
+            m_audio: audio,
+            m_texture_id: INVALID_TEXTURE_ID,
+            m_size: Vec2::zero(),
+            m_offset: Vec2::zero(),
+            m_muzzle_offset: Vec2::zero(),
+            m_mirror_x: 0.0,
+            m_fire_rate: 1.0,
+            m_spread: 0.0,
+            m_projectile_texture_id: INVALID_TEXTURE_ID,
+            m_projectile_speed: 150.0,
+            m_projectile_lifetime: 2.0,
+            m_projectile_damage: 1,
+            m_projectile_knockback: 0.0,
+            m_projectile_hit_mask: 0,
+            m_is_facing_left: false,
+            m_cooldown: 0.0,
+        }
+    }
+
+    pub fn with_sprite(mut self, texture_id: TextureId, size: Vec2) -> Self {
+        self.m_texture_id = texture_id;
+        self.m_size = size;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: Vec2, mirror_x: f32) -> Self {
+        self.m_offset = offset;
+        self.m_mirror_x = mirror_x;
+        self
+    }
+
+    pub fn with_muzzle_offset(mut self, muzzle_offset: Vec2) -> Self {
+        self.m_muzzle_offset = muzzle_offset;
+        self
+    }
+
+    pub fn with_fire_rate(mut self, fire_rate: f32) -> Self {
+        self.m_fire_rate = fire_rate;
+        self
+    }
+
+    pub fn with_spread(mut self, spread: f32) -> Self {
+        self.m_spread = spread;
+        self
+    }
+
+    pub fn with_projectile(mut self, prefab: ProjectilePrefab) -> Self {
+        self.set_projectile(prefab);
+        self
+    }
+
+    pub fn get_projectile(&self) -> ProjectilePrefab {
+        ProjectilePrefab {
+            texture_id: self.m_projectile_texture_id,
+            speed: self.m_projectile_speed,
+            lifetime: self.m_projectile_lifetime,
+            damage: self.m_projectile_damage,
+            knockback: self.m_projectile_knockback,
+            hit_mask: self.m_projectile_hit_mask,
+        }
+    }
+
+    pub fn set_projectile(&mut self, prefab: ProjectilePrefab) {
+        self.m_projectile_texture_id = prefab.texture_id;
+        self.m_projectile_speed = prefab.speed;
+        self.m_projectile_lifetime = prefab.lifetime;
+        self.m_projectile_damage = prefab.damage;
+        self.m_projectile_knockback = prefab.knockback;
+        self.m_projectile_hit_mask = prefab.hit_mask;
+    }
+
+    pub fn set_facing_left(&mut self, is_facing_left: bool) {
+        self.m_is_facing_left = is_facing_left;
+    }
+
+    pub fn can_fire(&self) -> bool {
+        self.m_cooldown == 0.0 && self.m_fire_rate > 0.0
+    }
+
+    // Fires in the facing direction unless the weapon is cooling down.
+    // Returns whether a shot was fired.
+    pub fn fire(&mut self) -> bool {
+        if !self.can_fire() {
+            return false;
+        }
+
+        let Some(position) = self
+            .get_entity()
+            .get_component::<TransformComponent>()
+            .map(|transform| transform.get_position())
+        else {
+            return false;
+        };
+
+        let forward = if self.m_is_facing_left {
+            Vec2::left()
+        } else {
+            Vec2::right()
+        };
+        let deviation = (fastrand::f32() - 0.5) * self.m_spread.to_radians();
+
+        let shot = Shot {
+            prefab: self.get_projectile(),
+            position: position + self.get_muzzle_offset(),
+            direction: forward.rotate(deviation),
+            owner_id: self.get_entity().get_id(),
+        };
+        if !self.m_pool.fire(shot) {
+            return false;
+        }
+
+        self.m_cooldown = 1.0 / self.m_fire_rate;
+        self.m_audio.play_sound(SHOOT_SOUND);
+        true
+    }
+
+    fn get_gun_offset(&self) -> Vec2 {
+        if self.m_is_facing_left {
+            let x = 2.0 * self.m_mirror_x - self.m_offset.x - self.m_size.x;
+            Vec2::from_xy(x, self.m_offset.y)
+        } else {
+            self.m_offset
+        }
+    }
+
+    fn get_muzzle_offset(&self) -> Vec2 {
+        let muzzle_x = if self.m_is_facing_left {
+            self.m_size.x - self.m_muzzle_offset.x
+        } else {
+            self.m_muzzle_offset.x
+        };
+
+        self.get_gun_offset() + Vec2::from_xy(muzzle_x, self.m_muzzle_offset.y)
+    }
+}